@@ -0,0 +1,37 @@
+use connx::encoding::binary::{BigEndian, ByteOrder, LittleEndian};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const LEN: usize = 1 << 20;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let bytes: Vec<u8> = (0..LEN * 4).map(|i| i as u8).collect();
+    let mut values = vec![0u32; LEN];
+
+    c.bench_function("binary decode_u32 loop 1M", |b| {
+        b.iter(|| {
+            for (n, chunk) in values.iter_mut().zip(bytes.chunks_exact(4)) {
+                *n = BigEndian::decode_u32(chunk);
+            }
+            black_box(&values);
+        })
+    });
+    c.bench_function("binary read_u32_into big 1M", |b| {
+        b.iter(|| BigEndian::read_u32_into(black_box(&bytes), &mut values))
+    });
+    c.bench_function("binary read_u32_into little 1M", |b| {
+        b.iter(|| LittleEndian::read_u32_into(black_box(&bytes), &mut values))
+    });
+
+    let mut out = vec![0u8; LEN * 4];
+    c.bench_function("binary write_u32_from_slice big 1M", |b| {
+        b.iter(|| BigEndian::write_u32_from_slice(black_box(&values), &mut out))
+    });
+
+    let mut floats: Vec<f64> = (0..LEN).map(|i| i as f64).collect();
+    c.bench_function("binary from_slice_f64 big 1M", |b| {
+        b.iter(|| BigEndian::from_slice_f64(black_box(&mut floats)))
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,128 @@
+/* Generated by cbindgen from capi/src/lib.rs, do not edit. */
+
+#ifndef CONNX_H
+#define CONNX_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Result of the encode and decode functions
+typedef enum ConnxStatus {
+  CONNX_STATUS_OK = 0,
+  // Byte outside of the alphabet or misplaced padding
+  CONNX_STATUS_INVALID_BYTE = 1,
+  // Input length impossible for the encoding
+  CONNX_STATUS_INVALID_LENGTH = 2,
+  // Output buffer shorter than `*_encode_len`/`*_decode_len`
+  CONNX_STATUS_BUFFER_TOO_SMALL = 3,
+  // NULL pointer with a non-zero length
+  CONNX_STATUS_NULL_POINTER = 4,
+} ConnxStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Length of the hex encoding of `n` bytes
+size_t connx_base16_encode_len(size_t n);
+
+// Maximum length of the data decoded from `n` hex characters
+size_t connx_base16_decode_len(size_t n);
+
+// Encode to lowercase hex, no NUL terminator
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` NULL or valid.
+enum ConnxStatus connx_base16_encode(const uint8_t *src,
+                                     size_t src_len,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written);
+
+// Decode hex of either case
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` and `error_offset` NULL or valid.
+enum ConnxStatus connx_base16_decode(const uint8_t *src,
+                                     size_t src_len,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written,
+                                     size_t *error_offset);
+
+// Length of the padded base32 encoding of `n` bytes
+size_t connx_base32_encode_len(size_t n);
+
+// Maximum length of the data decoded from `n` base32 characters
+size_t connx_base32_decode_len(size_t n);
+
+// Encode to padded base32, the RFC4648 "base32hex" alphabet if `hex`
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` NULL or valid.
+enum ConnxStatus connx_base32_encode(const uint8_t *src,
+                                     size_t src_len,
+                                     bool hex,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written);
+
+// Decode padded base32, the RFC4648 "base32hex" alphabet if `hex`
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` and `error_offset` NULL or valid.
+enum ConnxStatus connx_base32_decode(const uint8_t *src,
+                                     size_t src_len,
+                                     bool hex,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written,
+                                     size_t *error_offset);
+
+// Length of the padded base64 encoding of `n` bytes
+size_t connx_base64_encode_len(size_t n);
+
+// Maximum length of the data decoded from `n` base64 characters
+size_t connx_base64_decode_len(size_t n);
+
+// Encode to padded base64, the URL and filename safe alphabet if `url`
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` NULL or valid.
+enum ConnxStatus connx_base64_encode(const uint8_t *src,
+                                     size_t src_len,
+                                     bool url,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written);
+
+// Decode padded base64, the URL and filename safe alphabet if `url`
+//
+// # Safety
+//
+// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+// `written` and `error_offset` NULL or valid.
+enum ConnxStatus connx_base64_decode(const uint8_t *src,
+                                     size_t src_len,
+                                     bool url,
+                                     uint8_t *dst,
+                                     size_t dst_len,
+                                     size_t *written,
+                                     size_t *error_offset);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* CONNX_H */
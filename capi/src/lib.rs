@@ -0,0 +1,499 @@
+//! C ABI of `connx::encoding::{base16, base32, base64}`.
+//!
+//! Built as `libconnx.so`/`libconnx.a` with the header `include/connx.h`,
+//! regenerated after changes with `cbindgen --config cbindgen.toml --output
+//! include/connx.h` in this directory. The functions are the Rust codecs
+//! themselves, so C, C++ and Python callers get byte-identical encodings.
+//!
+//! Every encode and decode function writes into a caller buffer of
+//! `*_encode_len`/`*_decode_len` bytes and returns a [`ConnxStatus`]. The
+//! `written` and `error_offset` out-params may be NULL, input and output
+//! pointers may be NULL only when their length is 0.
+
+use connx::encoding::{base16, base32, base64};
+use std::slice;
+
+/// Result of the encode and decode functions
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnxStatus {
+    Ok = 0,
+    /// Byte outside of the alphabet or misplaced padding
+    InvalidByte = 1,
+    /// Input length impossible for the encoding
+    InvalidLength = 2,
+    /// Output buffer shorter than `*_encode_len`/`*_decode_len`
+    BufferTooSmall = 3,
+    /// NULL pointer with a non-zero length
+    NullPointer = 4,
+}
+
+/// Decode error, status and offset of the first rejected byte
+type DecodeError = (ConnxStatus, usize);
+
+/// One encoding with its alphabet, as the C functions select it
+trait Codec {
+    fn encode_len(&self, n: usize) -> usize;
+    fn decode_len(&self, n: usize) -> usize;
+    fn encode(&self, dst: &mut [u8], src: &[u8]);
+    fn decode(&self, dst: &mut [u8], src: &[u8]) -> Result<usize, DecodeError>;
+}
+
+struct Base16;
+
+impl Codec for Base16 {
+    fn encode_len(&self, n: usize) -> usize {
+        base16::encode_len(n)
+    }
+
+    fn decode_len(&self, n: usize) -> usize {
+        base16::decode_len(n)
+    }
+
+    fn encode(&self, dst: &mut [u8], src: &[u8]) {
+        base16::encode(dst, src);
+    }
+
+    fn decode(&self, dst: &mut [u8], src: &[u8]) -> Result<usize, DecodeError> {
+        if let Some(i) = src.iter().position(|b| !b.is_ascii_hexdigit()) {
+            return Err((ConnxStatus::InvalidByte, i));
+        }
+        base16::decode(dst, src).map_err(|_| (ConnxStatus::InvalidLength, src.len()))
+    }
+}
+
+struct Base32 {
+    hex: bool,
+}
+
+impl Base32 {
+    fn maps(&self) -> (&'static [u8], &'static [u8]) {
+        if self.hex {
+            (base32::ENCODE_HEX, base32::DECODE_HEX_MAP)
+        } else {
+            (base32::ENCODE_STD, base32::DECODE_STD_MAP)
+        }
+    }
+}
+
+impl Codec for Base32 {
+    fn encode_len(&self, n: usize) -> usize {
+        base32::encode_len(n)
+    }
+
+    fn decode_len(&self, n: usize) -> usize {
+        base32::decode_len(n)
+    }
+
+    fn encode(&self, dst: &mut [u8], src: &[u8]) {
+        base32::encode(dst, src, self.maps().0);
+    }
+
+    fn decode(&self, dst: &mut [u8], src: &[u8]) -> Result<usize, DecodeError> {
+        let decode_map = self.maps().1;
+        check_symbols(src, decode_map, base32::PAD_CHAR, &[0, 1, 3, 4, 6])?;
+        base32::decode(dst, src, decode_map).map_err(|_| (ConnxStatus::InvalidLength, src.len()))
+    }
+}
+
+struct Base64 {
+    url: bool,
+}
+
+impl Base64 {
+    fn maps(&self) -> (&'static [u8], &'static [u8]) {
+        if self.url {
+            (base64::ENCODE_URL, base64::DECODE_URL_MAP)
+        } else {
+            (base64::ENCODE_STD, base64::DECODE_STD_MAP)
+        }
+    }
+}
+
+impl Codec for Base64 {
+    fn encode_len(&self, n: usize) -> usize {
+        base64::encode_len(n)
+    }
+
+    fn decode_len(&self, n: usize) -> usize {
+        base64::decode_len(n)
+    }
+
+    fn encode(&self, dst: &mut [u8], src: &[u8]) {
+        base64::encode(dst, src, self.maps().0);
+    }
+
+    fn decode(&self, dst: &mut [u8], src: &[u8]) -> Result<usize, DecodeError> {
+        let decode_map = self.maps().1;
+        check_symbols(src, decode_map, base64::PAD_CHAR, &[0, 1, 2])?;
+        base64::decode(dst, src, decode_map).map_err(|_| (ConnxStatus::InvalidLength, src.len()))
+    }
+}
+
+/// Check the alphabet and the padding before decoding, the decoders ignore
+/// what follows the padding. Returns the offset of the first bad byte.
+///
+/// `pad_lens` lists the valid numbers of padding bytes.
+fn check_symbols(
+    src: &[u8],
+    decode_map: &[u8],
+    pad: u8,
+    pad_lens: &[usize],
+) -> Result<(), DecodeError> {
+    let digits = src
+        .iter()
+        .take_while(|&&b| decode_map[b as usize] != 0xFF)
+        .count();
+    let rest = &src[digits..];
+    let max_pad = pad_lens.iter().copied().max().unwrap_or(0);
+    let offset = match rest.iter().position(|&b| b != pad) {
+        Some(i) if i > 0 && rest.len() <= max_pad => digits + i,
+        Some(_) => digits,
+        None if !pad_lens.contains(&rest.len()) => digits,
+        None => return Ok(()),
+    };
+    Err((ConnxStatus::InvalidByte, offset))
+}
+
+unsafe fn input<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+unsafe fn output<'a>(ptr: *mut u8, len: usize) -> Option<&'a mut [u8]> {
+    if len == 0 {
+        Some(&mut [])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts_mut(ptr, len))
+    }
+}
+
+unsafe fn set(out: *mut usize, value: usize) {
+    if !out.is_null() {
+        *out = value;
+    }
+}
+
+unsafe fn encode_with(
+    codec: &impl Codec,
+    src: *const u8,
+    src_len: usize,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+) -> ConnxStatus {
+    set(written, 0);
+    let (Some(src), Some(dst)) = (input(src, src_len), output(dst, dst_len)) else {
+        return ConnxStatus::NullPointer;
+    };
+    let len = codec.encode_len(src.len());
+    if dst.len() < len {
+        return ConnxStatus::BufferTooSmall;
+    }
+    codec.encode(&mut dst[..len], src);
+    set(written, len);
+    ConnxStatus::Ok
+}
+
+unsafe fn decode_with(
+    codec: &impl Codec,
+    src: *const u8,
+    src_len: usize,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+    error_offset: *mut usize,
+) -> ConnxStatus {
+    set(written, 0);
+    set(error_offset, 0);
+    let (Some(src), Some(dst)) = (input(src, src_len), output(dst, dst_len)) else {
+        return ConnxStatus::NullPointer;
+    };
+    if src.is_empty() {
+        return ConnxStatus::Ok;
+    }
+    if dst.len() < codec.decode_len(src.len()) {
+        return ConnxStatus::BufferTooSmall;
+    }
+    match codec.decode(dst, src) {
+        Ok(n) => {
+            set(written, n);
+            ConnxStatus::Ok
+        }
+        Err((status, offset)) => {
+            set(error_offset, offset);
+            status
+        }
+    }
+}
+
+/// Length of the hex encoding of `n` bytes
+#[no_mangle]
+pub extern "C" fn connx_base16_encode_len(n: usize) -> usize {
+    base16::encode_len(n)
+}
+
+/// Maximum length of the data decoded from `n` hex characters
+#[no_mangle]
+pub extern "C" fn connx_base16_decode_len(n: usize) -> usize {
+    base16::decode_len(n)
+}
+
+/// Encode to lowercase hex, no NUL terminator
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base16_encode(
+    src: *const u8,
+    src_len: usize,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+) -> ConnxStatus {
+    encode_with(&Base16, src, src_len, dst, dst_len, written)
+}
+
+/// Decode hex of either case
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` and `error_offset` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base16_decode(
+    src: *const u8,
+    src_len: usize,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+    error_offset: *mut usize,
+) -> ConnxStatus {
+    decode_with(&Base16, src, src_len, dst, dst_len, written, error_offset)
+}
+
+/// Length of the padded base32 encoding of `n` bytes
+#[no_mangle]
+pub extern "C" fn connx_base32_encode_len(n: usize) -> usize {
+    base32::encode_len(n)
+}
+
+/// Maximum length of the data decoded from `n` base32 characters
+#[no_mangle]
+pub extern "C" fn connx_base32_decode_len(n: usize) -> usize {
+    base32::decode_len(n)
+}
+
+/// Encode to padded base32, the RFC4648 "base32hex" alphabet if `hex`
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base32_encode(
+    src: *const u8,
+    src_len: usize,
+    hex: bool,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+) -> ConnxStatus {
+    encode_with(&Base32 { hex }, src, src_len, dst, dst_len, written)
+}
+
+/// Decode padded base32, the RFC4648 "base32hex" alphabet if `hex`
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` and `error_offset` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base32_decode(
+    src: *const u8,
+    src_len: usize,
+    hex: bool,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+    error_offset: *mut usize,
+) -> ConnxStatus {
+    let codec = Base32 { hex };
+    decode_with(&codec, src, src_len, dst, dst_len, written, error_offset)
+}
+
+/// Length of the padded base64 encoding of `n` bytes
+#[no_mangle]
+pub extern "C" fn connx_base64_encode_len(n: usize) -> usize {
+    base64::encode_len(n)
+}
+
+/// Maximum length of the data decoded from `n` base64 characters
+#[no_mangle]
+pub extern "C" fn connx_base64_decode_len(n: usize) -> usize {
+    base64::decode_len(n)
+}
+
+/// Encode to padded base64, the URL and filename safe alphabet if `url`
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base64_encode(
+    src: *const u8,
+    src_len: usize,
+    url: bool,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+) -> ConnxStatus {
+    encode_with(&Base64 { url }, src, src_len, dst, dst_len, written)
+}
+
+/// Decode padded base64, the URL and filename safe alphabet if `url`
+///
+/// # Safety
+///
+/// `src` must be valid for `src_len` bytes, `dst` for `dst_len` bytes,
+/// `written` and `error_offset` NULL or valid.
+#[no_mangle]
+pub unsafe extern "C" fn connx_base64_decode(
+    src: *const u8,
+    src_len: usize,
+    url: bool,
+    dst: *mut u8,
+    dst_len: usize,
+    written: *mut usize,
+    error_offset: *mut usize,
+) -> ConnxStatus {
+    let codec = Base64 { url };
+    decode_with(&codec, src, src_len, dst, dst_len, written, error_offset)
+}
+
+#[cfg(test)]
+mod tests_capi {
+    use super::*;
+    use std::ptr;
+
+    /// Call an encode or decode function on a buffer of `dst_len` bytes
+    fn call(
+        dst_len: usize,
+        f: impl FnOnce(*mut u8, usize, &mut usize, &mut usize) -> ConnxStatus,
+    ) -> Result<Vec<u8>, DecodeError> {
+        let mut dst = vec![0; dst_len];
+        let (mut written, mut offset) = (usize::MAX, usize::MAX);
+        match f(dst.as_mut_ptr(), dst_len, &mut written, &mut offset) {
+            ConnxStatus::Ok => Ok(dst[..written].to_vec()),
+            status => Err((status, offset)),
+        }
+    }
+
+    fn base64_decode(src: &[u8]) -> Result<Vec<u8>, DecodeError> {
+        call(
+            connx_base64_decode_len(src.len()),
+            |dst, len, w, o| unsafe {
+                connx_base64_decode(src.as_ptr(), src.len(), false, dst, len, w, o)
+            },
+        )
+    }
+
+    #[test]
+    fn test_base16() {
+        let encoded = call(connx_base16_encode_len(5), |dst, len, w, _| unsafe {
+            connx_base16_encode(b"hello".as_ptr(), 5, dst, len, w)
+        });
+        assert_eq!(encoded, Ok(b"68656c6c6f".to_vec()));
+
+        let decode = |src: &[u8]| {
+            call(
+                connx_base16_decode_len(src.len()),
+                |dst, len, w, o| unsafe {
+                    connx_base16_decode(src.as_ptr(), src.len(), dst, len, w, o)
+                },
+            )
+        };
+        assert_eq!(decode(b"68656C6c"), Ok(b"hell".to_vec()));
+        assert_eq!(decode(b"68656C6c6g"), Err((ConnxStatus::InvalidByte, 9)));
+        assert_eq!(decode(b"68656C6c6"), Err((ConnxStatus::InvalidLength, 9)));
+    }
+
+    #[test]
+    fn test_base32() {
+        let encoded = call(8, |dst, len, w, _| unsafe {
+            connx_base32_encode(b"hello".as_ptr(), 5, true, dst, len, w)
+        });
+        assert_eq!(encoded, Ok(b"D1IMOR3F".to_vec()));
+
+        let decode = |src: &[u8]| {
+            call(
+                connx_base32_decode_len(src.len()),
+                |dst, len, w, o| unsafe {
+                    connx_base32_decode(src.as_ptr(), src.len(), false, dst, len, w, o)
+                },
+            )
+        };
+        assert_eq!(decode(b"MY======"), Ok(b"f".to_vec()));
+        assert_eq!(decode(b"MY0====="), Err((ConnxStatus::InvalidByte, 2)));
+        assert_eq!(decode(b"MY=====M"), Err((ConnxStatus::InvalidByte, 7)));
+        assert_eq!(decode(b"MY"), Err((ConnxStatus::InvalidLength, 2)));
+    }
+
+    #[test]
+    fn test_base64() {
+        let encoded = call(connx_base64_encode_len(2), |dst, len, w, _| unsafe {
+            connx_base64_encode([0xfb, 0xff].as_ptr(), 2, true, dst, len, w)
+        });
+        assert_eq!(encoded, Ok(b"-_8=".to_vec()));
+
+        assert_eq!(base64_decode(b"aGVsbG8="), Ok(b"hello".to_vec()));
+        assert_eq!(base64_decode(b""), Ok(Vec::new()));
+        assert_eq!(
+            base64_decode(b"aGVs!G8="),
+            Err((ConnxStatus::InvalidByte, 4))
+        );
+        assert_eq!(
+            base64_decode(b"aG=sbG8="),
+            Err((ConnxStatus::InvalidByte, 2))
+        );
+        assert_eq!(
+            base64_decode(b"aGVsb==="),
+            Err((ConnxStatus::InvalidByte, 5))
+        );
+        assert_eq!(
+            base64_decode(b"aGVsbG=x"),
+            Err((ConnxStatus::InvalidByte, 7))
+        );
+        assert_eq!(
+            base64_decode(b"aGVsbG8"),
+            Err((ConnxStatus::InvalidLength, 7))
+        );
+    }
+
+    #[test]
+    fn test_buffers() {
+        let hello = b"hello".as_ptr();
+        let encoded = call(7, |dst, len, w, _| unsafe {
+            connx_base64_encode(hello, 5, false, dst, len, w)
+        });
+        assert_eq!(encoded, Err((ConnxStatus::BufferTooSmall, usize::MAX)));
+        let status = unsafe {
+            connx_base64_encode(ptr::null(), 5, false, ptr::null_mut(), 0, ptr::null_mut())
+        };
+        assert_eq!(status, ConnxStatus::NullPointer);
+        let mut written = 1;
+        let status =
+            unsafe { connx_base64_encode(ptr::null(), 0, false, ptr::null_mut(), 0, &mut written) };
+        assert_eq!((status, written), (ConnxStatus::Ok, 0));
+    }
+}
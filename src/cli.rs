@@ -0,0 +1,392 @@
+//! Commands of the `connx` binary.
+//!
+//! Every command reads a file, or standard input when the file is missing or
+//! `-`, and streams the result to standard output, so large inputs work in
+//! constant memory. Base58 is the exception: its digits depend on the whole
+//! input, which is read into memory first.
+
+mod b58;
+mod base32;
+mod base64;
+mod hex;
+
+use std::fs::File;
+use std::io::{self, Read, Write};
+
+pub const USAGE: &str = "\
+usage: connx <command> [options] [FILE]
+
+commands:
+  base64 [-d] [--url] [--no-pad] [-w COLS]   base64 encode or decode
+  base32 [-d] [--hex] [-w COLS]              base32 encode or decode
+  hex [--dump | --reverse] [-w COLS]         hex encode, dump or decode
+  b58 [-d]                                   base58 (Bitcoin) encode or decode
+
+With no FILE, or when FILE is -, read standard input. Encoded output is
+wrapped at COLS characters (default 76, 0 disables wrapping) and ends with
+a newline. Decoding ignores white space and accepts missing padding.
+";
+
+/// Default line length of encoded output, the one of GNU base64
+const WRAP_COLS: usize = 76;
+
+/// Errors of a command
+#[derive(Debug)]
+pub enum CliError {
+    /// Bad command line, with the reason
+    Usage(String),
+    Io(io::Error),
+    /// Input not valid for the decoder, with the offset of the first bad byte
+    InvalidInput {
+        offset: u64,
+    },
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Usage(msg) => f.write_str(msg),
+            Self::Io(e) => e.fmt(f),
+            Self::InvalidInput { offset } => write!(f, "invalid input at offset {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<io::Error> for CliError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Run the command named by the first argument
+///
+/// - @param args: command line arguments, without the program name
+/// - @param stdin: input when no file is given
+/// - @param stdout: output
+pub fn run(args: &[String], stdin: &mut dyn Read, stdout: &mut dyn Write) -> Result<(), CliError> {
+    let (command, args) = args
+        .split_first()
+        .ok_or_else(|| CliError::Usage("missing command".to_string()))?;
+    let result = match command.as_str() {
+        "base64" => base64::run(args, stdin, stdout),
+        "base32" => base32::run(args, stdin, stdout),
+        "hex" => hex::run(args, stdin, stdout),
+        "b58" => b58::run(args, stdin, stdout),
+        "-h" | "--help" => {
+            stdout.write_all(USAGE.as_bytes())?;
+            Ok(())
+        }
+        _ => Err(CliError::Usage(format!("unknown command: {}", command))),
+    };
+    result.and_then(|()| Ok(stdout.flush()?))
+}
+
+/// Options shared by the commands, collected while each command matches
+/// its own flags
+#[derive(Default)]
+struct CommonArgs {
+    file: Option<String>,
+    wrap: Option<usize>,
+}
+
+impl CommonArgs {
+    /// Take a positional argument or a shared option, `iter` yields the
+    /// option's value
+    fn parse<'a>(
+        &mut self,
+        arg: &'a str,
+        iter: &mut impl Iterator<Item = &'a String>,
+    ) -> Result<(), CliError> {
+        let cols = match arg {
+            "-w" | "--wrap" => Some(
+                iter.next()
+                    .ok_or_else(|| CliError::Usage(format!("{} needs a value", arg)))?
+                    .as_str(),
+            ),
+            _ => arg.strip_prefix("--wrap="),
+        };
+        if let Some(cols) = cols {
+            let cols = cols
+                .parse()
+                .map_err(|_| CliError::Usage(format!("invalid wrap columns: {}", cols)))?;
+            self.wrap = Some(cols);
+        } else if arg.starts_with('-') && arg != "-" {
+            return Err(CliError::Usage(format!("unknown option: {}", arg)));
+        } else if self.file.is_some() {
+            return Err(CliError::Usage(format!("extra operand: {}", arg)));
+        } else {
+            self.file = Some(arg.to_string());
+        }
+        Ok(())
+    }
+
+    fn wrap(&self) -> usize {
+        self.wrap.unwrap_or(WRAP_COLS)
+    }
+
+    /// Open the input file, standard input if there is none
+    fn input<'a>(&self, stdin: &'a mut dyn Read) -> Result<Box<dyn Read + 'a>, CliError> {
+        match self.file.as_deref() {
+            None | Some("-") => Ok(Box::new(stdin)),
+            Some(path) => File::open(path)
+                .map(|f| Box::new(f) as Box<dyn Read>)
+                .map_err(|e| CliError::Io(io::Error::new(e.kind(), format!("{}: {}", path, e)))),
+        }
+    }
+}
+
+/// Read until `buf` is full or the input ends
+fn read_full(input: &mut dyn Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut n = 0;
+    while n < buf.len() {
+        match input.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(k) => n += k,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(n)
+}
+
+/// Writer breaking the output into lines of `cols` characters
+struct Wrap<'a> {
+    out: &'a mut dyn Write,
+    cols: usize,
+    col: usize,
+    empty: bool,
+}
+
+impl<'a> Wrap<'a> {
+    /// - @param cols: line length, 0 for a single line
+    fn new(out: &'a mut dyn Write, cols: usize) -> Self {
+        Self {
+            out,
+            cols,
+            col: 0,
+            empty: true,
+        }
+    }
+
+    fn write(&mut self, mut data: &[u8]) -> io::Result<()> {
+        self.empty &= data.is_empty();
+        if self.cols == 0 {
+            return self.out.write_all(data);
+        }
+        while !data.is_empty() {
+            let n = data.len().min(self.cols - self.col);
+            self.out.write_all(&data[..n])?;
+            data = &data[n..];
+            self.col += n;
+            if self.col == self.cols {
+                self.out.write_all(b"\n")?;
+                self.col = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// End the last line, if anything was written
+    fn finish(self) -> io::Result<()> {
+        if !self.empty && (self.cols == 0 || self.col > 0) {
+            self.out.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Encode a stream in blocks
+///
+/// - @param block: input bytes per encoded group, every call of `encode` but
+///   the last gets a multiple of it
+/// - @param encode: append the encoding of the bytes to the vector
+fn encode_stream(
+    input: &mut dyn Read,
+    out: &mut Wrap,
+    block: usize,
+    mut encode: impl FnMut(&[u8], &mut Vec<u8>),
+) -> Result<(), CliError> {
+    let mut buf = vec![0; block * 8192];
+    let mut dst = Vec::new();
+    loop {
+        let n = read_full(input, &mut buf)?;
+        dst.clear();
+        encode(&buf[..n], &mut dst);
+        out.write(&dst)?;
+        if n < buf.len() {
+            return Ok(());
+        }
+    }
+}
+
+/// Decoder of fixed size groups of characters, such as the 4 characters of
+/// base64
+trait GroupCodec {
+    /// Characters per group, at most 8
+    const GROUP_LEN: usize;
+    const PAD: Option<u8>;
+
+    /// Whether the byte is a digit of the alphabet
+    fn is_symbol(&self, b: u8) -> bool;
+
+    /// Whether a final group may hold this many digits
+    fn valid_len(&self, n: usize) -> bool;
+
+    /// Decode a full group, padded if needed, digits and padding validated
+    fn decode(&self, dst: &mut [u8], group: &[u8]) -> Option<usize>;
+}
+
+/// Check the padding of a full group, returns the number of digits
+fn check_group<C: GroupCodec>(codec: &C, group: &[u8], offsets: &[u64]) -> Result<usize, CliError> {
+    let digits = group.iter().take_while(|&&b| codec.is_symbol(b)).count();
+    if let Some(i) = (digits..group.len()).find(|&i| Some(group[i]) != C::PAD) {
+        return Err(CliError::InvalidInput { offset: offsets[i] });
+    }
+    if digits < group.len() && !codec.valid_len(digits) {
+        return Err(CliError::InvalidInput {
+            offset: offsets[digits],
+        });
+    }
+    Ok(digits)
+}
+
+/// Decode a stream group by group, skipping white space
+///
+/// A padded group ends the data, only white space may follow it. A final
+/// group without padding is accepted.
+fn decode_stream<C: GroupCodec>(
+    codec: &C,
+    input: &mut dyn Read,
+    out: &mut dyn Write,
+) -> Result<(), CliError> {
+    let group_len = C::GROUP_LEN;
+    let mut buf = vec![0; 64 * 1024];
+    let mut group = [0u8; 8];
+    let mut offsets = [0u64; 8];
+    let mut n = 0;
+    let mut offset: u64 = 0;
+    let mut ended = false;
+    let mut dst = [0u8; 8];
+    let mut decoded = Vec::with_capacity(buf.len());
+
+    let mut decode_group = |group: &[u8], offsets: &[u64], decoded: &mut Vec<u8>| {
+        let size = codec
+            .decode(&mut dst, group)
+            .ok_or(CliError::InvalidInput { offset: offsets[0] })?;
+        decoded.extend_from_slice(&dst[..size]);
+        Ok::<(), CliError>(())
+    };
+
+    loop {
+        let len = read_full(input, &mut buf)?;
+        decoded.clear();
+        for &b in &buf[..len] {
+            if !b.is_ascii_whitespace() {
+                if ended {
+                    return Err(CliError::InvalidInput { offset });
+                }
+                group[n] = b;
+                offsets[n] = offset;
+                n += 1;
+                if n == group_len {
+                    let digits = check_group(codec, &group[..n], &offsets[..n])?;
+                    decode_group(&group[..n], &offsets[..n], &mut decoded)?;
+                    ended = digits < n;
+                    n = 0;
+                }
+            }
+            offset += 1;
+        }
+        out.write_all(&decoded)?;
+        if len < buf.len() {
+            break;
+        }
+    }
+
+    if n > 0 {
+        let digits = check_group(codec, &group[..n], &offsets[..n])?;
+        if digits < n {
+            // padding must fill the group
+            return Err(CliError::InvalidInput { offset });
+        }
+        match C::PAD {
+            Some(pad) if codec.valid_len(n) => {
+                group[n..group_len].fill(pad);
+                decoded.clear();
+                decode_group(&group[..group_len], &offsets[..n], &mut decoded)?;
+                out.write_all(&decoded)?;
+            }
+            _ => {
+                return Err(CliError::InvalidInput {
+                    offset: offsets[n - 1],
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+fn run_str(args: &[&str], input: &[u8]) -> Result<Vec<u8>, CliError> {
+    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+    let mut out = Vec::new();
+    run(&args, &mut &input[..], &mut out)?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests_cli {
+    use super::*;
+
+    #[test]
+    fn test_wrap() {
+        let mut out = Vec::new();
+        let mut wrap = Wrap::new(&mut out, 4);
+        wrap.write(b"abc").unwrap();
+        wrap.write(b"defgh").unwrap();
+        wrap.finish().unwrap();
+        assert_eq!(out, b"abcd\nefgh\n");
+
+        let mut out = Vec::new();
+        let mut wrap = Wrap::new(&mut out, 0);
+        wrap.write(b"abc").unwrap();
+        wrap.finish().unwrap();
+        assert_eq!(out, b"abc\n");
+
+        let mut out = Vec::new();
+        Wrap::new(&mut out, 4).finish().unwrap();
+        assert_eq!(out, b"");
+    }
+
+    #[test]
+    fn test_usage_errors() {
+        let usage = |args: &[&str]| matches!(run_str(args, b""), Err(CliError::Usage(_)));
+        assert!(usage(&[]));
+        assert!(usage(&["base99"]));
+        assert!(usage(&["base64", "--bogus"]));
+        assert!(usage(&["base64", "-w"]));
+        assert!(usage(&["base64", "-w", "x"]));
+        assert!(usage(&["base64", "a", "b"]));
+        assert_eq!(run_str(&["--help"], b"").unwrap(), USAGE.as_bytes());
+    }
+
+    #[test]
+    fn test_file_input() {
+        let path = std::env::temp_dir().join(format!("connx-cli-{}", std::process::id()));
+        std::fs::write(&path, b"hello").unwrap();
+        let out = run_str(&["base64", path.to_str().unwrap()], b"ignored");
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(out.unwrap(), b"aGVsbG8=\n");
+        assert_eq!(run_str(&["base64", "-"], b"hello").unwrap(), b"aGVsbG8=\n");
+
+        match run_str(&["hex", "/nonexistent/connx"], b"") {
+            Err(CliError::Io(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::NotFound);
+                assert!(e.to_string().starts_with("/nonexistent/connx: "));
+            }
+            other => panic!("unexpected result: {:?}", other.map(|_| ())),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! `connx b58 [-d] [FILE]`, base58 with the Bitcoin alphabet.
+//!
+//! Base58 is a conversion of the whole input to another radix, so the input
+//! is read into memory. Decoding ignores leading and trailing white space.
+
+use super::{CliError, CommonArgs};
+use connx::encoding::radix::{self, RadixError};
+use std::io::{Read, Write};
+
+pub fn run(args: &[String], stdin: &mut dyn Read, stdout: &mut dyn Write) -> Result<(), CliError> {
+    let mut common = CommonArgs::default();
+    let mut decode = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-d" | "--decode" => decode = true,
+            arg => common.parse(arg, &mut iter)?,
+        }
+    }
+    if common.wrap.is_some() {
+        return Err(CliError::Usage("b58 output is not wrapped".to_string()));
+    }
+    let mut src = Vec::new();
+    common.input(stdin)?.read_to_end(&mut src)?;
+
+    if decode {
+        let start = src
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(src.len());
+        let end = src
+            .iter()
+            .rposition(|b| !b.is_ascii_whitespace())
+            .map_or(start, |i| i + 1);
+        let digits = &src[start..end];
+        let mut dst = vec![0; radix::decode_len(digits.len())];
+        let n = radix::decode(&mut dst, digits, &radix::BASE58).map_err(|e| {
+            let RadixError::InvalidByte(b) = e;
+            let pos = digits.iter().position(|&c| c == b).unwrap_or(0);
+            CliError::InvalidInput {
+                offset: (start + pos) as u64,
+            }
+        })?;
+        stdout.write_all(&dst[..n])?;
+        return Ok(());
+    }
+
+    if !src.is_empty() {
+        let encoded = radix::encode_to_string(&src, &radix::BASE58);
+        writeln!(stdout, "{}", encoded)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests_cli_b58 {
+    use super::super::{run_str, CliError};
+
+    #[test]
+    fn test_b58() {
+        assert_eq!(run_str(&["b58"], b"").unwrap(), b"");
+        assert_eq!(
+            run_str(&["b58"], b"hello world").unwrap(),
+            b"StV1DL6CwTryKyV\n"
+        );
+        assert_eq!(run_str(&["b58"], b"\0\0a").unwrap(), b"112g\n");
+        assert_eq!(
+            run_str(&["b58", "-d"], b" StV1DL6CwTryKyV\n").unwrap(),
+            b"hello world"
+        );
+        match run_str(&["b58", "-d"], b"\nStV0") {
+            Err(CliError::InvalidInput { offset }) => assert_eq!(offset, 4),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            run_str(&["b58", "-w", "4"], b""),
+            Err(CliError::Usage(_))
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+//! `connx base32 [-d] [--hex] [-w COLS] [FILE]`
+
+use super::{decode_stream, encode_stream, CliError, CommonArgs, GroupCodec, Wrap};
+use connx::encoding::base32;
+use std::io::{Read, Write};
+
+struct Base32Codec {
+    decode_map: &'static [u8],
+}
+
+impl GroupCodec for Base32Codec {
+    const GROUP_LEN: usize = 8;
+    const PAD: Option<u8> = Some(base32::PAD_CHAR);
+
+    fn is_symbol(&self, b: u8) -> bool {
+        self.decode_map[b as usize] != 0xFF
+    }
+
+    fn valid_len(&self, n: usize) -> bool {
+        matches!(n, 2 | 4 | 5 | 7)
+    }
+
+    fn decode(&self, dst: &mut [u8], group: &[u8]) -> Option<usize> {
+        base32::decode(dst, group, self.decode_map).ok()
+    }
+}
+
+pub fn run(args: &[String], stdin: &mut dyn Read, stdout: &mut dyn Write) -> Result<(), CliError> {
+    let mut common = CommonArgs::default();
+    let (mut decode, mut hex) = (false, false);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-d" | "--decode" => decode = true,
+            "--hex" => hex = true,
+            arg => common.parse(arg, &mut iter)?,
+        }
+    }
+    let mut input = common.input(stdin)?;
+
+    if decode {
+        let decode_map = if hex {
+            base32::DECODE_HEX_MAP
+        } else {
+            base32::DECODE_STD_MAP
+        };
+        return decode_stream(&Base32Codec { decode_map }, &mut input, stdout);
+    }
+
+    let encode_map = if hex {
+        base32::ENCODE_HEX
+    } else {
+        base32::ENCODE_STD
+    };
+    let mut out = Wrap::new(stdout, common.wrap());
+    encode_stream(&mut input, &mut out, 5, |src, dst| {
+        dst.resize(base32::encode_len(src.len()), 0);
+        base32::encode(dst, src, encode_map);
+    })?;
+    Ok(out.finish()?)
+}
+
+#[cfg(test)]
+mod tests_cli_base32 {
+    use super::super::{run_str, CliError};
+
+    #[test]
+    fn test_encode_decode() {
+        assert_eq!(
+            run_str(&["base32"], b"hello!").unwrap(),
+            b"NBSWY3DPEE======\n"
+        );
+        assert_eq!(
+            run_str(&["base32", "--hex"], b"hello").unwrap(),
+            b"D1IMOR3F\n"
+        );
+        assert_eq!(
+            run_str(&["base32", "-d"], b"NBSWY3DP\nEE======\n").unwrap(),
+            b"hello!"
+        );
+        assert_eq!(
+            run_str(&["base32", "-d"], b"NBSWY3DPEE").unwrap(),
+            b"hello!"
+        );
+        assert_eq!(
+            run_str(&["base32", "-d", "--hex"], b"D1IMOR3F").unwrap(),
+            b"hello"
+        );
+
+        let data: Vec<u8> = (0..100_000u32).map(|i| (i % 253) as u8).collect();
+        let encoded = run_str(&["base32", "-w", "60"], &data).unwrap();
+        assert_eq!(run_str(&["base32", "-d"], &encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let invalid_at = |input: &[u8]| match run_str(&["base32", "-d"], input) {
+            Err(CliError::InvalidInput { offset }) => offset,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(invalid_at(b"NBSWY3D1"), 7);
+        assert_eq!(invalid_at(b"NBS====="), 3);
+        assert_eq!(invalid_at(b"NBSWY3DPE"), 8);
+        assert_eq!(invalid_at(b"MY======MY"), 8);
+    }
+}
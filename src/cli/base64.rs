@@ -0,0 +1,131 @@
+//! `connx base64 [-d] [--url] [--no-pad] [-w COLS] [FILE]`
+
+use super::{decode_stream, encode_stream, CliError, CommonArgs, GroupCodec, Wrap};
+use connx::encoding::base64;
+use std::io::{Read, Write};
+
+struct Base64Codec {
+    decode_map: &'static [u8],
+}
+
+impl GroupCodec for Base64Codec {
+    const GROUP_LEN: usize = 4;
+    const PAD: Option<u8> = Some(base64::PAD_CHAR);
+
+    fn is_symbol(&self, b: u8) -> bool {
+        self.decode_map[b as usize] != 0xFF
+    }
+
+    fn valid_len(&self, n: usize) -> bool {
+        n >= 2
+    }
+
+    fn decode(&self, dst: &mut [u8], group: &[u8]) -> Option<usize> {
+        base64::decode(dst, group, self.decode_map).ok()
+    }
+}
+
+pub fn run(args: &[String], stdin: &mut dyn Read, stdout: &mut dyn Write) -> Result<(), CliError> {
+    let mut common = CommonArgs::default();
+    let (mut decode, mut url, mut pad) = (false, false, true);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-d" | "--decode" => decode = true,
+            "--url" => url = true,
+            "--no-pad" => pad = false,
+            arg => common.parse(arg, &mut iter)?,
+        }
+    }
+    let mut input = common.input(stdin)?;
+
+    if decode {
+        let decode_map = if url {
+            base64::DECODE_URL_MAP
+        } else {
+            base64::DECODE_STD_MAP
+        };
+        return decode_stream(&Base64Codec { decode_map }, &mut input, stdout);
+    }
+
+    let encode_map = if url {
+        base64::ENCODE_URL
+    } else {
+        base64::ENCODE_STD
+    };
+    let mut out = Wrap::new(stdout, common.wrap());
+    encode_stream(&mut input, &mut out, 3, |src, dst| {
+        dst.resize(base64::encode_len(src.len()), 0);
+        base64::encode(dst, src, encode_map);
+        if !pad {
+            while dst.last() == Some(&base64::PAD_CHAR) {
+                dst.pop();
+            }
+        }
+    })?;
+    Ok(out.finish()?)
+}
+
+#[cfg(test)]
+mod tests_cli_base64 {
+    use super::super::{run_str, CliError};
+
+    fn invalid_at(args: &[&str], input: &[u8]) -> u64 {
+        match run_str(args, input) {
+            Err(CliError::InvalidInput { offset }) => offset,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(run_str(&["base64"], b"").unwrap(), b"");
+        assert_eq!(run_str(&["base64"], b"hello").unwrap(), b"aGVsbG8=\n");
+        assert_eq!(
+            run_str(&["base64", "--no-pad"], b"hello").unwrap(),
+            b"aGVsbG8\n"
+        );
+        assert_eq!(
+            run_str(&["base64", "--url"], b"\xfb\xff").unwrap(),
+            b"-_8=\n"
+        );
+        assert_eq!(
+            run_str(&["base64", "-w", "4"], b"hello").unwrap(),
+            b"aGVs\nbG8=\n"
+        );
+        assert_eq!(
+            run_str(&["base64", "--wrap=0"], &[0; 60]).unwrap().len(),
+            81
+        );
+        assert_eq!(run_str(&["base64"], &[0; 60]).unwrap()[76], b'\n');
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(run_str(&["base64", "-d"], b"").unwrap(), b"");
+        assert_eq!(
+            run_str(&["base64", "-d"], b"aGVs\nbG8=\n").unwrap(),
+            b"hello"
+        );
+        assert_eq!(run_str(&["base64", "-d"], b" aGVsbG8 ").unwrap(), b"hello");
+        assert_eq!(
+            run_str(&["base64", "-d", "--url"], b"-_8").unwrap(),
+            b"\xfb\xff"
+        );
+        assert_eq!(invalid_at(&["base64", "-d"], b"-_8="), 0);
+        assert_eq!(invalid_at(&["base64", "-d"], b"aGVs\nb!8="), 6);
+        assert_eq!(invalid_at(&["base64", "-d"], b"aGVsb"), 4);
+        assert_eq!(invalid_at(&["base64", "-d"], b"aG=s"), 3);
+        assert_eq!(invalid_at(&["base64", "-d"], b"a==="), 1);
+        assert_eq!(invalid_at(&["base64", "-d"], b"aA==aA=="), 4);
+        assert_eq!(invalid_at(&["base64", "-d"], b"aGVsbG="), 7);
+    }
+
+    #[test]
+    fn test_roundtrip_large() {
+        // crosses the read buffer boundaries of both directions
+        let data: Vec<u8> = (0..200_000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let encoded = run_str(&["base64"], &data).unwrap();
+        assert_eq!(run_str(&["base64", "-d"], &encoded).unwrap(), data);
+    }
+}
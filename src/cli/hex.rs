@@ -0,0 +1,148 @@
+//! `connx hex [--dump | --reverse] [-w COLS] [FILE]`
+//!
+//! Without option the input is encoded to lowercase hex, `--reverse` decodes
+//! hex of either case, `--dump` prints the canonical `hexdump -C` layout
+//! without folding repeated lines.
+
+use super::{decode_stream, encode_stream, read_full, CliError, CommonArgs, GroupCodec, Wrap};
+use connx::encoding::base16;
+use std::io::{Read, Write};
+
+/// Bytes per line of `--dump`
+const DUMP_LEN: usize = 16;
+
+struct HexCodec;
+
+impl GroupCodec for HexCodec {
+    const GROUP_LEN: usize = 2;
+    const PAD: Option<u8> = None;
+
+    fn is_symbol(&self, b: u8) -> bool {
+        b.is_ascii_hexdigit()
+    }
+
+    fn valid_len(&self, n: usize) -> bool {
+        n == 2
+    }
+
+    fn decode(&self, dst: &mut [u8], group: &[u8]) -> Option<usize> {
+        base16::decode(dst, group).ok()
+    }
+}
+
+/// Write one `--dump` line, offset, hex bytes in two halves and the text
+fn dump_line(out: &mut dyn Write, offset: u64, data: &[u8]) -> std::io::Result<()> {
+    let mut line = format!("{:08x} ", offset);
+    for i in 0..DUMP_LEN {
+        if i % 8 == 0 {
+            line.push(' ');
+        }
+        match data.get(i) {
+            Some(b) => line.push_str(&format!("{:02x} ", b)),
+            None => line.push_str("   "),
+        }
+    }
+    line.push_str(" |");
+    line.extend(data.iter().map(|&b| {
+        if b.is_ascii_graphic() || b == b' ' {
+            b as char
+        } else {
+            '.'
+        }
+    }));
+    line.push_str("|\n");
+    out.write_all(line.as_bytes())
+}
+
+fn dump(input: &mut dyn Read, out: &mut dyn Write) -> Result<(), CliError> {
+    let mut buf = vec![0; DUMP_LEN * 4096];
+    let mut offset: u64 = 0;
+    loop {
+        let n = read_full(input, &mut buf)?;
+        for line in buf[..n].chunks(DUMP_LEN) {
+            dump_line(out, offset, line)?;
+            offset += line.len() as u64;
+        }
+        if n < buf.len() {
+            break;
+        }
+    }
+    if offset > 0 {
+        writeln!(out, "{:08x}", offset)?;
+    }
+    Ok(())
+}
+
+pub fn run(args: &[String], stdin: &mut dyn Read, stdout: &mut dyn Write) -> Result<(), CliError> {
+    let mut common = CommonArgs::default();
+    let (mut dump_mode, mut reverse) = (false, false);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--dump" => dump_mode = true,
+            "-r" | "--reverse" => reverse = true,
+            arg => common.parse(arg, &mut iter)?,
+        }
+    }
+    if dump_mode && reverse {
+        return Err(CliError::Usage(
+            "--dump and --reverse are exclusive".to_string(),
+        ));
+    }
+    let mut input = common.input(stdin)?;
+
+    if reverse {
+        return decode_stream(&HexCodec, &mut input, stdout);
+    }
+    if dump_mode {
+        return dump(&mut input, stdout);
+    }
+    let mut out = Wrap::new(stdout, common.wrap());
+    encode_stream(&mut input, &mut out, 1, |src, dst| {
+        dst.resize(base16::encode_len(src.len()), 0);
+        base16::encode(dst, src);
+    })?;
+    Ok(out.finish()?)
+}
+
+#[cfg(test)]
+mod tests_cli_hex {
+    use super::super::{run_str, CliError};
+
+    #[test]
+    fn test_encode_reverse() {
+        assert_eq!(run_str(&["hex"], b"hello").unwrap(), b"68656c6c6f\n");
+        assert_eq!(
+            run_str(&["hex", "-w", "4"], b"hello").unwrap(),
+            b"6865\n6c6c\n6f\n"
+        );
+        assert_eq!(
+            run_str(&["hex", "--reverse"], b"68 65 6C\n6c 6f\n").unwrap(),
+            b"hello"
+        );
+        match run_str(&["hex", "-r"], b"68 6") {
+            Err(CliError::InvalidInput { offset }) => assert_eq!(offset, 3),
+            other => panic!("unexpected {:?}", other),
+        }
+        match run_str(&["hex", "-r"], b"6g") {
+            Err(CliError::InvalidInput { offset }) => assert_eq!(offset, 1),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(matches!(
+            run_str(&["hex", "--dump", "-r"], b""),
+            Err(CliError::Usage(_))
+        ));
+    }
+
+    #[test]
+    fn test_dump() {
+        assert_eq!(run_str(&["hex", "--dump"], b"").unwrap(), b"");
+        let out = run_str(&["hex", "--dump"], b"hello, world\n\x00\xffxyz").unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "00000000  68 65 6c 6c 6f 2c 20 77  6f 72 6c 64 0a 00 ff 78  |hello, world...x|\n\
+             00000010  79 7a                                             |yz|\n\
+             00000012\n"
+        );
+    }
+}
@@ -2,3 +2,38 @@ pub mod base16;
 pub mod base32;
 pub mod base64;
 pub mod binary;
+#[cfg(feature = "std")]
+pub mod cobs;
+#[cfg(feature = "std")]
+pub mod detect;
+#[cfg(feature = "std")]
+mod framing;
+#[cfg(feature = "std")]
+pub mod ihex;
+#[cfg(feature = "std")]
+pub mod image;
+#[cfg(feature = "std")]
+pub mod multibase;
+#[cfg(feature = "std")]
+pub mod percent;
+#[cfg(feature = "std")]
+pub mod punycode;
+#[cfg(feature = "std")]
+pub mod quoted_printable;
+#[cfg(feature = "std")]
+pub mod radix;
+#[cfg(feature = "serde")]
+mod serde_codec;
+#[cfg(feature = "std")]
+pub mod slip;
+#[cfg(feature = "std")]
+pub mod srec;
+#[cfg(feature = "std")]
+pub mod utf7;
+#[cfg(feature = "std")]
+pub mod uuencode;
+#[cfg(feature = "std")]
+pub mod yenc;
+
+#[cfg(feature = "std")]
+pub use detect::detect;
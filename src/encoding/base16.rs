@@ -23,15 +23,21 @@
 //! assert_eq!(dst, b"hello");
 //! ```
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+
 /// Errors when base16 encode and decode
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Base16Error {
     InvalidByte(InvalidByteError),
     OddLength,
 }
 
-impl std::fmt::Display for Base16Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Base16Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidByte(e) => e.fmt(f),
             Self::OddLength => write!(f, "encoding/base16: odd length hex string"),
@@ -39,6 +45,7 @@ impl std::fmt::Display for Base16Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Base16Error {}
 
 impl From<InvalidByteError> for Base16Error {
@@ -48,7 +55,7 @@ impl From<InvalidByteError> for Base16Error {
 }
 
 /// Error happens when pass invalid character to decode function
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct InvalidByteError {
     b: u8,
 }
@@ -59,17 +66,21 @@ impl InvalidByteError {
     }
 }
 
-impl std::fmt::Display for InvalidByteError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for InvalidByteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "encoding/base16: invalid byte: {}", self.b)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for InvalidByteError {}
 
 /// Base16 encoding map
 pub const HEX_TABLE: &[u8] = b"0123456789abcdef";
 
+/// Base16 uppercase encoding map, used by escapes such as `%XX` and `=XX`
+pub const HEX_TABLE_UPPER: &[u8] = b"0123456789ABCDEF";
+
 /// Calculate base16 encoded string length
 ///
 /// - @param n: raw bytes length
@@ -138,15 +149,12 @@ pub fn encode(dst: &mut [u8], src: &[u8]) -> usize {
     src.len() * 2
 }
 
-fn from_hex_char(b: u8) -> Result<u8, InvalidByteError> {
-    if b.is_ascii_digit() {
-        Ok(b - b'0')
-    } else if b.is_ascii_lowercase() {
-        Ok(b - b'a' + 10)
-    } else if b.is_ascii_uppercase() {
-        Ok(b - b'A' + 10)
-    } else {
-        Err(InvalidByteError::new(b))
+pub(crate) fn from_hex_char(b: u8) -> Result<u8, InvalidByteError> {
+    match b {
+        b'0'..=b'9' => Ok(b - b'0'),
+        b'a'..=b'f' => Ok(b - b'a' + 10),
+        b'A'..=b'F' => Ok(b - b'A' + 10),
+        _ => Err(InvalidByteError::new(b)),
     }
 }
 
@@ -199,6 +207,7 @@ pub fn decode(dst: &mut [u8], src: &[u8]) -> Result<usize, Base16Error> {
 /// let dst = base16::encode_to_string(src);
 /// assert_eq!(dst, "68656c6c6f".to_string());
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_to_string(src: &[u8]) -> String {
     let mut dst = vec![0u8; encode_len(src.len())];
     encode(&mut dst, src);
@@ -220,20 +229,134 @@ pub fn encode_to_string(src: &[u8]) -> String {
 /// let dst = base16::decode_string(src).unwrap();
 /// assert_eq!(dst, b"hello");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_string(src: &str) -> Result<Vec<u8>, Base16Error> {
     let mut dst = vec![0u8; decode_len(src.len())];
     decode(&mut dst, src.as_bytes())?;
     Ok(dst)
 }
 
+/// Bytes encoded per write by the formatting functions, kept on the stack
+const FMT_CHUNK_LEN: usize = 32;
+
+fn encode_to_fmt_with_table<W: core::fmt::Write>(
+    w: &mut W,
+    src: &[u8],
+    table: &[u8],
+) -> core::fmt::Result {
+    let mut buf = [0u8; FMT_CHUNK_LEN * 2];
+    for chunk in src.chunks(FMT_CHUNK_LEN) {
+        for (i, &src_byte) in chunk.iter().enumerate() {
+            buf[i * 2] = table[(src_byte >> 4) as usize];
+            buf[i * 2 + 1] = table[(src_byte & 0x0F) as usize];
+        }
+        w.write_str(core::str::from_utf8(&buf[..chunk.len() * 2]).unwrap())?;
+    }
+    Ok(())
+}
+
+/// Encode bytes to base16 directly into a formatter or any `fmt::Write`
+///
+/// - @param w: destination of the lowercase base16 characters
+/// - @param src: raw bytes
+/// - @return: error of the destination, if any
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base16;
+/// use core::fmt::Write;
+/// let mut dst = String::from("id=");
+/// base16::encode_to_fmt(&mut dst, b"hello").unwrap();
+/// assert_eq!(dst, "id=68656c6c6f");
+/// ```
+pub fn encode_to_fmt<W: core::fmt::Write>(w: &mut W, src: &[u8]) -> core::fmt::Result {
+    encode_to_fmt_with_table(w, src, HEX_TABLE)
+}
+
+/// Encode bytes to base16, appended to a string
+///
+/// - @param dst: string the lowercase base16 characters are appended to
+/// - @param src: raw bytes
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base16;
+/// let mut dst = String::from("0x");
+/// base16::encode_append(&mut dst, &[0xde, 0xad]);
+/// assert_eq!(dst, "0xdead");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_append(dst: &mut String, src: &[u8]) {
+    dst.reserve(encode_len(src.len()));
+    // writing to a String never fails
+    encode_to_fmt(dst, src).unwrap();
+}
+
+/// Formatting adapter writing bytes as base16 without allocating
+///
+/// `{}` and `{:x}` write lowercase, `{:X}` uppercase, and the alternate flag
+/// of the hex formats adds the `0x` prefix. The digits are streamed, so
+/// width, fill and alignment are ignored.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base16;
+/// let digest = [0xca, 0xfe, 0xba, 0xbe];
+/// assert_eq!(format!("{}", base16::Display(&digest)), "cafebabe");
+/// assert_eq!(format!("{:X}", base16::Display(&digest)), "CAFEBABE");
+/// assert_eq!(format!("{:#x}", base16::Display(&digest)), "0xcafebabe");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Display<'a>(pub &'a [u8]);
+
+impl core::fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        encode_to_fmt_with_table(f, self.0, HEX_TABLE)
+    }
+}
+
+impl core::fmt::LowerHex for Display<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        encode_to_fmt_with_table(f, self.0, HEX_TABLE)
+    }
+}
+
+impl core::fmt::UpperHex for Display<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        encode_to_fmt_with_table(f, self.0, HEX_TABLE_UPPER)
+    }
+}
+
 #[cfg(test)]
 mod tests_base16 {
     use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::format;
 
     fn test_base(data: &[u8], encoded_expect: &str) {
-        let encoded = encode_to_string(data);
-        assert_eq!(encoded, encoded_expect.to_string());
-        let decoded = decode_string(&encoded).unwrap();
+        let mut buf = [0; 64];
+        let encoded = &mut buf[..encode_len(data.len())];
+        assert_eq!(encode(encoded, data), encoded.len());
+        assert_eq!(encoded, encoded_expect.as_bytes());
+
+        let mut decoded_buf = [0; 32];
+        let decoded = &mut decoded_buf[..decode_len(encoded.len())];
+        assert_eq!(decode(decoded, encoded), Ok(data.len()));
         assert_eq!(data, decoded);
     }
 
@@ -250,7 +373,7 @@ mod tests_base16 {
             &[0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd, 0xfe, 0xff],
             "f8f9fafbfcfdfeff",
         );
-        test_base(&[b'g'], "67");
+        test_base(b"g", "67");
         test_base(&[0xe3, 0xa1], "e3a1");
     }
 
@@ -269,4 +392,31 @@ mod tests_base16 {
     fn test03() {
         test_base(b"hello", "68656c6c6f");
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_display() {
+        let data: Vec<u8> = (0..=255).collect();
+        let expect = encode_to_string(&data);
+        assert_eq!(format!("{}", Display(&data)), expect);
+        assert_eq!(format!("{:x}", Display(&data)), expect);
+        assert_eq!(format!("{:X}", Display(&data)), expect.to_uppercase());
+        assert_eq!(format!("{:#X}", Display(&[0xab])), "0xAB");
+        assert_eq!(format!("{}", Display(&[])), "");
+
+        let mut dst = String::from(">");
+        encode_append(&mut dst, &data);
+        assert_eq!(dst, format!(">{}", expect));
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_decode_invalid() {
+        assert_eq!(decode_string("68656C6C6F").unwrap(), b"hello");
+        assert!(matches!(
+            decode_string("6g"),
+            Err(Base16Error::InvalidByte(InvalidByteError { b: b'g' }))
+        ));
+        assert!(matches!(decode_string("686"), Err(Base16Error::OddLength)));
+    }
 }
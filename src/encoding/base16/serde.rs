@@ -0,0 +1,84 @@
+//! serde support for hex fields, lowercase when serializing.
+//!
+//! Use it with `#[serde(with = "connx::encoding::base16::serde")]` on
+//! `Vec<u8>` and `[u8; N]` fields, or with the [`option`] module on
+//! `Option<Vec<u8>>` fields. Human-readable formats such as JSON get a hex
+//! string, either case is accepted back, binary formats get the raw bytes.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::base16;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Node {
+//!     #[serde(with = "base16::serde")]
+//!     guid: [u8; 4],
+//! }
+//!
+//! let node = Node { guid: [0xca, 0xfe, 0xba, 0xbe] };
+//! assert_eq!(serde_json::to_string(&node).unwrap(), r#"{"guid":"cafebabe"}"#);
+//! assert_eq!(serde_json::from_str::<Node>(r#"{"guid":"CAFEBABE"}"#).unwrap(), node);
+//! ```
+
+use crate::encoding::base16;
+use crate::encoding::serde_codec::{serde_module, TextCodec};
+
+struct Base16Codec;
+
+impl TextCodec for Base16Codec {
+    const EXPECTING: &'static str = "a hex string or bytes";
+
+    fn encode(src: &[u8]) -> String {
+        base16::encode_to_string(src)
+    }
+
+    fn decode(src: &str) -> Result<Vec<u8>, String> {
+        base16::decode_string(src).map_err(|e| e.to_string())
+    }
+}
+
+serde_module!(crate::encoding::base16::serde::Base16Codec);
+
+#[cfg(test)]
+mod tests_base16_serde {
+    use serde::{Deserialize, Serialize};
+    use serde_test::{assert_de_tokens, assert_tokens, Configure, Token};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct Node {
+        #[serde(with = "crate::encoding::base16::serde")]
+        guid: Vec<u8>,
+    }
+
+    fn tokens(guid: Token) -> [Token; 4] {
+        [
+            Token::Struct {
+                name: "Node",
+                len: 1,
+            },
+            Token::Str("guid"),
+            guid,
+            Token::StructEnd,
+        ]
+    }
+
+    #[test]
+    fn test_readable_and_compact() {
+        let node = Node {
+            guid: vec![0x01, 0xab],
+        };
+        assert_tokens(&node.clone().readable(), &tokens(Token::Str("01ab")));
+        assert_de_tokens(&node.clone().readable(), &tokens(Token::Str("01AB")));
+        assert_tokens(&node.compact(), &tokens(Token::Bytes(&[0x01, 0xab])));
+    }
+
+    #[test]
+    fn test_json_odd_length() {
+        let err = serde_json::from_str::<Node>(r#"{"guid":"abc"}"#).unwrap_err();
+        assert!(err.to_string().contains("odd length"));
+    }
+}
@@ -17,6 +17,12 @@
 //! assert_eq!(base32::decode_str("NBSWY3DP"), Ok((b"hello").to_vec()));
 //! ```
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+
 /// Standard encoding map from RFC4648
 pub const ENCODE_STD: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
 
@@ -73,8 +79,8 @@ pub enum Base32Error {
     InvalidLength,
 }
 
-impl std::fmt::Display for Base32Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidByte(b) => write!(f, "encoding/base32: invalid byte: {}", b),
             Self::InvalidLength => write!(f, "encoding/base32: invalid input length"),
@@ -82,6 +88,7 @@ impl std::fmt::Display for Base32Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Base32Error {}
 
 /// Calculate base32 encoded string length
@@ -101,7 +108,7 @@ impl std::error::Error for Base32Error {}
 /// ```
 #[inline]
 pub fn encode_len(n: usize) -> usize {
-    (n + 4) / 5 * 8
+    n.div_ceil(5) * 8
 }
 
 /// Calculate base32 decoded data length.
@@ -172,7 +179,7 @@ pub fn encode(dst: &mut [u8], src: &[u8], encode_map: &[u8]) {
     let remain = src_len - src_idx;
     match remain {
         0 => {}
-        1 | 2 | 3 | 4 => {
+        1..=4 => {
             let mut val: u64 = 0;
             for i in 0..remain {
                 val |= u64::from(src[src_idx + i]) << ((4 - i) * 8);
@@ -206,6 +213,7 @@ pub fn encode(dst: &mut [u8], src: &[u8], encode_map: &[u8]) {
 /// let encoded_bytes: Vec<u8> = base32::encode_bytes(b"hello");
 /// assert_eq!(encoded_bytes, b"NBSWY3DP");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_bytes(src: &[u8]) -> Vec<u8> {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -227,6 +235,7 @@ pub fn encode_bytes(src: &[u8]) -> Vec<u8> {
 /// let encoded_string: String = base32::encode_str("hello");
 /// assert_eq!(encoded_string, "NBSWY3DP".to_string());
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_str(src: &str) -> String {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -353,6 +362,7 @@ pub fn decode(dst: &mut [u8], src: &[u8], decode_map: &[u8]) -> Result<usize, Ba
 /// let dst = base32::decode_str(src).unwrap();
 /// assert_eq!(dst, b"hello");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_str(src: &str) -> Result<Vec<u8>, Base32Error> {
     let mut dst = vec![0; decode_len(src.len())];
     let dst_size = decode(&mut dst, src.as_bytes(), DECODE_STD_MAP)?;
@@ -360,19 +370,84 @@ pub fn decode_str(src: &str) -> Result<Vec<u8>, Base32Error> {
     Ok(dst)
 }
 
+/// Encode bytes to base32 bytes without padding, with custom map
+///
+/// - @param src: raw bytes
+/// - @param encode_map: base32 encoding map
+/// - @return: base32 encoded bytes, without trailing `=`
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base32;
+/// let encoded_bytes = base32::encode_unpadded(b"hello!", base32::ENCODE_STD);
+/// assert_eq!(encoded_bytes, b"NBSWY3DPEE");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_unpadded(src: &[u8], encode_map: &[u8]) -> Vec<u8> {
+    let mut dst = vec![0; encode_len(src.len())];
+    encode(&mut dst, src, encode_map);
+    while dst.last() == Some(&PAD_CHAR) {
+        dst.pop();
+    }
+    dst
+}
+
+/// Decode base32 bytes without padding, with custom map
+///
+/// Letters are matched as they are, uppercase `src` first to decode it
+/// case-insensitively with the uppercase maps.
+///
+/// - @param src: unpadded base32 bytes
+/// - @param decode_map: base32 decoding map
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base32::{self, Base32Error};
+/// let dst = base32::decode_unpadded(b"NBSWY3DPEE", base32::DECODE_STD_MAP).unwrap();
+/// assert_eq!(dst, b"hello!");
+/// assert_eq!(
+///     base32::decode_unpadded(b"NBS", base32::DECODE_STD_MAP),
+///     Err(Base32Error::InvalidLength)
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_unpadded(src: &[u8], decode_map: &[u8]) -> Result<Vec<u8>, Base32Error> {
+    if src.is_empty() {
+        return Ok(Vec::new());
+    }
+    // unpadded base32 never leaves 1, 3 or 6 characters in the last group
+    if matches!(src.len() % 8, 1 | 3 | 6) {
+        return Err(Base32Error::InvalidLength);
+    }
+    let mut padded = src.to_vec();
+    padded.resize(src.len().div_ceil(8) * 8, PAD_CHAR);
+    let mut dst = vec![0; decode_len(padded.len())];
+    let n = decode(&mut dst, &padded, decode_map)?;
+    dst.truncate(n);
+    Ok(dst)
+}
+
 #[cfg(test)]
 mod tests_base32 {
     use super::*;
 
     fn test_base(data: &[u8], encoded_expect: &[u8], encode_map: &[u8], decode_map: &[u8]) {
-        let mut dst = vec![0; encode_len(data.len())];
-        encode(&mut dst, data, encode_map);
+        let mut buf = [0; 256];
+        let dst = &mut buf[..encode_len(data.len())];
+        encode(dst, data, encode_map);
         assert_eq!(dst, encoded_expect);
 
-        let mut decoded_dst = vec![0; decode_len(dst.len())];
-        if let Ok(size) = decode(&mut decoded_dst, &dst, decode_map) {
-            decoded_dst.drain(size..);
-            assert_eq!(data, decoded_dst);
+        let mut decoded_buf = [0; 256];
+        let decoded_dst = &mut decoded_buf[..decode_len(dst.len())];
+        if let Ok(size) = decode(decoded_dst, dst, decode_map) {
+            assert_eq!(data, &decoded_dst[..size]);
         }
     }
 
@@ -510,4 +585,19 @@ mod tests_base32 {
         test_std_base(b"SmPverljLU6ct4biRiKqiFzzAK0q9TE43A9j6BW15N1s4lKg0uA4EOcVq6LMUtWlcHAQZB8jSUurlRkQwKXpo3RIAkpecncZpIem", b"KNWVA5TFOJWGUTCVGZRXINDCNFJGSS3RNFDHU6SBJMYHCOKUIU2DGQJZNI3EEVZRGVHDC4ZUNRFWOMDVIE2EKT3DKZYTMTCNKV2FO3DDJBAVCWSCHBVFGVLVOJWFE22RO5FVQ4DPGNJESQLLOBSWG3TDLJYESZLN");
         test_std_base(b"P9vVvoGvt6tLUDQxl7P2UXhujjf2DyeMQOHPoEhXnSdPO7D2U2w5aqmLubmTZRGf4UgzSThVFPBJZHsdCteyr6eEfRO6YFPyHRoM", b"KA4XMVTWN5DXM5BWORGFKRCRPBWDOUBSKVMGQ5LKNJTDERDZMVGVCT2IKBXUK2CYNZJWIUCPG5CDEVJSO42WC4LNJR2WE3KULJJEOZRUKVTXUU2UNBLEMUCCJJNEQ43EIN2GK6LSGZSUKZSSJ43FSRSQPFEFE32N");
     }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_unpadded() {
+        assert_eq!(encode_unpadded(b"hello!", ENCODE_STD), b"NBSWY3DPEE");
+        assert_eq!(
+            decode_unpadded(b"NBSWY3DPEE", DECODE_STD_MAP),
+            Ok(b"hello!".to_vec())
+        );
+        assert_eq!(
+            decode_unpadded(b"nbswy3dpee", DECODE_STD_MAP),
+            Err(Base32Error::InvalidByte(b'n'))
+        );
+        assert_eq!(decode_unpadded(b"", DECODE_STD_MAP), Ok(vec![]));
+    }
 }
@@ -0,0 +1,84 @@
+//! serde support for base32 fields, standard alphabet with padding.
+//!
+//! Use it with `#[serde(with = "connx::encoding::base32::serde")]` on
+//! `Vec<u8>` and `[u8; N]` fields, or with the [`option`] module on
+//! `Option<Vec<u8>>` fields. Human-readable formats such as JSON get a base32
+//! string, binary formats get the raw bytes.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::base32;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Secret {
+//!     #[serde(with = "base32::serde")]
+//!     totp: Vec<u8>,
+//! }
+//!
+//! let secret = Secret { totp: b"hello".to_vec() };
+//! let json = serde_json::to_string(&secret).unwrap();
+//! assert_eq!(json, r#"{"totp":"NBSWY3DP"}"#);
+//! assert_eq!(serde_json::from_str::<Secret>(&json).unwrap(), secret);
+//! ```
+
+use crate::encoding::base32;
+use crate::encoding::serde_codec::{serde_module, TextCodec};
+
+struct Base32Codec;
+
+impl TextCodec for Base32Codec {
+    const EXPECTING: &'static str = "a base32 string or bytes";
+
+    fn encode(src: &[u8]) -> String {
+        String::from_utf8(base32::encode_bytes(src)).unwrap()
+    }
+
+    fn decode(src: &str) -> Result<Vec<u8>, String> {
+        base32::decode_str(src).map_err(|e| e.to_string())
+    }
+}
+
+serde_module!(crate::encoding::base32::serde::Base32Codec);
+
+#[cfg(test)]
+mod tests_base32_serde {
+    use serde::{Deserialize, Serialize};
+    use serde_test::{assert_tokens, Configure, Token};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct Secret(
+        #[serde(with = "crate::encoding::base32::serde")] [u8; 5],
+        #[serde(with = "crate::encoding::base32::serde::option")] Option<Vec<u8>>,
+    );
+
+    #[test]
+    fn test_readable_and_compact() {
+        let secret = Secret(*b"hello", Some(b"f".to_vec()));
+        let readable = [
+            Token::TupleStruct {
+                name: "Secret",
+                len: 2,
+            },
+            Token::Str("NBSWY3DP"),
+            Token::Some,
+            Token::Str("MY======"),
+            Token::TupleStructEnd,
+        ];
+        assert_tokens(&secret.clone().readable(), &readable);
+        let compact = [
+            Token::TupleStruct {
+                name: "Secret",
+                len: 2,
+            },
+            Token::Bytes(b"hello"),
+            Token::Some,
+            Token::Bytes(b"f"),
+            Token::TupleStructEnd,
+        ];
+        assert_tokens(&secret.compact(), &compact);
+    }
+}
@@ -17,12 +17,21 @@
 //! assert_eq!(base64::decode_str("aGVsbG8="), Ok((b"hello").to_vec()));
 //! ```
 
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec, vec::Vec};
+
 /// Standard encoding map from RFC4648
 pub const ENCODE_STD: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
 
 /// URL and filename encoding map from RFC4648
 pub const ENCODE_URL: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
+/// Modified base64 encoding map from RFC3501, `,` is used instead of `/`
+pub const ENCODE_IMAP: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+,";
+
 /// Standard padding char from RFC4648
 pub const PAD_CHAR: u8 = b'=';
 
@@ -73,8 +82,8 @@ pub enum Base64Error {
     InvalidLength,
 }
 
-impl std::fmt::Display for Base64Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::InvalidByte(e) => e.fmt(f),
             Self::InvalidLength => write!(f, "encoding/base64: invalid input length"),
@@ -82,6 +91,7 @@ impl std::fmt::Display for Base64Error {
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for Base64Error {}
 
 impl From<InvalidByteError> for Base64Error {
@@ -102,12 +112,13 @@ impl InvalidByteError {
     }
 }
 
-impl std::fmt::Display for InvalidByteError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for InvalidByteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "encoding/base64: invalid byte: {}", self.b)
     }
 }
 
+#[cfg(feature = "std")]
 impl std::error::Error for InvalidByteError {}
 
 /// Calculate base64 encoded string length
@@ -155,7 +166,7 @@ pub fn encode_str_len(src: &str) -> usize {
 /// ```
 #[inline]
 pub fn encode_len(src_len: usize) -> usize {
-    (src_len + 2) / 3 * 4
+    src_len.div_ceil(3) * 4
 }
 
 /// Calculate base64 decoded data length. Padded base64 should always be
@@ -260,6 +271,7 @@ pub fn encode(dst: &mut [u8], src: &[u8], encode_map: &[u8]) {
 /// let encoded_bytes: Vec<u8> = base64::encode_bytes_with_map(b"hello", base64::ENCODE_STD);
 /// assert_eq!(encoded_bytes, b"aGVsbG8=");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_bytes_with_map(src: &[u8], encode_map: &[u8]) -> Vec<u8> {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -282,6 +294,7 @@ pub fn encode_bytes_with_map(src: &[u8], encode_map: &[u8]) -> Vec<u8> {
 /// let encoded_string: String = base64::encode_str_with_map("hello", base64::ENCODE_STD);
 /// assert_eq!(encoded_string, "aGVsbG8=".to_string());
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_str_with_map(src: &str, encode_map: &[u8]) -> String {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -303,6 +316,7 @@ pub fn encode_str_with_map(src: &str, encode_map: &[u8]) -> String {
 /// let encoded_bytes: Vec<u8> = base64::encode_bytes(b"hello");
 /// assert_eq!(encoded_bytes, b"aGVsbG8=");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_bytes(src: &[u8]) -> Vec<u8> {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -324,6 +338,7 @@ pub fn encode_bytes(src: &[u8]) -> Vec<u8> {
 /// let encoded: String = base64::encode_to_str(b"hello");
 /// assert_eq!(encoded, "aGVsbG8=".to_string());
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_to_str(src: &[u8]) -> String {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -345,6 +360,7 @@ pub fn encode_to_str(src: &[u8]) -> String {
 /// let encoded_string: String = base64::encode_str("hello");
 /// assert_eq!(encoded_string, "aGVsbG8=".to_string());
 /// ```
+#[cfg(feature = "alloc")]
 pub fn encode_str(src: &str) -> String {
     let dst_len = encode_len(src.len());
     let mut dst = vec![0; dst_len];
@@ -368,6 +384,7 @@ pub fn encode_str(src: &str) -> String {
 /// assert_eq!(&base64_std_decode_map, base64::DECODE_STD_MAP);
 /// assert_eq!(&base64_url_decode_map, base64::DECODE_URL_MAP);
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_map(encode_map: &[u8]) -> Vec<u8> {
     encode_map
         .iter()
@@ -474,6 +491,7 @@ pub fn decode(dst: &mut [u8], src: &[u8], decode_map: &[u8]) -> Result<usize, Ba
 /// let dst = base64::decode_str(src).unwrap();
 /// assert_eq!(dst, b"hello");
 /// ```
+#[cfg(feature = "alloc")]
 pub fn decode_str(src: &str) -> Result<Vec<u8>, Base64Error> {
     let mut dst = vec![0; decode_len(src.len())];
     let dst_size = decode(&mut dst, src.as_bytes(), DECODE_STD_MAP)?;
@@ -481,9 +499,181 @@ pub fn decode_str(src: &str) -> Result<Vec<u8>, Base64Error> {
     Ok(dst)
 }
 
+/// Decode padded base64 bytes to raw bytes, with custom map
+///
+/// - @param src: base64 bytes
+/// - @param decode_map: base64 decoding map
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64;
+/// let dst = base64::decode_bytes_with_map(b"aGk_", base64::DECODE_URL_MAP).unwrap();
+/// assert_eq!(dst, b"hi?");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_bytes_with_map(src: &[u8], decode_map: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    if src.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut dst = vec![0; decode_len(src.len())];
+    let n = decode(&mut dst, src, decode_map)?;
+    dst.truncate(n);
+    Ok(dst)
+}
+
+/// Encode bytes to base64 bytes without padding, with custom map
+///
+/// - @param src: raw bytes
+/// - @param encode_map: base64 encoding map
+/// - @return: base64 encoded bytes, without trailing `=`
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64;
+/// let encoded_bytes = base64::encode_unpadded(b"hello", base64::ENCODE_STD);
+/// assert_eq!(encoded_bytes, b"aGVsbG8");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_unpadded(src: &[u8], encode_map: &[u8]) -> Vec<u8> {
+    let mut dst = encode_bytes_with_map(src, encode_map);
+    while dst.last() == Some(&PAD_CHAR) {
+        dst.pop();
+    }
+    dst
+}
+
+/// Decode base64 bytes without padding, with custom map
+///
+/// - @param src: unpadded base64 bytes
+/// - @param decode_map: base64 decoding map
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64::{self, Base64Error};
+/// let dst = base64::decode_unpadded(b"aGVsbG8", base64::DECODE_STD_MAP).unwrap();
+/// assert_eq!(dst, b"hello");
+/// assert_eq!(
+///     base64::decode_unpadded(b"aGVsbG8=", base64::DECODE_STD_MAP),
+///     Err(Base64Error::InvalidLength)
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+pub fn decode_unpadded(src: &[u8], decode_map: &[u8]) -> Result<Vec<u8>, Base64Error> {
+    if src.len() % 4 == 1 || src.contains(&PAD_CHAR) {
+        return Err(Base64Error::InvalidLength);
+    }
+    let mut padded = src.to_vec();
+    padded.resize(src.len().div_ceil(4) * 4, PAD_CHAR);
+    decode_bytes_with_map(&padded, decode_map)
+}
+
+/// Bytes encoded per write by the formatting functions, kept on the stack
+const FMT_CHUNK_LEN: usize = 48;
+
+/// Encode bytes to base64 directly into a formatter or any `fmt::Write`
+///
+/// A non-ASCII byte of a custom map is written as the char of the same
+/// value, `U+0080` to `U+00FF`.
+///
+/// - @param w: destination of the base64 characters
+/// - @param src: raw bytes
+/// - @param encode_map: base64 encoding map
+/// - @return: error of the destination, if any
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64;
+/// use core::fmt::Write;
+/// let mut dst = String::from("Basic ");
+/// base64::encode_to_fmt(&mut dst, b"user:pass", base64::ENCODE_STD).unwrap();
+/// assert_eq!(dst, "Basic dXNlcjpwYXNz");
+/// ```
+pub fn encode_to_fmt<W: core::fmt::Write>(
+    w: &mut W,
+    src: &[u8],
+    encode_map: &[u8],
+) -> core::fmt::Result {
+    let mut buf = [0u8; FMT_CHUNK_LEN / 3 * 4];
+    for chunk in src.chunks(FMT_CHUNK_LEN) {
+        let n = encode_len(chunk.len());
+        encode(&mut buf[..n], chunk, encode_map);
+        if buf[..n].is_ascii() {
+            w.write_str(core::str::from_utf8(&buf[..n]).unwrap())?;
+        } else {
+            for &b in &buf[..n] {
+                w.write_char(char::from(b))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encode bytes to base64, appended to a string
+///
+/// - @param dst: string the base64 characters are appended to
+/// - @param src: raw bytes
+/// - @param encode_map: base64 encoding map
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64;
+/// let mut dst = String::from("token=");
+/// base64::encode_append(&mut dst, b"\xfb\xff", base64::ENCODE_URL);
+/// assert_eq!(dst, "token=-_8=");
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_append(dst: &mut String, src: &[u8], encode_map: &[u8]) {
+    dst.reserve(encode_len(src.len()));
+    // writing to a String never fails
+    encode_to_fmt(dst, src, encode_map).unwrap();
+}
+
+/// Formatting adapter writing bytes as base64 without allocating, with the
+/// encoding map to use
+///
+/// The characters are streamed, so width, fill and alignment are ignored.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::base64;
+/// let display = base64::Display(b"hello", base64::ENCODE_STD);
+/// assert_eq!(format!("{}", display), "aGVsbG8=");
+/// assert_eq!(display.to_string(), "aGVsbG8=");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Display<'a>(pub &'a [u8], pub &'a [u8]);
+
+impl core::fmt::Display for Display<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        encode_to_fmt(f, self.0, self.1)
+    }
+}
+
 #[cfg(test)]
 mod tests_base64 {
     use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::{format, string::ToString};
 
     #[test]
     fn test_encode_str_len_01() {
@@ -495,6 +685,7 @@ mod tests_base64 {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encode_str_01() {
         assert_eq!(encode_str(""), "".to_string());
         assert_eq!(encode_str("f"), "Zg==".to_string());
@@ -506,6 +697,7 @@ mod tests_base64 {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encode_str_02() {
         assert_eq!(encode_str("sure."), "c3VyZS4=".to_string());
         assert_eq!(encode_str("sure"), "c3VyZQ==".to_string());
@@ -518,18 +710,46 @@ mod tests_base64 {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_encode_str_03() {
         assert_eq!(encode_str("hello"), "aGVsbG8=".to_string());
         assert_eq!(encode_bytes(b"hello"), b"aGVsbG8=");
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
+    fn test_display() {
+        for len in [0, 1, 2, 3, 47, 48, 49, 100] {
+            let data: Vec<u8> = (0..len as u8).map(|b| b.wrapping_mul(37)).collect();
+            let expect = String::from_utf8(encode_bytes_with_map(&data, ENCODE_URL)).unwrap();
+            assert_eq!(format!("{}", Display(&data, ENCODE_URL)), expect);
+
+            let mut dst = String::from(">");
+            encode_append(&mut dst, &data, ENCODE_URL);
+            assert_eq!(dst, format!(">{}", expect));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_display_non_ascii_map() {
+        let mut map: [u8; 64] = ENCODE_STD.try_into().unwrap();
+        map[0] = 0xe9;
+        let mut dst = String::new();
+        encode_to_fmt(&mut dst, &[0, 0, 0], &map).unwrap();
+        assert_eq!(dst, "\u{e9}\u{e9}\u{e9}\u{e9}");
+        assert_eq!(format!("{:>6}", Display(b"f", &map)), "Zg==");
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_map_01() {
         let m = decode_map(ENCODE_STD);
         assert_eq!(m, DECODE_STD_MAP);
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_str_01() {
         assert_eq!(decode_str(""), Err(Base64Error::InvalidLength));
         assert_eq!(decode_str("Zg=="), Ok((b"f").to_vec()));
@@ -541,6 +761,7 @@ mod tests_base64 {
     }
 
     #[test]
+    #[cfg(feature = "alloc")]
     fn test_decode_str_02() {
         assert_eq!(decode_str("c3VyZS4="), Ok((b"sure.").to_vec()));
         assert_eq!(decode_str("c3VyZQ=="), Ok((b"sure").to_vec()));
@@ -0,0 +1,121 @@
+//! serde support for base64 fields, standard alphabet with padding.
+//!
+//! Use it with `#[serde(with = "connx::encoding::base64::serde")]` on
+//! `Vec<u8>` and `[u8; N]` fields, or with the [`option`] module on
+//! `Option<Vec<u8>>` fields. Human-readable formats such as JSON get a base64
+//! string, binary formats get the raw bytes.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::base64;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, PartialEq, Debug)]
+//! struct Config {
+//!     #[serde(with = "base64::serde")]
+//!     key: [u8; 4],
+//!     #[serde(with = "base64::serde::option")]
+//!     cert: Option<Vec<u8>>,
+//! }
+//!
+//! let config = Config { key: *b"key!", cert: Some(b"hello".to_vec()) };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"key":"a2V5IQ==","cert":"aGVsbG8="}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! ```
+
+use crate::encoding::base64;
+use crate::encoding::serde_codec::{serde_module, TextCodec};
+
+struct Base64Codec;
+
+impl TextCodec for Base64Codec {
+    const EXPECTING: &'static str = "a base64 string or bytes";
+
+    fn encode(src: &[u8]) -> String {
+        base64::encode_to_str(src)
+    }
+
+    fn decode(src: &str) -> Result<Vec<u8>, String> {
+        base64::decode_str(src).map_err(|e| e.to_string())
+    }
+}
+
+serde_module!(crate::encoding::base64::serde::Base64Codec);
+
+#[cfg(test)]
+mod tests_base64_serde {
+    use serde::{Deserialize, Serialize};
+    use serde_test::{assert_tokens, Configure, Token};
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct Keys {
+        #[serde(with = "crate::encoding::base64::serde")]
+        vec: Vec<u8>,
+        #[serde(with = "crate::encoding::base64::serde")]
+        array: [u8; 2],
+        #[serde(with = "crate::encoding::base64::serde::option")]
+        option: Option<Vec<u8>>,
+    }
+
+    fn tokens(vec: Token, array: Token, option: &[Token]) -> Vec<Token> {
+        let mut tokens = vec![
+            Token::Struct {
+                name: "Keys",
+                len: 3,
+            },
+            Token::Str("vec"),
+            vec,
+            Token::Str("array"),
+            array,
+            Token::Str("option"),
+        ];
+        tokens.extend_from_slice(option);
+        tokens.push(Token::StructEnd);
+        tokens
+    }
+
+    #[test]
+    fn test_readable() {
+        let keys = Keys {
+            vec: b"hello".to_vec(),
+            array: [0xfb, 0xff],
+            option: Some(Vec::new()),
+        };
+        let expect = tokens(
+            Token::Str("aGVsbG8="),
+            Token::Str("+/8="),
+            &[Token::Some, Token::Str("")],
+        );
+        assert_tokens(&keys.readable(), &expect);
+    }
+
+    #[test]
+    fn test_compact() {
+        let keys = Keys {
+            vec: b"hello".to_vec(),
+            array: [0xfb, 0xff],
+            option: None,
+        };
+        let expect = tokens(
+            Token::Bytes(b"hello"),
+            Token::Bytes(&[0xfb, 0xff]),
+            &[Token::None],
+        );
+        assert_tokens(&keys.compact(), &expect);
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let err = serde_json::from_str::<Keys>(r#"{"vec":"a!==","array":"+/8=","option":null}"#);
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("encoding/base64: invalid byte"));
+        let err = serde_json::from_str::<Keys>(r#"{"vec":"","array":"+/+/","option":null}"#);
+        assert!(err.unwrap_err().to_string().contains("invalid length 3"));
+    }
+}
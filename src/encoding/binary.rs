@@ -46,6 +46,229 @@
 //! assert_eq!(n, BigEndian::decode_u32(&b));
 //! ```
 
+pub mod bits;
+#[cfg(feature = "alloc")]
+pub mod structfmt;
+pub mod varint;
+
+mod buf;
+pub use buf::{Placeholder, Reader, WriteBuf, Writer};
+
+mod unaligned;
+pub use unaligned::{
+    as_bytes, mut_from_prefix, ref_from_prefix, slice_from_prefix, FromBytes, F32, F64, I128, I16,
+    I32, I64, U128, U16, U32, U64,
+};
+
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use io::{ReadBytesExt, WriteBytesExt};
+
+/// Errors of the checked `try_*` methods, the cursors, [`varint`] and
+/// [`bits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The slice is shorter than the value
+    UnexpectedEof { needed: usize, available: usize },
+    /// The slice is shorter than the bit field, counted in bits
+    UnexpectedEofBits { needed: usize, available: usize },
+    /// A variable-length integer, LEB128 or Exp-Golomb, does not fit in 64
+    /// bits
+    VarintOverflow,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnexpectedEof { needed, available } => write!(
+                f,
+                "encoding/binary: unexpected end of input, needed {} bytes, {} available",
+                needed, available
+            ),
+            Self::UnexpectedEofBits { needed, available } => write!(
+                f,
+                "encoding/binary: unexpected end of input, needed {} bits, {} available",
+                needed, available
+            ),
+            Self::VarintOverflow => write!(
+                f,
+                "encoding/binary: variable-length integer overflows 64 bits"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Check that `available` bytes hold a value of `needed` bytes
+fn check_len(needed: usize, available: usize) -> Result<(), Error> {
+    if available < needed {
+        return Err(Error::UnexpectedEof { needed, available });
+    }
+    Ok(())
+}
+
+/// Define the checked `try_*` methods of [`ByteOrder`] on top of the
+/// panicking ones
+macro_rules! try_methods {
+    ($($ty:ty, $size:literal => $try_encode:ident = $encode:ident, $try_decode:ident = $decode:ident;)*) => {
+        $(
+            #[doc = concat!("Like [`", stringify!($encode), "`](Self::", stringify!($encode), "), an error instead of a panic when `b` is too short")]
+            fn $try_encode(n: $ty, b: &mut [u8]) -> Result<(), Error> {
+                check_len($size, b.len())?;
+                Self::$encode(n, b);
+                Ok(())
+            }
+
+            #[doc = concat!("Like [`", stringify!($decode), "`](Self::", stringify!($decode), "), an error instead of a panic when `b` is too short")]
+            fn $try_decode(b: &[u8]) -> Result<$ty, Error> {
+                check_len($size, b.len())?;
+                Ok(Self::$decode(b))
+            }
+        )*
+    };
+}
+
+/// Declare the slice methods of [`ByteOrder`] for every width
+macro_rules! slice_methods {
+    ($($ty:ident, $encode:ident, $decode:ident => $read:ident, $write:ident, $from:ident;)*) => {
+        $(
+            #[doc = concat!("Decode `", stringify!($ty), "` values from `src` into `dst`")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if `src.len()` is not `dst.len()` times the value size.
+            fn $read(src: &[u8], dst: &mut [$ty]) {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                assert_eq!(src.len(), dst.len() * SIZE, "encoding/binary: invalid slice length");
+                for (n, chunk) in dst.iter_mut().zip(src.chunks_exact(SIZE)) {
+                    *n = Self::$decode(chunk);
+                }
+            }
+
+            #[doc = concat!("Encode `", stringify!($ty), "` values from `src` into `dst`")]
+            ///
+            /// # Panics
+            ///
+            /// Panics if `dst.len()` is not `src.len()` times the value size.
+            fn $write(src: &[$ty], dst: &mut [u8]) {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                assert_eq!(dst.len(), src.len() * SIZE, "encoding/binary: invalid slice length");
+                for (chunk, &n) in dst.chunks_exact_mut(SIZE).zip(src) {
+                    Self::$encode(n, chunk);
+                }
+            }
+
+            #[doc = concat!("Convert `", stringify!($ty), "` values between this byte order and the native one, in place")]
+            fn $from(values: &mut [$ty]) {
+                for n in values {
+                    let mut b = [0; core::mem::size_of::<$ty>()];
+                    b.copy_from_slice(&n.to_ne_bytes());
+                    *n = Self::$decode(&b);
+                }
+            }
+        )*
+    };
+}
+
+/// Implement the slice methods of [`ByteOrder`], with `$from_bytes` and
+/// `$to_bytes` the conversions of the byte order and `$endian` its
+/// `target_endian`
+///
+/// The loops work on fixed size chunks, so the compiler turns them into
+/// vector byte shuffles, or into a copy when the byte order is native.
+macro_rules! impl_slice_methods {
+    ($from_bytes:ident, $to_bytes:ident, $endian:literal) => {
+        impl_slice_methods!(@impl $from_bytes, $to_bytes, $endian;
+            u16 => read_u16_into, write_u16_from_slice, from_slice_u16;
+            i16 => read_i16_into, write_i16_from_slice, from_slice_i16;
+            u32 => read_u32_into, write_u32_from_slice, from_slice_u32;
+            i32 => read_i32_into, write_i32_from_slice, from_slice_i32;
+            u64 => read_u64_into, write_u64_from_slice, from_slice_u64;
+            i64 => read_i64_into, write_i64_from_slice, from_slice_i64;
+            u128 => read_u128_into, write_u128_from_slice, from_slice_u128;
+            i128 => read_i128_into, write_i128_from_slice, from_slice_i128;
+            f32 => read_f32_into, write_f32_from_slice, from_slice_f32;
+            f64 => read_f64_into, write_f64_from_slice, from_slice_f64;
+        );
+    };
+    (@impl $from_bytes:ident, $to_bytes:ident, $endian:literal;
+        $($ty:ident => $read:ident, $write:ident, $from:ident;)*) => {
+        $(
+            fn $read(src: &[u8], dst: &mut [$ty]) {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                assert_eq!(src.len(), dst.len() * SIZE, "encoding/binary: invalid slice length");
+                for (n, chunk) in dst.iter_mut().zip(src.chunks_exact(SIZE)) {
+                    *n = $ty::$from_bytes(chunk.try_into().unwrap());
+                }
+            }
+
+            fn $write(src: &[$ty], dst: &mut [u8]) {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+                assert_eq!(dst.len(), src.len() * SIZE, "encoding/binary: invalid slice length");
+                for (chunk, n) in dst.chunks_exact_mut(SIZE).zip(src) {
+                    chunk.copy_from_slice(&n.$to_bytes());
+                }
+            }
+
+            fn $from(values: &mut [$ty]) {
+                if cfg!(target_endian = $endian) {
+                    return;
+                }
+                for n in values {
+                    *n = $ty::$from_bytes(n.to_ne_bytes());
+                }
+            }
+        )*
+    };
+}
+
+/// Byte order of integers and floats in a byte slice
+///
+/// The slice must hold at least the size of the value, extra bytes are left
+/// untouched. The `u16`, `u32` and `u64` methods are implemented by each
+/// byte order, all the others are provided on top of them; [`LittleEndian`]
+/// and [`BigEndian`] override them with faster versions.
+///
+/// The `try_*` methods return [`Error::UnexpectedEof`] instead, for input
+/// that may be truncated.
+///
+/// The `read_*_into`, `write_*_from_slice` and `from_slice_*` methods convert
+/// whole slices at once, much faster than a loop of single values.
+///
+/// # Examples
+///
+/// Checked decode basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{ByteOrder, Error, LittleEndian};
+/// let packet = [0x01, 0x02, 0x03];
+/// assert_eq!(LittleEndian::try_decode_u16(&packet), Ok(0x0201));
+/// assert_eq!(
+///     LittleEndian::try_decode_u32(&packet),
+///     Err(Error::UnexpectedEof { needed: 4, available: 3 })
+/// );
+/// ```
+///
+/// Slices basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{BigEndian, ByteOrder, LittleEndian};
+/// let mut values = [0u16; 2];
+/// LittleEndian::read_u16_into(&[0x34, 0x12, 0x78, 0x56], &mut values);
+/// assert_eq!(values, [0x1234, 0x5678]);
+///
+/// let mut b = [0; 8];
+/// BigEndian::write_u32_from_slice(&[1, 2], &mut b);
+/// assert_eq!(b, [0, 0, 0, 1, 0, 0, 0, 2]);
+///
+/// // values received in big endian, fixed up in place
+/// let mut values = [f64::from_bits(u64::from_ne_bytes(1.5f64.to_be_bytes()))];
+/// BigEndian::from_slice_f64(&mut values);
+/// assert_eq!(values, [1.5]);
+/// ```
 pub trait ByteOrder {
     fn encode_u16(n: u16, b: &mut [u8]);
     fn encode_u32(n: u32, b: &mut [u8]);
@@ -53,6 +276,343 @@ pub trait ByteOrder {
     fn decode_u16(b: &[u8]) -> u16;
     fn decode_u32(b: &[u8]) -> u32;
     fn decode_u64(b: &[u8]) -> u64;
+
+    /// Encode a `u128` as two `u64` halves in this byte order
+    fn encode_u128(n: u128, b: &mut [u8]) {
+        let (first, second) = if is_big_endian::<Self>() {
+            ((n >> 64) as u64, n as u64)
+        } else {
+            (n as u64, (n >> 64) as u64)
+        };
+        Self::encode_u64(first, &mut b[..8]);
+        Self::encode_u64(second, &mut b[8..16]);
+    }
+
+    fn decode_u128(b: &[u8]) -> u128 {
+        let first = u128::from(Self::decode_u64(&b[..8]));
+        let second = u128::from(Self::decode_u64(&b[8..16]));
+        if is_big_endian::<Self>() {
+            first << 64 | second
+        } else {
+            second << 64 | first
+        }
+    }
+
+    /// Encode the low `nbytes` bytes of an unsigned integer
+    ///
+    /// - @param n: value, must fit in `nbytes` bytes
+    /// - @param nbytes: width, 1 to 8
+    /// - @param b: at least `nbytes` bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8 or `n` does not fit.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::{BigEndian, ByteOrder, LittleEndian};
+    /// let mut b = [0; 3];
+    /// BigEndian::encode_uint(0x123456, 3, &mut b);
+    /// assert_eq!(b, [0x12, 0x34, 0x56]);
+    /// LittleEndian::encode_uint(0x123456, 3, &mut b);
+    /// assert_eq!(b, [0x56, 0x34, 0x12]);
+    /// ```
+    fn encode_uint(n: u64, nbytes: usize, b: &mut [u8]) {
+        check_uint(n, nbytes);
+        let mut buf = [0; 8];
+        Self::encode_u64(n, &mut buf);
+        if is_big_endian::<Self>() {
+            b[..nbytes].copy_from_slice(&buf[8 - nbytes..]);
+        } else {
+            b[..nbytes].copy_from_slice(&buf[..nbytes]);
+        }
+    }
+
+    /// Decode an unsigned integer of `nbytes` bytes
+    ///
+    /// - @param b: at least `nbytes` bytes
+    /// - @param nbytes: width, 1 to 8
+    /// - @return: value, zero extended
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::{BigEndian, ByteOrder};
+    /// assert_eq!(BigEndian::decode_uint(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc], 6), 0x123456789abc);
+    /// ```
+    fn decode_uint(b: &[u8], nbytes: usize) -> u64 {
+        check_uint(0, nbytes);
+        let mut buf = [0; 8];
+        if is_big_endian::<Self>() {
+            buf[8 - nbytes..].copy_from_slice(&b[..nbytes]);
+        } else {
+            buf[..nbytes].copy_from_slice(&b[..nbytes]);
+        }
+        Self::decode_u64(&buf)
+    }
+
+    fn encode_i16(n: i16, b: &mut [u8]) {
+        Self::encode_u16(n as u16, b)
+    }
+
+    fn encode_i32(n: i32, b: &mut [u8]) {
+        Self::encode_u32(n as u32, b)
+    }
+
+    fn encode_i64(n: i64, b: &mut [u8]) {
+        Self::encode_u64(n as u64, b)
+    }
+
+    fn encode_i128(n: i128, b: &mut [u8]) {
+        Self::encode_u128(n as u128, b)
+    }
+
+    fn decode_i16(b: &[u8]) -> i16 {
+        Self::decode_u16(b) as i16
+    }
+
+    fn decode_i32(b: &[u8]) -> i32 {
+        Self::decode_u32(b) as i32
+    }
+
+    fn decode_i64(b: &[u8]) -> i64 {
+        Self::decode_u64(b) as i64
+    }
+
+    fn decode_i128(b: &[u8]) -> i128 {
+        Self::decode_u128(b) as i128
+    }
+
+    /// Encode the IEEE-754 bits of a float
+    fn encode_f32(n: f32, b: &mut [u8]) {
+        Self::encode_u32(n.to_bits(), b)
+    }
+
+    fn encode_f64(n: f64, b: &mut [u8]) {
+        Self::encode_u64(n.to_bits(), b)
+    }
+
+    fn decode_f32(b: &[u8]) -> f32 {
+        f32::from_bits(Self::decode_u32(b))
+    }
+
+    fn decode_f64(b: &[u8]) -> f64 {
+        f64::from_bits(Self::decode_u64(b))
+    }
+
+    /// Encode a float as IEEE-754 half precision, see [`f32_to_f16_bits`]
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::{ByteOrder, LittleEndian};
+    /// let mut b = [0; 2];
+    /// LittleEndian::encode_f16(1.5, &mut b);
+    /// assert_eq!(b, [0x00, 0x3e]);
+    /// assert_eq!(LittleEndian::decode_f16(&b), 1.5);
+    /// ```
+    fn encode_f16(n: f32, b: &mut [u8]) {
+        Self::encode_u16(f32_to_f16_bits(n), b)
+    }
+
+    /// Decode an IEEE-754 half precision float, exactly
+    fn decode_f16(b: &[u8]) -> f32 {
+        f16_bits_to_f32(Self::decode_u16(b))
+    }
+
+    try_methods! {
+        u16, 2 => try_encode_u16 = encode_u16, try_decode_u16 = decode_u16;
+        u32, 4 => try_encode_u32 = encode_u32, try_decode_u32 = decode_u32;
+        u64, 8 => try_encode_u64 = encode_u64, try_decode_u64 = decode_u64;
+        u128, 16 => try_encode_u128 = encode_u128, try_decode_u128 = decode_u128;
+        i16, 2 => try_encode_i16 = encode_i16, try_decode_i16 = decode_i16;
+        i32, 4 => try_encode_i32 = encode_i32, try_decode_i32 = decode_i32;
+        i64, 8 => try_encode_i64 = encode_i64, try_decode_i64 = decode_i64;
+        i128, 16 => try_encode_i128 = encode_i128, try_decode_i128 = decode_i128;
+        f32, 2 => try_encode_f16 = encode_f16, try_decode_f16 = decode_f16;
+        f32, 4 => try_encode_f32 = encode_f32, try_decode_f32 = decode_f32;
+        f64, 8 => try_encode_f64 = encode_f64, try_decode_f64 = decode_f64;
+    }
+
+    /// Like [`encode_uint`](Self::encode_uint), an error instead of a panic
+    /// when `b` is too short
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8 or `n` does not fit.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::{BigEndian, ByteOrder, Error};
+    /// let mut b = [0; 2];
+    /// assert_eq!(
+    ///     BigEndian::try_encode_uint(0x123456, 3, &mut b),
+    ///     Err(Error::UnexpectedEof { needed: 3, available: 2 })
+    /// );
+    /// ```
+    fn try_encode_uint(n: u64, nbytes: usize, b: &mut [u8]) -> Result<(), Error> {
+        check_len(nbytes, b.len())?;
+        Self::encode_uint(n, nbytes, b);
+        Ok(())
+    }
+
+    /// Like [`decode_uint`](Self::decode_uint), an error instead of a panic
+    /// when `b` is too short
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8.
+    fn try_decode_uint(b: &[u8], nbytes: usize) -> Result<u64, Error> {
+        check_len(nbytes, b.len())?;
+        Ok(Self::decode_uint(b, nbytes))
+    }
+
+    slice_methods! {
+        u16, encode_u16, decode_u16 => read_u16_into, write_u16_from_slice, from_slice_u16;
+        i16, encode_i16, decode_i16 => read_i16_into, write_i16_from_slice, from_slice_i16;
+        u32, encode_u32, decode_u32 => read_u32_into, write_u32_from_slice, from_slice_u32;
+        i32, encode_i32, decode_i32 => read_i32_into, write_i32_from_slice, from_slice_i32;
+        u64, encode_u64, decode_u64 => read_u64_into, write_u64_from_slice, from_slice_u64;
+        i64, encode_i64, decode_i64 => read_i64_into, write_i64_from_slice, from_slice_i64;
+        u128, encode_u128, decode_u128 => read_u128_into, write_u128_from_slice, from_slice_u128;
+        i128, encode_i128, decode_i128 => read_i128_into, write_i128_from_slice, from_slice_i128;
+        f32, encode_f32, decode_f32 => read_f32_into, write_f32_from_slice, from_slice_f32;
+        f64, encode_f64, decode_f64 => read_f64_into, write_f64_from_slice, from_slice_f64;
+    }
+}
+
+/// Whether a byte order puts the most significant byte first, for the
+/// provided methods of [`ByteOrder`]
+fn is_big_endian<E: ByteOrder + ?Sized>() -> bool {
+    let mut b = [0; 2];
+    E::encode_u16(1, &mut b);
+    b[0] == 0
+}
+
+/// Check the arguments of `encode_uint`
+fn check_uint(n: u64, nbytes: usize) {
+    assert!(
+        (1..=8).contains(&nbytes),
+        "encoding/binary: invalid integer width"
+    );
+    assert!(
+        nbytes == 8 || n >> (nbytes * 8) == 0,
+        "encoding/binary: integer too large for its width"
+    );
+}
+
+/// Convert a float to the bits of the nearest IEEE-754 half precision value
+///
+/// Ties round to even, values too large become infinity and NaN stays NaN.
+///
+/// - @param x: float
+/// - @return: half precision bits
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::f32_to_f16_bits;
+/// assert_eq!(f32_to_f16_bits(1.0), 0x3c00);
+/// assert_eq!(f32_to_f16_bits(65504.0), 0x7bff);
+/// assert_eq!(f32_to_f16_bits(1e6), 0x7c00);
+/// ```
+pub fn f32_to_f16_bits(x: f32) -> u16 {
+    to_f16_bits(u64::from(x.to_bits()), 23, 8)
+}
+
+/// Round a double straight to half precision bits, like CPython's
+/// `PyFloat_Pack2`; going through `f32` would round twice
+#[cfg(feature = "alloc")]
+pub(crate) fn f64_to_f16_bits(x: f64) -> u16 {
+    to_f16_bits(x.to_bits(), 52, 11)
+}
+
+/// Round the bits of a binary float to half precision, ties to even
+///
+/// - @param bits: float bits, sign at bit `man_bits + exp_bits`
+/// - @param man_bits: stored mantissa width, more than 10
+/// - @param exp_bits: exponent width, more than 5
+/// - @return: half precision bits
+fn to_f16_bits(bits: u64, man_bits: u32, exp_bits: u32) -> u16 {
+    let exp_max = (1 << exp_bits) - 1;
+    let sign = ((bits >> (man_bits + exp_bits - 15)) & 0x8000) as u16;
+    let exp = ((bits >> man_bits) & exp_max) as i32;
+    let man = bits & ((1 << man_bits) - 1);
+    let drop = man_bits - 10;
+
+    if exp == exp_max as i32 {
+        // infinity, or NaN kept quiet with its top payload bits
+        let nan = if man != 0 {
+            0x0200 | (man >> drop) as u16
+        } else {
+            0
+        };
+        return sign | 0x7C00 | nan;
+    }
+    let exp = exp - (exp_max >> 1) as i32 + 15;
+    if exp >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    // keep 10 bits of mantissa, fewer for a subnormal result
+    let (half, man, shift) = if exp > 0 {
+        (((exp as u64) << 10) | (man >> drop), man, drop)
+    } else if exp >= -10 {
+        let man = man | 1 << man_bits;
+        let shift = (drop as i32 + 1 - exp) as u32;
+        (man >> shift, man, shift)
+    } else {
+        return sign;
+    };
+    let rem = man & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    // a carry out of the mantissa correctly bumps the exponent
+    let round = rem > halfway || (rem == halfway && half & 1 == 1);
+    sign | (half + u64::from(round)) as u16
+}
+
+/// Convert IEEE-754 half precision bits to a float, exactly
+///
+/// - @param h: half precision bits
+/// - @return: float
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::f16_bits_to_f32;
+/// assert_eq!(f16_bits_to_f32(0x3c00), 1.0);
+/// assert_eq!(f16_bits_to_f32(0xc000), -2.0);
+/// assert!(f16_bits_to_f32(0x7e00).is_nan());
+/// ```
+pub fn f16_bits_to_f32(h: u16) -> f32 {
+    let sign = u32::from(h & 0x8000) << 16;
+    let exp = u32::from(h >> 10) & 0x1F;
+    let man = u32::from(h & 0x3FF);
+    let bits = match exp {
+        // zero or subnormal, man * 2^-24 is exact
+        0 => sign | (man as f32 * (1.0 / 16_777_216.0)).to_bits(),
+        0x1F => sign | 0x7F80_0000 | (man << 13),
+        _ => sign | ((exp + 127 - 15) << 23) | (man << 13),
+    };
+    f32::from_bits(bits)
 }
 
 /// Little Endian implemented
@@ -82,6 +642,17 @@ impl ByteOrder for LittleEndian {
         b[7] = (n >> 56) as u8;
     }
 
+    fn encode_u128(n: u128, b: &mut [u8]) {
+        b[..16].copy_from_slice(&n.to_le_bytes());
+    }
+
+    fn encode_uint(n: u64, nbytes: usize, b: &mut [u8]) {
+        check_uint(n, nbytes);
+        for (i, x) in b[..nbytes].iter_mut().enumerate() {
+            *x = (n >> (i * 8)) as u8;
+        }
+    }
+
     fn decode_u16(b: &[u8]) -> u16 {
         (b[0] as u16) | ((b[1] as u16) << 8)
     }
@@ -100,6 +671,20 @@ impl ByteOrder for LittleEndian {
             | ((b[6] as u64) << 48)
             | ((b[7] as u64) << 56)
     }
+
+    fn decode_u128(b: &[u8]) -> u128 {
+        u128::from_le_bytes(b[..16].try_into().unwrap())
+    }
+
+    fn decode_uint(b: &[u8], nbytes: usize) -> u64 {
+        check_uint(0, nbytes);
+        b[..nbytes]
+            .iter()
+            .rev()
+            .fold(0, |n, &x| (n << 8) | u64::from(x))
+    }
+
+    impl_slice_methods!(from_le_bytes, to_le_bytes, "little");
 }
 
 /// Big Endian implemented
@@ -129,6 +714,17 @@ impl ByteOrder for BigEndian {
         b[7] = n as u8;
     }
 
+    fn encode_u128(n: u128, b: &mut [u8]) {
+        b[..16].copy_from_slice(&n.to_be_bytes());
+    }
+
+    fn encode_uint(n: u64, nbytes: usize, b: &mut [u8]) {
+        check_uint(n, nbytes);
+        for (i, x) in b[..nbytes].iter_mut().rev().enumerate() {
+            *x = (n >> (i * 8)) as u8;
+        }
+    }
+
     fn decode_u16(b: &[u8]) -> u16 {
         (b[1] as u16) | ((b[0] as u16) << 8)
     }
@@ -147,26 +743,186 @@ impl ByteOrder for BigEndian {
             | ((b[1] as u64) << 48)
             | ((b[0] as u64) << 56)
     }
+
+    fn decode_u128(b: &[u8]) -> u128 {
+        u128::from_be_bytes(b[..16].try_into().unwrap())
+    }
+
+    fn decode_uint(b: &[u8], nbytes: usize) -> u64 {
+        check_uint(0, nbytes);
+        b[..nbytes].iter().fold(0, |n, &x| (n << 8) | u64::from(x))
+    }
+
+    impl_slice_methods!(from_be_bytes, to_be_bytes, "big");
+}
+
+/// Byte order of the target, [`LittleEndian`] or [`BigEndian`]
+#[cfg(target_endian = "little")]
+pub type NativeEndian = LittleEndian;
+
+/// Byte order of the target, [`LittleEndian`] or [`BigEndian`]
+#[cfg(target_endian = "big")]
+pub type NativeEndian = BigEndian;
+
+/// Byte order of network protocols
+pub type NetworkEndian = BigEndian;
+
+/// Define methods of [`Endianness`] forwarding to the [`ByteOrder`] method
+/// of the same name
+macro_rules! endianness_methods {
+    ($(fn $name:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)?;)*) => {
+        $(
+            #[doc = concat!("See [`ByteOrder::", stringify!($name), "`]")]
+            pub fn $name(self, $($arg: $ty),*) $(-> $ret)? {
+                match self {
+                    Self::Little => LittleEndian::$name($($arg),*),
+                    Self::Big => BigEndian::$name($($arg),*),
+                }
+            }
+        )*
+    };
+}
+
+/// Byte order chosen at run time, for protocols carrying it in a flag
+///
+/// The methods are the ones of [`ByteOrder`], dispatching to
+/// [`LittleEndian`] or [`BigEndian`].
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::Endianness;
+/// // the RTPS submessage flag E is set for little endian
+/// let flags = 0x01;
+/// let endianness = if flags & 0x01 != 0 { Endianness::Little } else { Endianness::Big };
+/// assert_eq!(endianness.decode_u32(&[0x78, 0x56, 0x34, 0x12]), 0x12345678);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// Byte order of the target
+    pub const fn native() -> Self {
+        if cfg!(target_endian = "little") {
+            Self::Little
+        } else {
+            Self::Big
+        }
+    }
+
+    endianness_methods! {
+        fn encode_u16(n: u16, b: &mut [u8]);
+        fn encode_u32(n: u32, b: &mut [u8]);
+        fn encode_u64(n: u64, b: &mut [u8]);
+        fn encode_u128(n: u128, b: &mut [u8]);
+        fn encode_i16(n: i16, b: &mut [u8]);
+        fn encode_i32(n: i32, b: &mut [u8]);
+        fn encode_i64(n: i64, b: &mut [u8]);
+        fn encode_i128(n: i128, b: &mut [u8]);
+        fn encode_f16(n: f32, b: &mut [u8]);
+        fn encode_f32(n: f32, b: &mut [u8]);
+        fn encode_f64(n: f64, b: &mut [u8]);
+        fn encode_uint(n: u64, nbytes: usize, b: &mut [u8]);
+        fn decode_u16(b: &[u8]) -> u16;
+        fn decode_u32(b: &[u8]) -> u32;
+        fn decode_u64(b: &[u8]) -> u64;
+        fn decode_u128(b: &[u8]) -> u128;
+        fn decode_i16(b: &[u8]) -> i16;
+        fn decode_i32(b: &[u8]) -> i32;
+        fn decode_i64(b: &[u8]) -> i64;
+        fn decode_i128(b: &[u8]) -> i128;
+        fn decode_f16(b: &[u8]) -> f32;
+        fn decode_f32(b: &[u8]) -> f32;
+        fn decode_f64(b: &[u8]) -> f64;
+        fn decode_uint(b: &[u8], nbytes: usize) -> u64;
+        fn try_encode_u16(n: u16, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_u32(n: u32, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_u64(n: u64, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_u128(n: u128, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_i16(n: i16, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_i32(n: i32, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_i64(n: i64, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_i128(n: i128, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_f16(n: f32, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_f32(n: f32, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_f64(n: f64, b: &mut [u8]) -> Result<(), Error>;
+        fn try_encode_uint(n: u64, nbytes: usize, b: &mut [u8]) -> Result<(), Error>;
+        fn try_decode_u16(b: &[u8]) -> Result<u16, Error>;
+        fn try_decode_u32(b: &[u8]) -> Result<u32, Error>;
+        fn try_decode_u64(b: &[u8]) -> Result<u64, Error>;
+        fn try_decode_u128(b: &[u8]) -> Result<u128, Error>;
+        fn try_decode_i16(b: &[u8]) -> Result<i16, Error>;
+        fn try_decode_i32(b: &[u8]) -> Result<i32, Error>;
+        fn try_decode_i64(b: &[u8]) -> Result<i64, Error>;
+        fn try_decode_i128(b: &[u8]) -> Result<i128, Error>;
+        fn try_decode_f16(b: &[u8]) -> Result<f32, Error>;
+        fn try_decode_f32(b: &[u8]) -> Result<f32, Error>;
+        fn try_decode_f64(b: &[u8]) -> Result<f64, Error>;
+        fn try_decode_uint(b: &[u8], nbytes: usize) -> Result<u64, Error>;
+        fn read_u16_into(src: &[u8], dst: &mut [u16]);
+        fn read_i16_into(src: &[u8], dst: &mut [i16]);
+        fn read_u32_into(src: &[u8], dst: &mut [u32]);
+        fn read_i32_into(src: &[u8], dst: &mut [i32]);
+        fn read_u64_into(src: &[u8], dst: &mut [u64]);
+        fn read_i64_into(src: &[u8], dst: &mut [i64]);
+        fn read_u128_into(src: &[u8], dst: &mut [u128]);
+        fn read_i128_into(src: &[u8], dst: &mut [i128]);
+        fn read_f32_into(src: &[u8], dst: &mut [f32]);
+        fn read_f64_into(src: &[u8], dst: &mut [f64]);
+        fn write_u16_from_slice(src: &[u16], dst: &mut [u8]);
+        fn write_i16_from_slice(src: &[i16], dst: &mut [u8]);
+        fn write_u32_from_slice(src: &[u32], dst: &mut [u8]);
+        fn write_i32_from_slice(src: &[i32], dst: &mut [u8]);
+        fn write_u64_from_slice(src: &[u64], dst: &mut [u8]);
+        fn write_i64_from_slice(src: &[i64], dst: &mut [u8]);
+        fn write_u128_from_slice(src: &[u128], dst: &mut [u8]);
+        fn write_i128_from_slice(src: &[i128], dst: &mut [u8]);
+        fn write_f32_from_slice(src: &[f32], dst: &mut [u8]);
+        fn write_f64_from_slice(src: &[f64], dst: &mut [u8]);
+        fn from_slice_u16(values: &mut [u16]);
+        fn from_slice_i16(values: &mut [i16]);
+        fn from_slice_u32(values: &mut [u32]);
+        fn from_slice_i32(values: &mut [i32]);
+        fn from_slice_u64(values: &mut [u64]);
+        fn from_slice_i64(values: &mut [i64]);
+        fn from_slice_u128(values: &mut [u128]);
+        fn from_slice_i128(values: &mut [i128]);
+        fn from_slice_f32(values: &mut [f32]);
+        fn from_slice_f64(values: &mut [f64]);
+    }
+}
+
+impl Default for Endianness {
+    fn default() -> Self {
+        Self::native()
+    }
 }
 
 #[cfg(test)]
 mod tests_binary {
     use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::string::ToString;
 
     #[test]
     fn test() {
         let n = 0x1234;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x34, 0x12]);
+        assert_eq!(b, [0x34, 0x12]);
     }
 
     #[test]
     fn test_little_u16_00() {
         let n = 0xcf86;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x86, 0xcf]);
+        assert_eq!(b, [0x86, 0xcf]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -174,9 +930,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_01() {
         let n = 0x1272;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x72, 0x12]);
+        assert_eq!(b, [0x72, 0x12]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -184,9 +940,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_02() {
         let n = 0xa2d7;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xd7, 0xa2]);
+        assert_eq!(b, [0xd7, 0xa2]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -194,9 +950,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_03() {
         let n = 0x3cc2;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xc2, 0x3c]);
+        assert_eq!(b, [0xc2, 0x3c]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -204,9 +960,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_04() {
         let n = 0xbbdb;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xdb, 0xbb]);
+        assert_eq!(b, [0xdb, 0xbb]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -214,9 +970,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_05() {
         let n = 0x2970;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x70, 0x29]);
+        assert_eq!(b, [0x70, 0x29]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -224,9 +980,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_06() {
         let n = 0xd39f;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x9f, 0xd3]);
+        assert_eq!(b, [0x9f, 0xd3]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -234,9 +990,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_07() {
         let n = 0xe6b4;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xb4, 0xe6]);
+        assert_eq!(b, [0xb4, 0xe6]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -244,9 +1000,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_08() {
         let n = 0x8bb1;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xb1, 0x8b]);
+        assert_eq!(b, [0xb1, 0x8b]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -254,9 +1010,9 @@ mod tests_binary {
     #[test]
     fn test_little_u16_09() {
         let n = 0x6e28;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         LittleEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x28, 0x6e]);
+        assert_eq!(b, [0x28, 0x6e]);
         let n2 = LittleEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -264,9 +1020,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_00() {
         let n = 0x83e4f98d;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x8d, 0xf9, 0xe4, 0x83]);
+        assert_eq!(b, [0x8d, 0xf9, 0xe4, 0x83]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -274,9 +1030,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_01() {
         let n = 0xd04ab55f;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x5f, 0xb5, 0x4a, 0xd0]);
+        assert_eq!(b, [0x5f, 0xb5, 0x4a, 0xd0]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -284,9 +1040,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_02() {
         let n = 0x36d9ff45;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x45, 0xff, 0xd9, 0x36]);
+        assert_eq!(b, [0x45, 0xff, 0xd9, 0x36]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -294,9 +1050,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_03() {
         let n = 0x6172bfe3;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0xe3, 0xbf, 0x72, 0x61]);
+        assert_eq!(b, [0xe3, 0xbf, 0x72, 0x61]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -304,9 +1060,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_04() {
         let n = 0x516c42b0;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0xb0, 0x42, 0x6c, 0x51]);
+        assert_eq!(b, [0xb0, 0x42, 0x6c, 0x51]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -314,9 +1070,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_05() {
         let n = 0x78092a35;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x35, 0x2a, 0x09, 0x78]);
+        assert_eq!(b, [0x35, 0x2a, 0x09, 0x78]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -324,9 +1080,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_06() {
         let n = 0x4874ed16;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x16, 0xed, 0x74, 0x48]);
+        assert_eq!(b, [0x16, 0xed, 0x74, 0x48]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -334,9 +1090,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_07() {
         let n = 0x4b08b92b;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x2b, 0xb9, 0x08, 0x4b]);
+        assert_eq!(b, [0x2b, 0xb9, 0x08, 0x4b]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -344,9 +1100,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_08() {
         let n = 0xadd87e4a;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x4a, 0x7e, 0xd8, 0xad]);
+        assert_eq!(b, [0x4a, 0x7e, 0xd8, 0xad]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -354,9 +1110,9 @@ mod tests_binary {
     #[test]
     fn test_little_u32_09() {
         let n = 0x37f317c5;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         LittleEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0xc5, 0x17, 0xf3, 0x37]);
+        assert_eq!(b, [0xc5, 0x17, 0xf3, 0x37]);
         let n2 = LittleEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -364,9 +1120,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_00() {
         let n = 0x1a02070f169c1121;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x21, 0x11, 0x9c, 0x16, 0x0f, 0x07, 0x02, 0x1a]);
+        assert_eq!(b, [0x21, 0x11, 0x9c, 0x16, 0x0f, 0x07, 0x02, 0x1a]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -374,9 +1130,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_01() {
         let n = 0x2e3108dabb158644;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x44, 0x86, 0x15, 0xbb, 0xda, 0x08, 0x31, 0x2e]);
+        assert_eq!(b, [0x44, 0x86, 0x15, 0xbb, 0xda, 0x08, 0x31, 0x2e]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -384,9 +1140,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_02() {
         let n = 0xc90bd268b68e6a3f;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x3f, 0x6a, 0x8e, 0xb6, 0x68, 0xd2, 0x0b, 0xc9]);
+        assert_eq!(b, [0x3f, 0x6a, 0x8e, 0xb6, 0x68, 0xd2, 0x0b, 0xc9]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -394,9 +1150,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_03() {
         let n = 0x6e661e92759805f5;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xf5, 0x05, 0x98, 0x75, 0x92, 0x1e, 0x66, 0x6e]);
+        assert_eq!(b, [0xf5, 0x05, 0x98, 0x75, 0x92, 0x1e, 0x66, 0x6e]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -404,9 +1160,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_04() {
         let n = 0xa584c47f2cdf5b8a;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x8a, 0x5b, 0xdf, 0x2c, 0x7f, 0xc4, 0x84, 0xa5]);
+        assert_eq!(b, [0x8a, 0x5b, 0xdf, 0x2c, 0x7f, 0xc4, 0x84, 0xa5]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -414,9 +1170,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_05() {
         let n = 0x2606cd2b57d29245;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x45, 0x92, 0xd2, 0x57, 0x2b, 0xcd, 0x06, 0x26]);
+        assert_eq!(b, [0x45, 0x92, 0xd2, 0x57, 0x2b, 0xcd, 0x06, 0x26]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -424,9 +1180,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_06() {
         let n = 0x6054502fc5d6d268;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x68, 0xd2, 0xd6, 0xc5, 0x2f, 0x50, 0x54, 0x60]);
+        assert_eq!(b, [0x68, 0xd2, 0xd6, 0xc5, 0x2f, 0x50, 0x54, 0x60]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -434,9 +1190,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_07() {
         let n = 0x1a714cf86b83d0e2;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xe2, 0xd0, 0x83, 0x6b, 0xf8, 0x4c, 0x71, 0x1a]);
+        assert_eq!(b, [0xe2, 0xd0, 0x83, 0x6b, 0xf8, 0x4c, 0x71, 0x1a]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -444,9 +1200,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_08() {
         let n = 0xeec34c367674cb74;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x74, 0xcb, 0x74, 0x76, 0x36, 0x4c, 0xc3, 0xee]);
+        assert_eq!(b, [0x74, 0xcb, 0x74, 0x76, 0x36, 0x4c, 0xc3, 0xee]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -454,9 +1210,9 @@ mod tests_binary {
     #[test]
     fn test_little_u64_09() {
         let n = 0xd92e17f7b068d9db;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         LittleEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xdb, 0xd9, 0x68, 0xb0, 0xf7, 0x17, 0x2e, 0xd9]);
+        assert_eq!(b, [0xdb, 0xd9, 0x68, 0xb0, 0xf7, 0x17, 0x2e, 0xd9]);
         let n2 = LittleEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -464,9 +1220,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_00() {
         let n = 0xce41;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xce, 0x41]);
+        assert_eq!(b, [0xce, 0x41]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -474,9 +1230,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_01() {
         let n = 0x7317;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x73, 0x17]);
+        assert_eq!(b, [0x73, 0x17]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -484,9 +1240,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_02() {
         let n = 0x2e1f;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x2e, 0x1f]);
+        assert_eq!(b, [0x2e, 0x1f]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -494,9 +1250,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_03() {
         let n = 0xc8c9;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xc8, 0xc9]);
+        assert_eq!(b, [0xc8, 0xc9]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -504,9 +1260,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_04() {
         let n = 0x347a;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x34, 0x7a]);
+        assert_eq!(b, [0x34, 0x7a]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -514,9 +1270,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_05() {
         let n = 0x9377;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x93, 0x77]);
+        assert_eq!(b, [0x93, 0x77]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -524,9 +1280,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_06() {
         let n = 0xfc4b;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0xfc, 0x4b]);
+        assert_eq!(b, [0xfc, 0x4b]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -534,9 +1290,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_07() {
         let n = 0x4b88;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x4b, 0x88]);
+        assert_eq!(b, [0x4b, 0x88]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -544,9 +1300,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_08() {
         let n = 0x9b91;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x9b, 0x91]);
+        assert_eq!(b, [0x9b, 0x91]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -554,9 +1310,9 @@ mod tests_binary {
     #[test]
     fn test_big_u16_09() {
         let n = 0x722a;
-        let mut b = vec![0; 2];
+        let mut b = [0; 2];
         BigEndian::encode_u16(n, &mut b);
-        assert_eq!(b, vec![0x72, 0x2a]);
+        assert_eq!(b, [0x72, 0x2a]);
         let n2 = BigEndian::decode_u16(&b);
         assert_eq!(n, n2);
     }
@@ -564,9 +1320,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_00() {
         let n = 0x2c5b2d1a;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x2c, 0x5b, 0x2d, 0x1a]);
+        assert_eq!(b, [0x2c, 0x5b, 0x2d, 0x1a]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -574,9 +1330,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_01() {
         let n = 0x8a858525;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x8a, 0x85, 0x85, 0x25]);
+        assert_eq!(b, [0x8a, 0x85, 0x85, 0x25]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -584,9 +1340,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_02() {
         let n = 0x8b4dc795;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x8b, 0x4d, 0xc7, 0x95]);
+        assert_eq!(b, [0x8b, 0x4d, 0xc7, 0x95]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -594,9 +1350,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_03() {
         let n = 0x474c4687;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x47, 0x4c, 0x46, 0x87]);
+        assert_eq!(b, [0x47, 0x4c, 0x46, 0x87]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -604,9 +1360,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_04() {
         let n = 0x6c53b3e2;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x6c, 0x53, 0xb3, 0xe2]);
+        assert_eq!(b, [0x6c, 0x53, 0xb3, 0xe2]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -614,9 +1370,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_05() {
         let n = 0x87d47727;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x87, 0xd4, 0x77, 0x27]);
+        assert_eq!(b, [0x87, 0xd4, 0x77, 0x27]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -624,9 +1380,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_06() {
         let n = 0x40e807bd;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x40, 0xe8, 0x07, 0xbd]);
+        assert_eq!(b, [0x40, 0xe8, 0x07, 0xbd]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -634,9 +1390,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_07() {
         let n = 0x483675c8;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x48, 0x36, 0x75, 0xc8]);
+        assert_eq!(b, [0x48, 0x36, 0x75, 0xc8]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -644,9 +1400,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_08() {
         let n = 0xc9e202ff;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0xc9, 0xe2, 0x02, 0xff]);
+        assert_eq!(b, [0xc9, 0xe2, 0x02, 0xff]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -654,9 +1410,9 @@ mod tests_binary {
     #[test]
     fn test_big_u32_09() {
         let n = 0x5c9f48b2;
-        let mut b = vec![0; 4];
+        let mut b = [0; 4];
         BigEndian::encode_u32(n, &mut b);
-        assert_eq!(b, vec![0x5c, 0x9f, 0x48, 0xb2]);
+        assert_eq!(b, [0x5c, 0x9f, 0x48, 0xb2]);
         let n2 = BigEndian::decode_u32(&b);
         assert_eq!(n, n2);
     }
@@ -664,9 +1420,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_00() {
         let n = 0xf0b5a315724c7af1;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xf0, 0xb5, 0xa3, 0x15, 0x72, 0x4c, 0x7a, 0xf1]);
+        assert_eq!(b, [0xf0, 0xb5, 0xa3, 0x15, 0x72, 0x4c, 0x7a, 0xf1]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -674,9 +1430,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_01() {
         let n = 0xa607c649581eeb39;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xa6, 0x07, 0xc6, 0x49, 0x58, 0x1e, 0xeb, 0x39]);
+        assert_eq!(b, [0xa6, 0x07, 0xc6, 0x49, 0x58, 0x1e, 0xeb, 0x39]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -684,9 +1440,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_02() {
         let n = 0x727a71f52257bb7d;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x72, 0x7a, 0x71, 0xf5, 0x22, 0x57, 0xbb, 0x7d]);
+        assert_eq!(b, [0x72, 0x7a, 0x71, 0xf5, 0x22, 0x57, 0xbb, 0x7d]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -694,9 +1450,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_03() {
         let n = 0xc7964976f269a28;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x0c, 0x79, 0x64, 0x97, 0x6f, 0x26, 0x9a, 0x28]);
+        assert_eq!(b, [0x0c, 0x79, 0x64, 0x97, 0x6f, 0x26, 0x9a, 0x28]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -704,9 +1460,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_04() {
         let n = 0x7d0b9ca8be8e9981;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x7d, 0x0b, 0x9c, 0xa8, 0xbe, 0x8e, 0x99, 0x81]);
+        assert_eq!(b, [0x7d, 0x0b, 0x9c, 0xa8, 0xbe, 0x8e, 0x99, 0x81]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -714,9 +1470,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_05() {
         let n = 0x89825e117039374b;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x89, 0x82, 0x5e, 0x11, 0x70, 0x39, 0x37, 0x4b]);
+        assert_eq!(b, [0x89, 0x82, 0x5e, 0x11, 0x70, 0x39, 0x37, 0x4b]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -724,9 +1480,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_06() {
         let n = 0x9c73fac825416fed;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x9c, 0x73, 0xfa, 0xc8, 0x25, 0x41, 0x6f, 0xed]);
+        assert_eq!(b, [0x9c, 0x73, 0xfa, 0xc8, 0x25, 0x41, 0x6f, 0xed]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -734,9 +1490,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_07() {
         let n = 0xd72d92faded7e411;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xd7, 0x2d, 0x92, 0xfa, 0xde, 0xd7, 0xe4, 0x11]);
+        assert_eq!(b, [0xd7, 0x2d, 0x92, 0xfa, 0xde, 0xd7, 0xe4, 0x11]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -744,9 +1500,9 @@ mod tests_binary {
     #[test]
     fn test_big_u64_08() {
         let n = 0x1ee9f7676678e7aa;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0x1e, 0xe9, 0xf7, 0x67, 0x66, 0x78, 0xe7, 0xaa]);
+        assert_eq!(b, [0x1e, 0xe9, 0xf7, 0x67, 0x66, 0x78, 0xe7, 0xaa]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
@@ -754,10 +1510,288 @@ mod tests_binary {
     #[test]
     fn test_big_u64_09() {
         let n = 0xa7dff7ab244fcd36;
-        let mut b = vec![0; 8];
+        let mut b = [0; 8];
         BigEndian::encode_u64(n, &mut b);
-        assert_eq!(b, vec![0xa7, 0xdf, 0xf7, 0xab, 0x24, 0x4f, 0xcd, 0x36]);
+        assert_eq!(b, [0xa7, 0xdf, 0xf7, 0xab, 0x24, 0x4f, 0xcd, 0x36]);
         let n2 = BigEndian::decode_u64(&b);
         assert_eq!(n, n2);
     }
+
+    #[test]
+    fn test_signed_float() {
+        let mut b = [0; 16];
+        LittleEndian::encode_i16(-2, &mut b);
+        assert_eq!(b[..2], [0xfe, 0xff]);
+        assert_eq!(LittleEndian::decode_i16(&b), -2);
+        BigEndian::encode_i32(-0x12345678, &mut b);
+        assert_eq!(b[..4], [0xed, 0xcb, 0xa9, 0x88]);
+        assert_eq!(BigEndian::decode_i32(&b), -0x12345678);
+        LittleEndian::encode_i64(i64::MIN, &mut b);
+        assert_eq!(LittleEndian::decode_i64(&b), i64::MIN);
+
+        BigEndian::encode_u128(0x0102030405060708090a0b0c0d0e0f10, &mut b);
+        assert_eq!(b, core::array::from_fn::<u8, 16, _>(|i| i as u8 + 1));
+        LittleEndian::encode_i128(-1 << 100, &mut b);
+        assert_eq!(LittleEndian::decode_i128(&b), -1 << 100);
+        assert_eq!(b[12], 0xf0);
+
+        BigEndian::encode_f32(1.0, &mut b);
+        assert_eq!(b[..4], [0x3f, 0x80, 0x00, 0x00]);
+        assert_eq!(BigEndian::decode_f32(&b), 1.0);
+        LittleEndian::encode_f64(-0.5, &mut b);
+        assert_eq!(b[..8], [0, 0, 0, 0, 0, 0, 0xe0, 0xbf]);
+        assert_eq!(LittleEndian::decode_f64(&b), -0.5);
+    }
+
+    #[test]
+    fn test_uint() {
+        let mut b = [0xaa; 9];
+        LittleEndian::encode_uint(0x0102030405, 5, &mut b);
+        assert_eq!(b, [0x05, 0x04, 0x03, 0x02, 0x01, 0xaa, 0xaa, 0xaa, 0xaa]);
+        assert_eq!(LittleEndian::decode_uint(&b, 5), 0x0102030405);
+        BigEndian::encode_uint(0x0102030405, 5, &mut b);
+        assert_eq!(b[..5], [0x01, 0x02, 0x03, 0x04, 0x05]);
+        assert_eq!(BigEndian::decode_uint(&b, 5), 0x0102030405);
+        assert_eq!(BigEndian::decode_uint(&b, 1), 0x01);
+        BigEndian::encode_uint(u64::MAX, 8, &mut b);
+        assert_eq!(BigEndian::decode_uint(&b, 8), u64::MAX);
+        assert_eq!(LittleEndian::decode_uint(&[0xff, 0xff, 0x7f], 3), 0x7fffff);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_uint_too_large() {
+        BigEndian::encode_uint(0x1000000, 3, &mut [0; 3]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_uint_width() {
+        LittleEndian::decode_uint(&[0; 9], 9);
+    }
+
+    #[test]
+    fn test_f16() {
+        let cases: [(f32, u16); 12] = [
+            (1.0, 0x3c00),
+            (-2.0, 0xc000),
+            (65504.0, 0x7bff),
+            (65519.0, 0x7bff),
+            (65520.0, 0x7c00),
+            (0.1, 0x2e66),
+            (1.0 / 3.0, 0x3555),
+            (5.960_464_5e-8, 0x0001),
+            (2.980_232_2e-8, 0x0000),
+            (6.1e-5, 0x03ff),
+            (-0.0, 0x8000),
+            (f32::NEG_INFINITY, 0xfc00),
+        ];
+        for (x, h) in cases {
+            assert_eq!(f32_to_f16_bits(x), h, "{}", x);
+        }
+        assert_eq!(f32_to_f16_bits(f32::NAN) & 0x7e00, 0x7e00);
+
+        // every half value converts exactly and back
+        for h in 0..=u16::MAX {
+            let x = f16_bits_to_f32(h);
+            if x.is_nan() {
+                assert_eq!(h & 0x7c00, 0x7c00);
+                assert_eq!(f32_to_f16_bits(x), h | 0x0200);
+            } else {
+                assert_eq!(f32_to_f16_bits(x), h);
+            }
+        }
+        assert_eq!(f16_bits_to_f32(0x0001), 5.960_464_5e-8);
+        assert_eq!(f16_bits_to_f32(0x3bff), 0.999_511_7);
+
+        let mut b = [0; 2];
+        BigEndian::encode_f16(-1.5, &mut b);
+        assert_eq!(b, [0xbe, 0x00]);
+        assert_eq!(BigEndian::decode_f16(&b), -1.5);
+    }
+
+    #[test]
+    fn test_slices() {
+        let bytes: [u8; 64] = core::array::from_fn(|i| (i as u8).wrapping_mul(37));
+
+        fn check<E: ByteOrder>(bytes: &[u8]) {
+            let mut values = [0u32; 16];
+            E::read_u32_into(bytes, &mut values);
+            for (i, &n) in values.iter().enumerate() {
+                assert_eq!(n, E::decode_u32(&bytes[i * 4..]));
+            }
+            let mut out = [0; 64];
+            E::write_u32_from_slice(&values, &mut out);
+            assert_eq!(out, bytes);
+
+            let mut values = [0i16; 32];
+            E::read_i16_into(bytes, &mut values);
+            assert_eq!(values[3], E::decode_i16(&bytes[6..]));
+            E::write_i16_from_slice(&values, &mut out);
+            assert_eq!(out, bytes);
+
+            let mut values = [0u128; 4];
+            E::read_u128_into(bytes, &mut values);
+            assert_eq!(values[1], E::decode_u128(&bytes[16..]));
+            E::write_u128_from_slice(&values, &mut out);
+            assert_eq!(out, bytes);
+
+            let mut values = [0f64; 8];
+            E::read_f64_into(bytes, &mut values);
+            assert_eq!(values[7].to_bits(), E::decode_u64(&bytes[56..]));
+            E::write_f64_from_slice(&values, &mut out);
+            assert_eq!(out, bytes);
+
+            // in place conversion of the same bytes read natively
+            let mut values: [u64; 8] = core::array::from_fn(|i| {
+                u64::from_ne_bytes(bytes[i * 8..i * 8 + 8].try_into().unwrap())
+            });
+            E::from_slice_u64(&mut values);
+            for (i, &n) in values.iter().enumerate() {
+                assert_eq!(n, E::decode_u64(&bytes[i * 8..]));
+            }
+            let mut values = [f32::from_bits(u32::from_ne_bytes(
+                bytes[..4].try_into().unwrap(),
+            ))];
+            E::from_slice_f32(&mut values);
+            assert_eq!(values[0].to_bits(), E::decode_u32(bytes));
+        }
+        check::<LittleEndian>(&bytes);
+        check::<BigEndian>(&bytes);
+        check::<MinimalLittle>(&bytes);
+        check::<MinimalBig>(&bytes);
+    }
+
+    /// Only the required methods, forwarded, to exercise the provided ones
+    macro_rules! minimal_byte_order {
+        ($name:ident, $order:ident) => {
+            struct $name;
+
+            impl ByteOrder for $name {
+                fn encode_u16(n: u16, b: &mut [u8]) {
+                    $order::encode_u16(n, b)
+                }
+                fn encode_u32(n: u32, b: &mut [u8]) {
+                    $order::encode_u32(n, b)
+                }
+                fn encode_u64(n: u64, b: &mut [u8]) {
+                    $order::encode_u64(n, b)
+                }
+                fn decode_u16(b: &[u8]) -> u16 {
+                    $order::decode_u16(b)
+                }
+                fn decode_u32(b: &[u8]) -> u32 {
+                    $order::decode_u32(b)
+                }
+                fn decode_u64(b: &[u8]) -> u64 {
+                    $order::decode_u64(b)
+                }
+            }
+        };
+    }
+
+    minimal_byte_order!(MinimalLittle, LittleEndian);
+    minimal_byte_order!(MinimalBig, BigEndian);
+
+    #[test]
+    fn test_provided() {
+        fn check<E: ByteOrder, F: ByteOrder>() {
+            let bytes: [u8; 64] = core::array::from_fn(|i| (i as u8).wrapping_mul(37));
+            assert_eq!(E::decode_u128(&bytes), F::decode_u128(&bytes));
+            let (mut b, mut c) = ([0; 16], [0; 16]);
+            E::encode_u128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10, &mut b);
+            F::encode_u128(0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10, &mut c);
+            assert_eq!(b, c);
+            for nbytes in 1..=8 {
+                assert_eq!(
+                    E::decode_uint(&bytes, nbytes),
+                    F::decode_uint(&bytes, nbytes)
+                );
+                let n = F::decode_uint(&bytes, nbytes);
+                E::encode_uint(n, nbytes, &mut b);
+                assert_eq!(b[..nbytes], bytes[..nbytes]);
+            }
+        }
+        check::<MinimalLittle, LittleEndian>();
+        check::<MinimalBig, BigEndian>();
+
+        let bytes: [u8; 64] = core::array::from_fn(|i| (i as u8).wrapping_mul(37));
+        let mut values = [0i64; 8];
+        MinimalBig::read_i64_into(&bytes, &mut values);
+        assert_eq!(values[2], BigEndian::decode_i64(&bytes[16..]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_slices_length() {
+        LittleEndian::read_u32_into(&[0; 7], &mut [0; 2]);
+    }
+
+    #[test]
+    fn test_endianness() {
+        assert_eq!(Endianness::default(), Endianness::native());
+        let native = if cfg!(target_endian = "little") {
+            Endianness::Little
+        } else {
+            Endianness::Big
+        };
+        assert_eq!(Endianness::native(), native);
+        assert_eq!(NativeEndian::decode_u32(&7u32.to_ne_bytes()), 7);
+        assert_eq!(NetworkEndian::decode_u16(&[0x12, 0x34]), 0x1234);
+
+        // one code path for both orders
+        fn parse(endianness: Endianness, b: &[u8]) -> (u16, i32, f32) {
+            (
+                endianness.decode_u16(b),
+                endianness.decode_i32(&b[2..]),
+                endianness.decode_f32(&b[6..]),
+            )
+        }
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let mut b = [0; 10];
+            endianness.encode_u16(0xbeef, &mut b);
+            endianness.encode_i32(-5, &mut b[2..]);
+            endianness.encode_f32(2.5, &mut b[6..]);
+            assert_eq!(parse(endianness, &b), (0xbeef, -5, 2.5));
+        }
+        assert_eq!(Endianness::Big.decode_uint(&[1, 2, 3], 3), 0x010203);
+
+        let mut values = [0u16; 2];
+        Endianness::Little.read_u16_into(&[1, 0, 2, 0], &mut values);
+        assert_eq!(values, [1, 2]);
+    }
+
+    #[test]
+    fn test_try() {
+        fn eof<T>(needed: usize, available: usize) -> Result<T, Error> {
+            Err(Error::UnexpectedEof { needed, available })
+        }
+        let b = [0x12, 0x34, 0x56];
+        assert_eq!(BigEndian::try_decode_u16(&b), Ok(0x1234));
+        assert_eq!(BigEndian::try_decode_u32(&b), eof(4, 3));
+        assert_eq!(LittleEndian::try_decode_i64(&[]), eof(8, 0));
+        assert_eq!(LittleEndian::try_decode_u128(&[0; 15]), eof(16, 15));
+        assert_eq!(LittleEndian::try_decode_f16(&[0x00, 0x3c]), Ok(1.0));
+        assert_eq!(BigEndian::try_decode_uint(&b, 3), Ok(0x123456));
+        assert_eq!(BigEndian::try_decode_uint(&b, 4), eof(4, 3));
+
+        let mut out = [0; 3];
+        assert_eq!(LittleEndian::try_encode_u16(0x1234, &mut out), Ok(()));
+        assert_eq!(out[..2], [0x34, 0x12]);
+        assert_eq!(LittleEndian::try_encode_f64(1.0, &mut out), eof(8, 3));
+        assert_eq!(BigEndian::try_encode_uint(1, 3, &mut out), Ok(()));
+        assert_eq!(out, [0, 0, 1]);
+        assert_eq!(Endianness::Big.try_decode_u32(&b), eof(4, 3));
+        assert_eq!(Endianness::Little.try_decode_u16(&b), Ok(0x3412));
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(
+            Error::UnexpectedEof {
+                needed: 4,
+                available: 3
+            }
+            .to_string(),
+            "encoding/binary: unexpected end of input, needed 4 bytes, 3 available"
+        );
+    }
 }
@@ -0,0 +1,649 @@
+//! Bit fields that do not sit on byte boundaries.
+//!
+//! [`BitReader`] and [`BitWriter`] handle fields of 0 to 64 bits in a
+//! [`BitOrder`]:
+//!
+//! - [`MsbFirst`]: the most significant bit of each byte comes first and the
+//!   first bit of a field is its most significant one. MPEG, H.264 and
+//!   Motorola CAN signals.
+//! - [`LsbFirst`]: the least significant bit of each byte comes first and the
+//!   first bit of a field is its least significant one. DEFLATE and Intel
+//!   CAN signals.
+//!
+//! Both also read and write the Exp-Golomb codes of H.264 and HEVC. Running
+//! out of bits is an [`Error::UnexpectedEofBits`], never a panic.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::binary::bits::{BitReader, BitWriter, MsbFirst};
+//! let mut w = BitWriter::<_, MsbFirst>::new(Vec::new());
+//! w.write_bits(0b101, 3).unwrap();
+//! w.write_bit(true).unwrap();
+//! w.write_ue(4).unwrap();
+//! assert_eq!(w.position(), 9);
+//! w.align_to_byte().unwrap();
+//! assert_eq!(w.into_inner(), [0b1011_0010, 0b1000_0000]);
+//!
+//! let buf = [0b1011_0010, 0b1000_0000];
+//! let mut r = BitReader::<MsbFirst>::new(&buf);
+//! assert_eq!(r.read_bits(3), Ok(0b101));
+//! assert_eq!(r.read_bit(), Ok(true));
+//! assert_eq!(r.read_ue(), Ok(4));
+//! ```
+
+pub use super::Error;
+use super::WriteBuf;
+use core::marker::PhantomData;
+
+/// Order of the bits within bytes and fields
+pub trait BitOrder {
+    /// Whether the most significant bit comes first
+    const MSB_FIRST: bool;
+}
+
+/// Most significant bit first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MsbFirst;
+
+impl BitOrder for MsbFirst {
+    const MSB_FIRST: bool = true;
+}
+
+/// Least significant bit first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LsbFirst;
+
+impl BitOrder for LsbFirst {
+    const MSB_FIRST: bool = false;
+}
+
+/// Mask of the low `n` bits, `n` up to 64
+fn mask(n: usize) -> u64 {
+    if n >= 64 {
+        u64::MAX
+    } else {
+        (1 << n) - 1
+    }
+}
+
+/// Check a field width
+fn check_width(n: u32) {
+    assert!(n <= 64, "encoding/binary: invalid bit field width");
+}
+
+/// Bytes holding `n` bits from bit offset `off`
+fn bytes_for(off: usize, n: usize) -> usize {
+    (off + n).div_ceil(8)
+}
+
+/// Read `n` bits from bit offset `off` of `b`
+fn get_bits<O: BitOrder>(b: &[u8], mut off: usize, n: usize) -> u64 {
+    let mut value = 0;
+    let mut done = 0;
+    for &byte in b {
+        if done == n {
+            break;
+        }
+        let k = (8 - off).min(n - done);
+        let byte = u64::from(byte);
+        if O::MSB_FIRST {
+            value = value << k | (byte >> (8 - off - k)) & mask(k);
+        } else {
+            value |= ((byte >> off) & mask(k)) << done;
+        }
+        done += k;
+        off = 0;
+    }
+    value
+}
+
+/// Write the low `n` bits of `value` at bit offset `off` of `b`, the bits
+/// from `off` on must be zero
+fn put_bits<O: BitOrder>(b: &mut [u8], mut off: usize, value: u64, n: usize) {
+    let mut done = 0;
+    for byte in b {
+        if done == n {
+            break;
+        }
+        let k = (8 - off).min(n - done);
+        if O::MSB_FIRST {
+            let chunk = (value >> (n - done - k)) & mask(k);
+            *byte |= (chunk << (8 - off - k)) as u8;
+        } else {
+            let chunk = (value >> done) & mask(k);
+            *byte |= (chunk << off) as u8;
+        }
+        done += k;
+        off = 0;
+    }
+}
+
+/// Cursor reading bit fields from a byte slice
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::bits::{BitReader, Error, LsbFirst};
+/// let mut r = BitReader::<LsbFirst>::new(&[0b1110_0101]);
+/// assert_eq!(r.read_bits(3), Ok(0b101));
+/// assert_eq!(r.peek_bits(2), Ok(0b00));
+/// assert_eq!(r.read_signed_bits(5), Ok(-4));
+/// assert_eq!(
+///     r.read_bit(),
+///     Err(Error::UnexpectedEofBits { needed: 1, available: 0 })
+/// );
+/// ```
+#[derive(Debug)]
+pub struct BitReader<'a, O> {
+    buf: &'a [u8],
+    pos: usize,
+    order: PhantomData<O>,
+}
+
+impl<O> Clone for BitReader<'_, O> {
+    fn clone(&self) -> Self {
+        Self {
+            buf: self.buf,
+            pos: self.pos,
+            order: PhantomData,
+        }
+    }
+}
+
+impl<'a, O: BitOrder> BitReader<'a, O> {
+    /// New reader at the first bit of `buf`
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            pos: 0,
+            order: PhantomData,
+        }
+    }
+
+    /// Bits read so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Bits left to read
+    pub fn remaining(&self) -> usize {
+        self.buf.len() * 8 - self.pos
+    }
+
+    /// Whether all bits were read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Whether the position is on a byte boundary
+    pub fn is_aligned(&self) -> bool {
+        self.pos % 8 == 0
+    }
+
+    /// Skip to the next byte boundary, if not on one
+    pub fn align_to_byte(&mut self) {
+        self.pos = self.pos.next_multiple_of(8);
+    }
+
+    /// Check that `n` bits are left
+    fn check(&self, n: usize) -> Result<(), Error> {
+        let available = self.remaining();
+        if available < n {
+            return Err(Error::UnexpectedEofBits {
+                needed: n,
+                available,
+            });
+        }
+        Ok(())
+    }
+
+    /// Advance past `n` bits
+    pub fn skip_bits(&mut self, n: usize) -> Result<(), Error> {
+        self.check(n)?;
+        self.pos += n;
+        Ok(())
+    }
+
+    /// The next `n` bits, without advancing
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64.
+    pub fn peek_bits(&self, n: u32) -> Result<u64, Error> {
+        check_width(n);
+        let n = n as usize;
+        self.check(n)?;
+        let off = self.pos % 8;
+        let b = &self.buf[self.pos / 8..];
+        Ok(get_bits::<O>(&b[..bytes_for(off, n)], off, n))
+    }
+
+    /// Read an unsigned field of `n` bits
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64.
+    pub fn read_bits(&mut self, n: u32) -> Result<u64, Error> {
+        let value = self.peek_bits(n)?;
+        self.pos += n as usize;
+        Ok(value)
+    }
+
+    /// Read a two's complement field of `n` bits, sign extended
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64.
+    pub fn read_signed_bits(&mut self, n: u32) -> Result<i64, Error> {
+        let value = self.read_bits(n)?;
+        if n == 0 || n == 64 {
+            return Ok(value as i64);
+        }
+        let shift = 64 - n;
+        Ok((value << shift) as i64 >> shift)
+    }
+
+    /// Read one bit
+    pub fn read_bit(&mut self) -> Result<bool, Error> {
+        self.read_bits(1).map(|bit| bit == 1)
+    }
+
+    /// Read an unsigned Exp-Golomb code, `ue(v)` of H.264
+    ///
+    /// Does not advance on error.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::bits::{BitReader, MsbFirst};
+    /// // 1, 010, 011, 00100
+    /// let mut r = BitReader::<MsbFirst>::new(&[0b1010_0110, 0b0100_0000]);
+    /// assert_eq!(r.read_ue(), Ok(0));
+    /// assert_eq!(r.read_ue(), Ok(1));
+    /// assert_eq!(r.read_ue(), Ok(2));
+    /// assert_eq!(r.read_ue(), Ok(3));
+    /// ```
+    pub fn read_ue(&mut self) -> Result<u64, Error> {
+        let mut r = self.clone();
+        let mut zeros = 0;
+        while !r.read_bit()? {
+            zeros += 1;
+            if zeros > 64 {
+                return Err(Error::VarintOverflow);
+            }
+        }
+        let value = if zeros == 64 {
+            // Only 2^64 - 1 + 0 fits
+            match r.read_bits(64)? {
+                0 => u64::MAX,
+                _ => return Err(Error::VarintOverflow),
+            }
+        } else {
+            mask(zeros as usize) + r.read_bits(zeros)?
+        };
+        *self = r;
+        Ok(value)
+    }
+
+    /// Read a signed Exp-Golomb code, `se(v)` of H.264
+    ///
+    /// Code numbers 0, 1, 2, 3, 4... are 0, 1, -1, 2, -2... Does not advance
+    /// on error.
+    pub fn read_se(&mut self) -> Result<i64, Error> {
+        let mut r = self.clone();
+        let k = r.read_ue()?;
+        let value = if k % 2 == 1 {
+            i64::try_from(k / 2 + 1).map_err(|_| Error::VarintOverflow)?
+        } else {
+            -((k / 2) as i64)
+        };
+        *self = r;
+        Ok(value)
+    }
+}
+
+/// Cursor writing bit fields into a [`WriteBuf`]
+///
+/// Writing starts at [`WriteBuf::start`] of the buffer, the unwritten bits of
+/// the last byte are zero.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::bits::{BitWriter, Error, LsbFirst};
+/// let mut w = BitWriter::<_, LsbFirst>::new([0xffu8; 1]);
+/// w.write_bits(0b101, 3).unwrap();
+/// w.write_signed_bits(-1, 2).unwrap();
+/// assert_eq!(w.written(), [0b0001_1101]);
+/// assert_eq!(
+///     w.write_bits(0, 4),
+///     Err(Error::UnexpectedEofBits { needed: 4, available: 3 })
+/// );
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<B, O> {
+    buf: B,
+    pos: usize,
+    order: PhantomData<O>,
+}
+
+impl<B: WriteBuf, O: BitOrder> BitWriter<B, O> {
+    /// New writer at [`WriteBuf::start`] of `buf`
+    pub fn new(buf: B) -> Self {
+        let pos = buf.start() * 8;
+        Self {
+            buf,
+            pos,
+            order: PhantomData,
+        }
+    }
+
+    /// Position of the next bit, from the start of the buffer
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Whether the position is on a byte boundary
+    pub fn is_aligned(&self) -> bool {
+        self.pos % 8 == 0
+    }
+
+    /// The bytes holding the written bits
+    pub fn written(&self) -> &[u8] {
+        &self.buf.bytes()[..self.pos.div_ceil(8)]
+    }
+
+    /// Give back the buffer
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+
+    /// Write the low `n` bits of `value`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64 or `value` does not fit.
+    pub fn write_bits(&mut self, value: u64, n: u32) -> Result<(), Error> {
+        check_width(n);
+        assert!(
+            value & !mask(n as usize) == 0,
+            "encoding/binary: value too large for its bit width"
+        );
+        let n = n as usize;
+        let off = self.pos % 8;
+        let b = match self.buf.writable(self.pos / 8, bytes_for(off, n)) {
+            Ok(b) => b,
+            Err(Error::UnexpectedEof { available, .. }) => {
+                return Err(Error::UnexpectedEofBits {
+                    needed: n,
+                    available: available * 8 - off,
+                })
+            }
+            Err(e) => return Err(e),
+        };
+        // Clear the fresh bytes of fixed buffers
+        let fresh = usize::from(off != 0);
+        if let Some(rest) = b.get_mut(fresh..) {
+            rest.fill(0);
+        }
+        if off != 0 {
+            if O::MSB_FIRST {
+                b[0] &= !(0xff >> off);
+            } else {
+                b[0] &= !(0xff << off);
+            }
+        }
+        put_bits::<O>(b, off, value, n);
+        self.pos += n;
+        Ok(())
+    }
+
+    /// Write the low `n` bits of a two's complement value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is more than 64 or `value` does not fit.
+    pub fn write_signed_bits(&mut self, value: i64, n: u32) -> Result<(), Error> {
+        check_width(n);
+        let fits = match n {
+            0 => value == 0,
+            64 => true,
+            _ => (value >> (n - 1)) == 0 || (value >> (n - 1)) == -1,
+        };
+        assert!(fits, "encoding/binary: value too large for its bit width");
+        self.write_bits(value as u64 & mask(n as usize), n)
+    }
+
+    /// Write one bit
+    pub fn write_bit(&mut self, bit: bool) -> Result<(), Error> {
+        self.write_bits(u64::from(bit), 1)
+    }
+
+    /// Pad with zero bits to the next byte boundary, if not on one
+    pub fn align_to_byte(&mut self) -> Result<(), Error> {
+        let pad = self.pos.next_multiple_of(8) - self.pos;
+        self.write_bits(0, pad as u32)
+    }
+
+    /// Write an unsigned Exp-Golomb code, `ue(v)` of H.264
+    pub fn write_ue(&mut self, value: u64) -> Result<(), Error> {
+        match value.checked_add(1) {
+            Some(code) => {
+                let zeros = 63 - code.leading_zeros();
+                self.write_bits(0, zeros)?;
+                self.write_bits(code, zeros + 1)
+            }
+            None => {
+                // 2^64: 64 zeros, a one, then 64 zeros
+                self.write_bits(0, 64)?;
+                self.write_bit(true)?;
+                self.write_bits(0, 64)
+            }
+        }
+    }
+
+    /// Write a signed Exp-Golomb code, `se(v)` of H.264
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is `i64::MIN`, out of the code range.
+    pub fn write_se(&mut self, value: i64) -> Result<(), Error> {
+        assert!(
+            value != i64::MIN,
+            "encoding/binary: value out of Exp-Golomb range"
+        );
+        let k = if value > 0 {
+            value as u64 * 2 - 1
+        } else {
+            value.unsigned_abs() * 2
+        };
+        self.write_ue(k)
+    }
+}
+
+#[cfg(test)]
+mod tests_bits {
+    use super::*;
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    #[test]
+    fn test_msb_first() {
+        let buf = [0b1100_1010, 0b0111_0001, 0xff];
+        let mut r = BitReader::<MsbFirst>::new(&buf);
+        assert_eq!(r.read_bits(2), Ok(0b11));
+        assert_eq!(r.read_bits(0), Ok(0));
+        assert_eq!(r.read_bits(10), Ok(0b00_1010_0111));
+        assert!(!r.is_aligned());
+        assert_eq!(r.peek_bits(4), Ok(0b0001));
+        assert_eq!(r.read_signed_bits(4), Ok(1));
+        assert!(r.is_aligned());
+        r.skip_bits(1).unwrap();
+        r.align_to_byte();
+        assert!(r.is_empty());
+        assert_eq!(
+            r.skip_bits(1),
+            Err(Error::UnexpectedEofBits {
+                needed: 1,
+                available: 0
+            })
+        );
+
+        let mut w = BitWriter::<_, MsbFirst>::new([0u8; 3]);
+        w.write_bits(0b11, 2).unwrap();
+        w.write_bits(0b00_1010_0111, 10).unwrap();
+        w.write_signed_bits(1, 4).unwrap();
+        w.write_bits(0xff, 8).unwrap();
+        assert_eq!(w.written(), buf);
+    }
+
+    #[test]
+    fn test_lsb_first() {
+        let buf = [0b1100_1010, 0b0111_0001];
+        let mut r = BitReader::<LsbFirst>::new(&buf);
+        assert_eq!(r.read_bits(3), Ok(0b010));
+        assert_eq!(r.read_bits(9), Ok(0b0_0011_1001));
+        assert_eq!(r.read_signed_bits(4), Ok(0b0111));
+        assert!(r.is_empty());
+
+        let mut w = BitWriter::<_, LsbFirst>::new([0u8; 2]);
+        w.write_bits(0b010, 3).unwrap();
+        w.write_bits(0b0_0011_1001, 9).unwrap();
+        w.write_bits(0b0111, 4).unwrap();
+        assert_eq!(w.into_inner(), buf);
+    }
+
+    #[test]
+    fn test_wide_fields() {
+        for off in 0..8 {
+            let mut w = BitWriter::<_, MsbFirst>::new([0u8; 17]);
+            let mut lw = BitWriter::<_, LsbFirst>::new([0u8; 17]);
+            w.write_bits(0, off).unwrap();
+            lw.write_bits(0, off).unwrap();
+            w.write_bits(0x0123_4567_89ab_cdef, 64).unwrap();
+            lw.write_bits(0x0123_4567_89ab_cdef, 64).unwrap();
+            w.write_signed_bits(i64::MIN, 64).unwrap();
+            lw.write_signed_bits(-5, 33).unwrap();
+
+            let mut r = BitReader::<MsbFirst>::new(w.written());
+            r.skip_bits(off as usize).unwrap();
+            assert_eq!(r.read_bits(64), Ok(0x0123_4567_89ab_cdef));
+            assert_eq!(r.read_signed_bits(64), Ok(i64::MIN));
+
+            let mut r = BitReader::<LsbFirst>::new(lw.written());
+            r.skip_bits(off as usize).unwrap();
+            assert_eq!(r.read_bits(64), Ok(0x0123_4567_89ab_cdef));
+            assert_eq!(r.read_signed_bits(33), Ok(-5));
+        }
+    }
+
+    #[test]
+    fn test_fixed_buffer() {
+        let mut buf = [0xaau8; 3];
+        let mut w = BitWriter::<_, MsbFirst>::new(&mut buf[..]);
+        w.write_bits(0b1, 1).unwrap();
+        w.write_bits(0, 7).unwrap();
+        w.write_bits(0b1, 3).unwrap();
+        assert_eq!(
+            w.write_bits(0, 14),
+            Err(Error::UnexpectedEofBits {
+                needed: 14,
+                available: 13
+            })
+        );
+        w.align_to_byte().unwrap();
+        assert_eq!(w.position(), 16);
+        assert_eq!(buf, [0x80, 0x20, 0xaa]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_vec_buffer() {
+        // Appends to the existing contents of a Vec
+        let mut w = BitWriter::<_, LsbFirst>::new(vec![0xee]);
+        w.write_bit(true).unwrap();
+        assert_eq!(w.position(), 9);
+        assert_eq!(w.into_inner(), [0xee, 0x01]);
+    }
+
+    #[test]
+    fn test_exp_golomb() {
+        let mut w = BitWriter::<_, MsbFirst>::new([0u8; 256]);
+        let values = [
+            0,
+            1,
+            2,
+            3,
+            7,
+            8,
+            255,
+            u32::MAX as u64,
+            u64::MAX - 1,
+            u64::MAX,
+        ];
+        for v in values {
+            w.write_ue(v).unwrap();
+        }
+        let signed = [0, 1, -1, 2, -2, 1000, -1000, i64::MAX, i64::MIN + 1];
+        for v in signed {
+            w.write_se(v).unwrap();
+        }
+        let mut r = BitReader::<MsbFirst>::new(w.written());
+        for v in values {
+            assert_eq!(r.read_ue(), Ok(v));
+        }
+        for v in signed {
+            assert_eq!(r.read_se(), Ok(v));
+        }
+
+        // se(v) code numbers
+        let mut w = BitWriter::<_, MsbFirst>::new([0u8; 1]);
+        w.write_se(-2).unwrap();
+        assert_eq!(w.into_inner(), [0b0010_1000]);
+    }
+
+    #[test]
+    fn test_exp_golomb_errors() {
+        // Truncated, the position stays
+        let mut r = BitReader::<MsbFirst>::new(&[0b0000_0100]);
+        assert_eq!(r.read_bit(), Ok(false));
+        assert_eq!(
+            r.read_ue(),
+            Err(Error::UnexpectedEofBits {
+                needed: 4,
+                available: 2
+            })
+        );
+        assert_eq!(r.position(), 1);
+
+        let mut buf = [0u8; 18];
+        buf[8] = 0x80;
+        buf[16] = 0x80;
+        let mut r = BitReader::<MsbFirst>::new(&buf);
+        assert_eq!(r.read_ue(), Err(Error::VarintOverflow));
+        let mut r = BitReader::<MsbFirst>::new(&[0; 9]);
+        assert_eq!(r.read_ue(), Err(Error::VarintOverflow));
+        // ue(2^64 - 1) is the largest se(v) code number, out of i64
+        let mut buf = [0u8; 17];
+        buf[8] = 0x80;
+        let mut r = BitReader::<MsbFirst>::new(&buf);
+        assert_eq!(r.read_se(), Err(Error::VarintOverflow));
+        assert_eq!(r.position(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding/binary: value too large for its bit width")]
+    fn test_write_too_large() {
+        let mut w = BitWriter::<_, MsbFirst>::new([0u8; 1]);
+        let _ = w.write_bits(4, 2);
+    }
+}
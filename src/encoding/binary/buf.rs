@@ -0,0 +1,726 @@
+//! Cursors reading and writing values in a [`ByteOrder`] over byte buffers.
+//!
+//! [`Reader`] walks a `&[u8]` with position tracking, [`Writer`] fills a
+//! [`WriteBuf`]: a `Vec<u8>` growing as needed, or a fixed `&mut [u8]` or
+//! `[u8; N]`. Running out of bytes is an [`Error::UnexpectedEof`], never a
+//! panic.
+//!
+//! # Examples
+//!
+//! Length prefixed record, the length is back-patched:
+//!
+//! ```
+//! use connx::encoding::binary::{BigEndian, Reader, Writer};
+//! let mut w = Writer::new(Vec::new());
+//! w.put_u8(7).unwrap();
+//! let len = w.reserve_placeholder(2).unwrap();
+//! w.put_bytes(b"hello").unwrap();
+//! let n = w.position() - len.end();
+//! w.patch_u16::<BigEndian>(len, n as u16);
+//! let packet = w.into_inner();
+//! assert_eq!(packet, b"\x07\x00\x05hello");
+//!
+//! let mut r = Reader::new(&packet);
+//! assert_eq!(r.get_u8(), Ok(7));
+//! let n = r.get_u16::<BigEndian>().unwrap();
+//! assert_eq!(r.get_bytes(n as usize), Ok(&b"hello"[..]));
+//! assert!(r.is_empty());
+//! ```
+
+use super::{unaligned, varint, ByteOrder, Error, FromBytes};
+use core::mem::size_of;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+/// Define the get methods of [`Reader`]
+macro_rules! get_methods {
+    ($($ty:ty, $size:literal => $get:ident = $try_decode:ident;)*) => {
+        $(
+            #[doc = concat!("Read a `", stringify!($ty), "` in byte order `E` and advance past it")]
+            pub fn $get<E: ByteOrder>(&mut self) -> Result<$ty, Error> {
+                let n = E::$try_decode(self.remaining_slice())?;
+                self.pos += $size;
+                Ok(n)
+            }
+        )*
+    };
+}
+
+/// Define the put and patch methods of [`Writer`]
+macro_rules! put_methods {
+    ($($ty:ty, $size:literal => $put:ident = $encode:ident;)*) => {
+        $(
+            #[doc = concat!("Write a `", stringify!($ty), "` in byte order `E`")]
+            pub fn $put<E: ByteOrder>(&mut self, n: $ty) -> Result<(), Error> {
+                E::$encode(n, self.advance($size)?);
+                Ok(())
+            }
+        )*
+    };
+}
+
+/// Cursor reading values from a byte slice
+///
+/// Cloning a reader is cheap, a clone looks ahead without moving the
+/// original.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{Error, LittleEndian, Reader};
+/// let mut r = Reader::new(&[0x01, 0x00, 0x00, 0x00, 0xaa, 0x02, 0x00]);
+/// assert_eq!(r.get_u32::<LittleEndian>(), Ok(1));
+/// assert_eq!(r.peek(1), Ok(&[0xaa][..]));
+/// r.skip(1).unwrap();
+/// assert_eq!(r.remaining(), 2);
+/// assert_eq!(
+///     r.get_u32::<LittleEndian>(),
+///     Err(Error::UnexpectedEof { needed: 4, available: 2 })
+/// );
+/// assert_eq!(r.get_u16::<LittleEndian>(), Ok(2));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// New reader at the start of `buf`
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Bytes read so far
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Bytes left to read
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+
+    /// Whether all bytes were read
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// The bytes left to read
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Check that `n` bytes are left
+    fn check(&self, n: usize) -> Result<(), Error> {
+        super::check_len(n, self.remaining())
+    }
+
+    /// The next `n` bytes, without advancing
+    pub fn peek(&self, n: usize) -> Result<&'a [u8], Error> {
+        self.check(n)?;
+        Ok(&self.buf[self.pos..self.pos + n])
+    }
+
+    /// The next byte, without advancing
+    pub fn peek_u8(&self) -> Result<u8, Error> {
+        self.peek(1).map(|b| b[0])
+    }
+
+    /// Advance past `n` bytes
+    pub fn skip(&mut self, n: usize) -> Result<(), Error> {
+        self.check(n)?;
+        self.pos += n;
+        Ok(())
+    }
+
+    /// Read `n` bytes and advance past them
+    pub fn get_bytes(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let b = self.peek(n)?;
+        self.pos += n;
+        Ok(b)
+    }
+
+    /// Split the next `n` bytes off into their own reader
+    ///
+    /// `self` advances past them, the new reader starts at position 0.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::binary::{BigEndian, Reader};
+    /// let mut r = Reader::new(&[0x00, 0x02, 0x12, 0x34, 0xff]);
+    /// let len = r.get_u16::<BigEndian>().unwrap();
+    /// let mut body = r.split_to(len as usize).unwrap();
+    /// assert_eq!(body.get_u16::<BigEndian>(), Ok(0x1234));
+    /// assert!(body.is_empty());
+    /// assert_eq!(r.get_u8(), Ok(0xff));
+    /// ```
+    pub fn split_to(&mut self, n: usize) -> Result<Reader<'a>, Error> {
+        self.get_bytes(n).map(Reader::new)
+    }
+
+    /// Skip padding up to the next multiple of `align` from the start
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    pub fn align_to(&mut self, align: usize) -> Result<(), Error> {
+        self.skip(padding(self.pos, align))
+    }
+
+    /// Read one byte
+    pub fn get_u8(&mut self) -> Result<u8, Error> {
+        self.get_bytes(1).map(|b| b[0])
+    }
+
+    /// Read one signed byte
+    pub fn get_i8(&mut self) -> Result<i8, Error> {
+        self.get_u8().map(|n| n as i8)
+    }
+
+    get_methods! {
+        u16, 2 => get_u16 = try_decode_u16;
+        u32, 4 => get_u32 = try_decode_u32;
+        u64, 8 => get_u64 = try_decode_u64;
+        u128, 16 => get_u128 = try_decode_u128;
+        i16, 2 => get_i16 = try_decode_i16;
+        i32, 4 => get_i32 = try_decode_i32;
+        i64, 8 => get_i64 = try_decode_i64;
+        i128, 16 => get_i128 = try_decode_i128;
+        f32, 2 => get_f16 = try_decode_f16;
+        f32, 4 => get_f32 = try_decode_f32;
+        f64, 8 => get_f64 = try_decode_f64;
+    }
+
+    /// Read an unsigned integer of `nbytes` bytes in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8.
+    pub fn get_uint<E: ByteOrder>(&mut self, nbytes: usize) -> Result<u64, Error> {
+        let n = E::try_decode_uint(self.remaining_slice(), nbytes)?;
+        self.pos += nbytes;
+        Ok(n)
+    }
+
+    /// View the next bytes as a `T` without copying and advance past them,
+    /// see [`ref_from_prefix`](super::ref_from_prefix)
+    pub fn get_ref<T: FromBytes>(&mut self) -> Result<&'a T, Error> {
+        let (value, _) = unaligned::ref_from_prefix(self.remaining_slice())?;
+        self.pos += size_of::<T>();
+        Ok(value)
+    }
+
+    /// View the next bytes as `count` consecutive `T` without copying and
+    /// advance past them
+    pub fn get_slice<T: FromBytes>(&mut self, count: usize) -> Result<&'a [T], Error> {
+        let (values, _) = unaligned::slice_from_prefix(self.remaining_slice(), count)?;
+        self.pos += size_of::<T>() * count;
+        Ok(values)
+    }
+
+    /// Read a LEB128 unsigned value, see [`varint::decode_uvarint`]
+    pub fn get_uvarint(&mut self) -> Result<u64, Error> {
+        let (n, len) = varint::decode_uvarint(self.remaining_slice())?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    /// Read a zigzag LEB128 signed value, see [`varint::decode_varint`]
+    pub fn get_varint(&mut self) -> Result<i64, Error> {
+        let (n, len) = varint::decode_varint(self.remaining_slice())?;
+        self.pos += len;
+        Ok(n)
+    }
+
+    /// Read a signed LEB128 value, see [`varint::decode_sleb128`]
+    pub fn get_sleb128(&mut self) -> Result<i64, Error> {
+        let (n, len) = varint::decode_sleb128(self.remaining_slice())?;
+        self.pos += len;
+        Ok(n)
+    }
+}
+
+/// Padding from `pos` up to the next multiple of `align`
+fn padding(pos: usize, align: usize) -> usize {
+    assert!(align != 0, "encoding/binary: zero alignment");
+    (align - pos % align) % align
+}
+
+/// Storage a [`Writer`] writes into
+pub trait WriteBuf {
+    /// Position a new writer starts at
+    fn start(&self) -> usize;
+
+    /// The whole buffer
+    fn bytes(&self) -> &[u8];
+
+    /// The `n` bytes at `pos`, growing the buffer if it can
+    fn writable(&mut self, pos: usize, n: usize) -> Result<&mut [u8], Error>;
+}
+
+/// Fixed buffer, writing starts at the beginning
+impl WriteBuf for [u8] {
+    fn start(&self) -> usize {
+        0
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn writable(&mut self, pos: usize, n: usize) -> Result<&mut [u8], Error> {
+        super::check_len(n, self.len() - pos)?;
+        Ok(&mut self[pos..pos + n])
+    }
+}
+
+/// Fixed buffer, writing starts at the beginning
+impl<const N: usize> WriteBuf for [u8; N] {
+    fn start(&self) -> usize {
+        0
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn writable(&mut self, pos: usize, n: usize) -> Result<&mut [u8], Error> {
+        self[..].writable(pos, n)
+    }
+}
+
+/// Growing buffer, writing appends to the existing contents
+#[cfg(feature = "alloc")]
+impl WriteBuf for Vec<u8> {
+    fn start(&self) -> usize {
+        self.len()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn writable(&mut self, pos: usize, n: usize) -> Result<&mut [u8], Error> {
+        if self.len() < pos + n {
+            self.resize(pos + n, 0);
+        }
+        Ok(&mut self[pos..pos + n])
+    }
+}
+
+impl<B: WriteBuf + ?Sized> WriteBuf for &mut B {
+    fn start(&self) -> usize {
+        (**self).start()
+    }
+
+    fn bytes(&self) -> &[u8] {
+        (**self).bytes()
+    }
+
+    fn writable(&mut self, pos: usize, n: usize) -> Result<&mut [u8], Error> {
+        (**self).writable(pos, n)
+    }
+}
+
+/// Bytes reserved by [`Writer::reserve_placeholder`], to be patched later
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placeholder {
+    pos: usize,
+    len: usize,
+}
+
+impl Placeholder {
+    /// Position of the first reserved byte
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Number of reserved bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no bytes are reserved
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Position just past the reserved bytes
+    pub fn end(&self) -> usize {
+        self.pos + self.len
+    }
+}
+
+/// Cursor writing values into a [`WriteBuf`]
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{Error, LittleEndian, Writer};
+/// let mut w = Writer::new([0u8; 6]);
+/// w.put_u32::<LittleEndian>(0x04030201).unwrap();
+/// assert_eq!(
+///     w.put_u32::<LittleEndian>(5),
+///     Err(Error::UnexpectedEof { needed: 4, available: 2 })
+/// );
+/// w.put_u16::<LittleEndian>(0x0605).unwrap();
+/// assert_eq!(w.written(), [1, 2, 3, 4, 5, 6]);
+/// ```
+#[derive(Debug)]
+pub struct Writer<B> {
+    buf: B,
+    pos: usize,
+}
+
+impl<B: WriteBuf> Writer<B> {
+    /// New writer at [`WriteBuf::start`] of `buf`
+    pub fn new(buf: B) -> Self {
+        let pos = buf.start();
+        Self { buf, pos }
+    }
+
+    /// Position of the next write
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The bytes up to the current position
+    pub fn written(&self) -> &[u8] {
+        &self.buf.bytes()[..self.pos]
+    }
+
+    /// Give back the buffer
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+
+    /// The next `n` bytes to fill, advancing past them
+    fn advance(&mut self, n: usize) -> Result<&mut [u8], Error> {
+        let b = self.buf.writable(self.pos, n)?;
+        self.pos += n;
+        Ok(b)
+    }
+
+    /// Write raw bytes
+    pub fn put_bytes(&mut self, src: &[u8]) -> Result<(), Error> {
+        self.advance(src.len())?.copy_from_slice(src);
+        Ok(())
+    }
+
+    /// Write `n` zero bytes
+    pub fn put_zeros(&mut self, n: usize) -> Result<(), Error> {
+        self.advance(n)?.fill(0);
+        Ok(())
+    }
+
+    /// Write zero padding up to the next multiple of `align` from the start
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    pub fn align_to(&mut self, align: usize) -> Result<(), Error> {
+        self.put_zeros(padding(self.pos, align))
+    }
+
+    /// Write one byte
+    pub fn put_u8(&mut self, n: u8) -> Result<(), Error> {
+        self.put_bytes(&[n])
+    }
+
+    /// Write one signed byte
+    pub fn put_i8(&mut self, n: i8) -> Result<(), Error> {
+        self.put_u8(n as u8)
+    }
+
+    put_methods! {
+        u16, 2 => put_u16 = encode_u16;
+        u32, 4 => put_u32 = encode_u32;
+        u64, 8 => put_u64 = encode_u64;
+        u128, 16 => put_u128 = encode_u128;
+        i16, 2 => put_i16 = encode_i16;
+        i32, 4 => put_i32 = encode_i32;
+        i64, 8 => put_i64 = encode_i64;
+        i128, 16 => put_i128 = encode_i128;
+        f32, 2 => put_f16 = encode_f16;
+        f32, 4 => put_f32 = encode_f32;
+        f64, 8 => put_f64 = encode_f64;
+    }
+
+    /// Write the low `nbytes` bytes of an unsigned integer in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8 or `n` does not fit.
+    pub fn put_uint<E: ByteOrder>(&mut self, n: u64, nbytes: usize) -> Result<(), Error> {
+        super::check_uint(n, nbytes);
+        E::encode_uint(n, nbytes, self.advance(nbytes)?);
+        Ok(())
+    }
+
+    /// Write a LEB128 unsigned value, see [`varint::encode_uvarint`]
+    pub fn put_uvarint(&mut self, n: u64) -> Result<(), Error> {
+        let mut b = [0; varint::MAX_VARINT_LEN64];
+        let len = varint::encode_uvarint(n, &mut b);
+        self.put_bytes(&b[..len])
+    }
+
+    /// Write a zigzag LEB128 signed value, see [`varint::encode_varint`]
+    pub fn put_varint(&mut self, n: i64) -> Result<(), Error> {
+        self.put_uvarint(varint::zigzag_encode(n))
+    }
+
+    /// Write a signed LEB128 value, see [`varint::encode_sleb128`]
+    pub fn put_sleb128(&mut self, n: i64) -> Result<(), Error> {
+        let mut b = [0; varint::MAX_VARINT_LEN64];
+        let len = varint::encode_sleb128(n, &mut b);
+        self.put_bytes(&b[..len])
+    }
+
+    /// Write `len` zero bytes to fill in later with the `patch_*` methods
+    ///
+    /// Typically a length field, known once what follows is written.
+    pub fn reserve_placeholder(&mut self, len: usize) -> Result<Placeholder, Error> {
+        let pos = self.pos;
+        self.put_zeros(len)?;
+        Ok(Placeholder { pos, len })
+    }
+
+    /// The reserved bytes of `p`, checked to hold `len` bytes
+    fn placeholder(&mut self, p: Placeholder, len: usize) -> &mut [u8] {
+        assert!(
+            p.len == len && p.end() <= self.pos,
+            "encoding/binary: placeholder mismatch"
+        );
+        // Already written, so the buffer can not be short
+        self.buf.writable(p.pos, len).unwrap()
+    }
+
+    /// Fill a 1 byte placeholder
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not 1 byte long or not from this writer.
+    pub fn patch_u8(&mut self, p: Placeholder, n: u8) {
+        self.placeholder(p, 1)[0] = n;
+    }
+
+    /// Fill a 2 bytes placeholder in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not 2 bytes long or not from this writer.
+    pub fn patch_u16<E: ByteOrder>(&mut self, p: Placeholder, n: u16) {
+        E::encode_u16(n, self.placeholder(p, 2));
+    }
+
+    /// Fill a 4 bytes placeholder in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not 4 bytes long or not from this writer.
+    pub fn patch_u32<E: ByteOrder>(&mut self, p: Placeholder, n: u32) {
+        E::encode_u32(n, self.placeholder(p, 4));
+    }
+
+    /// Fill an 8 bytes placeholder in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not 8 bytes long or not from this writer.
+    pub fn patch_u64<E: ByteOrder>(&mut self, p: Placeholder, n: u64) {
+        E::encode_u64(n, self.placeholder(p, 8));
+    }
+
+    /// Fill a placeholder of 1 to 8 bytes in byte order `E`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is longer than 8 bytes, not from this writer, or `n`
+    /// does not fit.
+    pub fn patch_uint<E: ByteOrder>(&mut self, p: Placeholder, n: u64) {
+        super::check_uint(n, p.len);
+        E::encode_uint(n, p.len, self.placeholder(p, p.len));
+    }
+}
+
+#[cfg(test)]
+mod tests_binary_buf {
+    use super::*;
+    use crate::encoding::binary::{BigEndian, LittleEndian};
+    #[cfg(feature = "alloc")]
+    use alloc::vec;
+
+    #[test]
+    fn test_reader() {
+        let data = [
+            0xff, 0x12, 0x34, 0x78, 0x56, 0x34, 0x12, 0x00, 0x3c, 0x01, 0x02, 0x03,
+        ];
+        let mut r = Reader::new(&data);
+        assert_eq!(r.get_i8(), Ok(-1));
+        assert_eq!(r.clone().get_u16::<LittleEndian>(), Ok(0x3412));
+        assert_eq!(r.get_u16::<BigEndian>(), Ok(0x1234));
+        assert_eq!(r.position(), 3);
+        assert_eq!(r.get_u32::<LittleEndian>(), Ok(0x12345678));
+        assert_eq!(r.get_f16::<LittleEndian>(), Ok(1.0));
+        assert_eq!(r.peek_u8(), Ok(1));
+        assert_eq!(r.get_uint::<BigEndian>(3), Ok(0x010203));
+        assert!(r.is_empty());
+        assert_eq!(
+            r.get_u8(),
+            Err(Error::UnexpectedEof {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert_eq!(
+            r.peek(1),
+            Err(Error::UnexpectedEof {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert_eq!(
+            r.skip(1),
+            Err(Error::UnexpectedEof {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert_eq!(r.position(), data.len());
+    }
+
+    #[test]
+    fn test_reader_split_align() {
+        let data = [3, 0xaa, 0xbb, 0xcc, 0, 0, 0, 0, 0x11, 0x22];
+        let mut r = Reader::new(&data);
+        let n = r.get_u8().unwrap();
+        let mut sub = r.split_to(n as usize).unwrap();
+        assert_eq!(sub.remaining_slice(), [0xaa, 0xbb, 0xcc]);
+        assert_eq!(sub.get_u16::<BigEndian>(), Ok(0xaabb));
+        assert_eq!(
+            sub.get_u16::<BigEndian>(),
+            Err(Error::UnexpectedEof {
+                needed: 2,
+                available: 1
+            })
+        );
+
+        r.align_to(4).unwrap();
+        assert_eq!(r.position(), 4);
+        r.align_to(4).unwrap();
+        assert_eq!(r.position(), 4);
+        r.align_to(8).unwrap();
+        assert_eq!(r.get_u16::<LittleEndian>(), Ok(0x2211));
+        assert_eq!(
+            r.align_to(4),
+            Err(Error::UnexpectedEof {
+                needed: 2,
+                available: 0
+            })
+        );
+        assert!(r.split_to(1).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_writer_vec() {
+        let mut w = Writer::new(vec![0xee]);
+        assert_eq!(w.position(), 1);
+        w.put_i16::<BigEndian>(-2).unwrap();
+        w.align_to(4).unwrap();
+        let p = w.reserve_placeholder(4).unwrap();
+        assert_eq!(p.position(), 4);
+        w.put_f32::<LittleEndian>(1.0).unwrap();
+        w.put_uint::<LittleEndian>(0x0102, 2).unwrap();
+        let n = w.position() - p.end();
+        w.patch_u32::<BigEndian>(p, n as u32);
+        assert_eq!(
+            w.into_inner(),
+            [0xee, 0xff, 0xfe, 0, 0, 0, 0, 6, 0, 0, 0x80, 0x3f, 0x02, 0x01]
+        );
+
+        let mut vec = vec![1];
+        let mut w = Writer::new(&mut vec);
+        w.put_u16::<LittleEndian>(0x0302).unwrap();
+        assert_eq!(vec, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_writer_fixed() {
+        let mut buf = [0xffu8; 5];
+        let mut w = Writer::new(&mut buf[..]);
+        let p = w.reserve_placeholder(3).unwrap();
+        w.put_u8(9).unwrap();
+        assert_eq!(
+            w.put_u16::<BigEndian>(1),
+            Err(Error::UnexpectedEof {
+                needed: 2,
+                available: 1
+            })
+        );
+        assert_eq!(w.position(), 4);
+        w.patch_uint::<BigEndian>(p, 0x123456);
+        assert_eq!(w.written(), [0x12, 0x34, 0x56, 9]);
+        assert_eq!(buf, [0x12, 0x34, 0x56, 9, 0xff]);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let mut w = Writer::new([0u8; 35]);
+        w.put_u64::<BigEndian>(u64::MAX - 1).unwrap();
+        w.put_i128::<LittleEndian>(i128::MIN).unwrap();
+        w.put_f64::<BigEndian>(-0.5).unwrap();
+        w.put_bytes(b"xyz").unwrap();
+
+        let mut r = Reader::new(w.written());
+        assert_eq!(r.get_u64::<BigEndian>(), Ok(u64::MAX - 1));
+        assert_eq!(r.get_i128::<LittleEndian>(), Ok(i128::MIN));
+        assert_eq!(r.get_f64::<BigEndian>(), Ok(-0.5));
+        assert_eq!(r.get_bytes(3), Ok(&b"xyz"[..]));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut w = Writer::new([0u8; 8]);
+        w.put_uvarint(300).unwrap();
+        w.put_varint(-65).unwrap();
+        w.put_sleb128(-65).unwrap();
+        assert_eq!(w.written(), [0xac, 0x02, 0x81, 0x01, 0xbf, 0x7f]);
+        assert_eq!(
+            w.put_uvarint(u64::MAX),
+            Err(Error::UnexpectedEof {
+                needed: 10,
+                available: 2
+            })
+        );
+
+        let mut r = Reader::new(w.written());
+        assert_eq!(r.get_uvarint(), Ok(300));
+        assert_eq!(r.get_varint(), Ok(-65));
+        assert_eq!(r.position(), 4);
+        assert_eq!(r.get_sleb128(), Ok(-65));
+        assert!(r.is_empty());
+
+        let mut r = Reader::new(&[0x80]);
+        assert!(r.get_uvarint().is_err());
+        assert_eq!(r.position(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding/binary: placeholder mismatch")]
+    fn test_patch_mismatch() {
+        let mut w = Writer::new([0u8; 8]);
+        let p = w.reserve_placeholder(2).unwrap();
+        w.patch_u32::<BigEndian>(p, 1);
+    }
+}
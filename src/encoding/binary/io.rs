@@ -0,0 +1,397 @@
+//! `std::io` extension traits reading and writing values in a [`ByteOrder`].
+//!
+//! [`ReadBytesExt`] is implemented for every [`io::Read`] and
+//! [`WriteBytesExt`] for every [`io::Write`], the byte order is picked per
+//! call with a type parameter.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::binary::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+//! let mut out = Vec::new();
+//! out.write_u16::<BigEndian>(0x1234).unwrap();
+//! out.write_f64::<LittleEndian>(1.5).unwrap();
+//!
+//! let mut src = &out[..];
+//! assert_eq!(src.read_u16::<BigEndian>().unwrap(), 0x1234);
+//! assert_eq!(src.read_f64::<LittleEndian>().unwrap(), 1.5);
+//! assert!(src.is_empty());
+//! ```
+
+use super::{check_uint, varint, ByteOrder, Error};
+use std::io;
+use std::mem::size_of;
+
+/// Size of the stack buffer the slice methods convert through
+const CHUNK_LEN: usize = 256;
+
+/// Define the single value read methods of [`ReadBytesExt`]
+macro_rules! read_methods {
+    ($($ty:ty, $size:literal => $read:ident = $decode:ident;)*) => {
+        $(
+            #[doc = concat!("Read a `", stringify!($ty), "` in byte order `E`, see [`ByteOrder::", stringify!($decode), "`]")]
+            fn $read<E: ByteOrder>(&mut self) -> io::Result<$ty> {
+                let mut b = [0; $size];
+                self.read_exact(&mut b)?;
+                Ok(E::$decode(&b))
+            }
+        )*
+    };
+}
+
+/// Define the single value write methods of [`WriteBytesExt`]
+macro_rules! write_methods {
+    ($($ty:ty, $size:literal => $write:ident = $encode:ident;)*) => {
+        $(
+            #[doc = concat!("Write a `", stringify!($ty), "` in byte order `E`, see [`ByteOrder::", stringify!($encode), "`]")]
+            fn $write<E: ByteOrder>(&mut self, n: $ty) -> io::Result<()> {
+                let mut b = [0; $size];
+                E::$encode(n, &mut b);
+                self.write_all(&b)
+            }
+        )*
+    };
+}
+
+/// Define the slice read methods of [`ReadBytesExt`]
+macro_rules! read_slice_methods {
+    ($($ty:ident => $read:ident;)*) => {
+        $(
+            #[doc = concat!("Fill `dst` with `", stringify!($ty), "` values in byte order `E`, see [`ByteOrder::", stringify!($read), "`]")]
+            fn $read<E: ByteOrder>(&mut self, dst: &mut [$ty]) -> io::Result<()> {
+                let mut buf = [0; CHUNK_LEN];
+                for chunk in dst.chunks_mut(CHUNK_LEN / size_of::<$ty>()) {
+                    let b = &mut buf[..chunk.len() * size_of::<$ty>()];
+                    self.read_exact(b)?;
+                    E::$read(b, chunk);
+                }
+                Ok(())
+            }
+        )*
+    };
+}
+
+/// Define the slice write methods of [`WriteBytesExt`]
+macro_rules! write_slice_methods {
+    ($($ty:ident => $write:ident;)*) => {
+        $(
+            #[doc = concat!("Write all of `src` in byte order `E`, see [`ByteOrder::", stringify!($write), "`]")]
+            fn $write<E: ByteOrder>(&mut self, src: &[$ty]) -> io::Result<()> {
+                let mut buf = [0; CHUNK_LEN];
+                for chunk in src.chunks(CHUNK_LEN / size_of::<$ty>()) {
+                    let b = &mut buf[..chunk.len() * size_of::<$ty>()];
+                    E::$write(chunk, b);
+                    self.write_all(b)?;
+                }
+                Ok(())
+            }
+        )*
+    };
+}
+
+/// Truncated input is [`io::ErrorKind::UnexpectedEof`], anything else
+/// [`io::ErrorKind::InvalidData`]
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        let kind = match e {
+            Error::UnexpectedEof { .. } | Error::UnexpectedEofBits { .. } => {
+                io::ErrorKind::UnexpectedEof
+            }
+            Error::VarintOverflow => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+/// Read the bytes of one LEB128 value, up to the last one or
+/// [`varint::MAX_VARINT_LEN64`] bytes
+fn read_leb128<'a, R: io::Read + ?Sized>(
+    r: &mut R,
+    buf: &'a mut [u8; varint::MAX_VARINT_LEN64],
+) -> io::Result<&'a [u8]> {
+    for i in 0..buf.len() {
+        buf[i] = r.read_u8()?;
+        if buf[i] < 0x80 {
+            return Ok(&buf[..=i]);
+        }
+    }
+    Ok(buf)
+}
+
+/// Read values in a [`ByteOrder`] from any [`io::Read`]
+///
+/// Every method reads exactly the bytes of the value, a short input is an
+/// [`io::ErrorKind::UnexpectedEof`] error.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{BigEndian, ReadBytesExt};
+/// let mut src = &[0x00, 0x01, 0x00, 0x02, 0xff][..];
+/// let mut dst = [0u16; 2];
+/// src.read_u16_into::<BigEndian>(&mut dst).unwrap();
+/// assert_eq!(dst, [1, 2]);
+/// assert_eq!(src.read_i8().unwrap(), -1);
+/// assert!(src.read_u8().is_err());
+/// ```
+pub trait ReadBytesExt: io::Read {
+    /// Read one byte
+    fn read_u8(&mut self) -> io::Result<u8> {
+        let mut b = [0; 1];
+        self.read_exact(&mut b)?;
+        Ok(b[0])
+    }
+
+    /// Read one signed byte
+    fn read_i8(&mut self) -> io::Result<i8> {
+        self.read_u8().map(|n| n as i8)
+    }
+
+    read_methods! {
+        u16, 2 => read_u16 = decode_u16;
+        u32, 4 => read_u32 = decode_u32;
+        u64, 8 => read_u64 = decode_u64;
+        u128, 16 => read_u128 = decode_u128;
+        i16, 2 => read_i16 = decode_i16;
+        i32, 4 => read_i32 = decode_i32;
+        i64, 8 => read_i64 = decode_i64;
+        i128, 16 => read_i128 = decode_i128;
+        f32, 2 => read_f16 = decode_f16;
+        f32, 4 => read_f32 = decode_f32;
+        f64, 8 => read_f64 = decode_f64;
+    }
+
+    /// Read an unsigned integer of `nbytes` bytes, see [`ByteOrder::decode_uint`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8.
+    fn read_uint<E: ByteOrder>(&mut self, nbytes: usize) -> io::Result<u64> {
+        check_uint(0, nbytes);
+        let mut b = [0; 8];
+        self.read_exact(&mut b[..nbytes])?;
+        Ok(E::decode_uint(&b, nbytes))
+    }
+
+    /// Read a LEB128 unsigned value, see [`varint::decode_uvarint`]
+    ///
+    /// Reads one byte at a time, wrap unbuffered readers in a
+    /// [`io::BufReader`].
+    fn read_uvarint(&mut self) -> io::Result<u64> {
+        let mut buf = [0; varint::MAX_VARINT_LEN64];
+        Ok(varint::decode_uvarint(read_leb128(self, &mut buf)?)?.0)
+    }
+
+    /// Read a zigzag LEB128 signed value, see [`varint::decode_varint`]
+    fn read_varint(&mut self) -> io::Result<i64> {
+        self.read_uvarint().map(varint::zigzag_decode)
+    }
+
+    /// Read a signed LEB128 value, see [`varint::decode_sleb128`]
+    fn read_sleb128(&mut self) -> io::Result<i64> {
+        let mut buf = [0; varint::MAX_VARINT_LEN64];
+        Ok(varint::decode_sleb128(read_leb128(self, &mut buf)?)?.0)
+    }
+
+    read_slice_methods! {
+        u16 => read_u16_into;
+        i16 => read_i16_into;
+        u32 => read_u32_into;
+        i32 => read_i32_into;
+        u64 => read_u64_into;
+        i64 => read_i64_into;
+        u128 => read_u128_into;
+        i128 => read_i128_into;
+        f32 => read_f32_into;
+        f64 => read_f64_into;
+    }
+}
+
+impl<R: io::Read + ?Sized> ReadBytesExt for R {}
+
+/// Write values in a [`ByteOrder`] to any [`io::Write`]
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{LittleEndian, WriteBytesExt};
+/// let mut out = Vec::new();
+/// out.write_u8(0xff).unwrap();
+/// out.write_u32_from_slice::<LittleEndian>(&[1, 2]).unwrap();
+/// out.write_uint::<LittleEndian>(0x123456, 3).unwrap();
+/// assert_eq!(out, [0xff, 1, 0, 0, 0, 2, 0, 0, 0, 0x56, 0x34, 0x12]);
+/// ```
+pub trait WriteBytesExt: io::Write {
+    /// Write one byte
+    fn write_u8(&mut self, n: u8) -> io::Result<()> {
+        self.write_all(&[n])
+    }
+
+    /// Write one signed byte
+    fn write_i8(&mut self, n: i8) -> io::Result<()> {
+        self.write_u8(n as u8)
+    }
+
+    write_methods! {
+        u16, 2 => write_u16 = encode_u16;
+        u32, 4 => write_u32 = encode_u32;
+        u64, 8 => write_u64 = encode_u64;
+        u128, 16 => write_u128 = encode_u128;
+        i16, 2 => write_i16 = encode_i16;
+        i32, 4 => write_i32 = encode_i32;
+        i64, 8 => write_i64 = encode_i64;
+        i128, 16 => write_i128 = encode_i128;
+        f32, 2 => write_f16 = encode_f16;
+        f32, 4 => write_f32 = encode_f32;
+        f64, 8 => write_f64 = encode_f64;
+    }
+
+    /// Write the low `nbytes` bytes of an unsigned integer, see
+    /// [`ByteOrder::encode_uint`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if `nbytes` is not in 1..=8 or `n` does not fit.
+    fn write_uint<E: ByteOrder>(&mut self, n: u64, nbytes: usize) -> io::Result<()> {
+        let mut b = [0; 8];
+        E::encode_uint(n, nbytes, &mut b);
+        self.write_all(&b[..nbytes])
+    }
+
+    /// Write a LEB128 unsigned value, see [`varint::encode_uvarint`]
+    fn write_uvarint(&mut self, n: u64) -> io::Result<()> {
+        let mut b = [0; varint::MAX_VARINT_LEN64];
+        let len = varint::encode_uvarint(n, &mut b);
+        self.write_all(&b[..len])
+    }
+
+    /// Write a zigzag LEB128 signed value, see [`varint::encode_varint`]
+    fn write_varint(&mut self, n: i64) -> io::Result<()> {
+        self.write_uvarint(varint::zigzag_encode(n))
+    }
+
+    /// Write a signed LEB128 value, see [`varint::encode_sleb128`]
+    fn write_sleb128(&mut self, n: i64) -> io::Result<()> {
+        let mut b = [0; varint::MAX_VARINT_LEN64];
+        let len = varint::encode_sleb128(n, &mut b);
+        self.write_all(&b[..len])
+    }
+
+    write_slice_methods! {
+        u16 => write_u16_from_slice;
+        i16 => write_i16_from_slice;
+        u32 => write_u32_from_slice;
+        i32 => write_i32_from_slice;
+        u64 => write_u64_from_slice;
+        i64 => write_i64_from_slice;
+        u128 => write_u128_from_slice;
+        i128 => write_i128_from_slice;
+        f32 => write_f32_from_slice;
+        f64 => write_f64_from_slice;
+    }
+}
+
+impl<W: io::Write + ?Sized> WriteBytesExt for W {}
+
+#[cfg(test)]
+mod tests_binary_io {
+    use super::*;
+    use crate::encoding::binary::{BigEndian, LittleEndian};
+
+    #[test]
+    fn test_roundtrip() {
+        let mut out = Vec::new();
+        out.write_u8(1).unwrap();
+        out.write_i8(-2).unwrap();
+        out.write_u16::<BigEndian>(0x0304).unwrap();
+        out.write_u32::<LittleEndian>(0x08070605).unwrap();
+        out.write_i64::<BigEndian>(-9).unwrap();
+        out.write_u128::<LittleEndian>(u128::MAX - 1).unwrap();
+        out.write_f16::<BigEndian>(1.0).unwrap();
+        out.write_f32::<LittleEndian>(-2.5).unwrap();
+        out.write_uint::<BigEndian>(0x0a0b0c, 3).unwrap();
+        assert_eq!(out.len(), 1 + 1 + 2 + 4 + 8 + 16 + 2 + 4 + 3);
+        assert_eq!(out[..8], [1, 0xfe, 3, 4, 5, 6, 7, 8]);
+
+        let mut src = &out[..];
+        assert_eq!(src.read_u8().unwrap(), 1);
+        assert_eq!(src.read_i8().unwrap(), -2);
+        assert_eq!(src.read_u16::<BigEndian>().unwrap(), 0x0304);
+        assert_eq!(src.read_u32::<LittleEndian>().unwrap(), 0x08070605);
+        assert_eq!(src.read_i64::<BigEndian>().unwrap(), -9);
+        assert_eq!(src.read_u128::<LittleEndian>().unwrap(), u128::MAX - 1);
+        assert_eq!(src.read_f16::<BigEndian>().unwrap(), 1.0);
+        assert_eq!(src.read_f32::<LittleEndian>().unwrap(), -2.5);
+        assert_eq!(src.read_uint::<BigEndian>(3).unwrap(), 0x0a0b0c);
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_eof() {
+        let mut src = &[1, 2, 3][..];
+        let err = src.read_u32::<BigEndian>().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut dst = [0u64; 2];
+        let mut src = &[0; 15][..];
+        let err = src.read_u64_into::<LittleEndian>(&mut dst).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_slices() {
+        // More values than one stack chunk holds
+        let values: Vec<i32> = (0..1000).map(|i| i * 7919 - 500_000).collect();
+        let mut out = Vec::new();
+        out.write_i32_from_slice::<BigEndian>(&values).unwrap();
+        assert_eq!(out.len(), values.len() * 4);
+        assert_eq!(out[4..8], (7919 - 500_000i32).to_be_bytes());
+
+        let mut back = vec![0; values.len()];
+        (&out[..]).read_i32_into::<BigEndian>(&mut back).unwrap();
+        assert_eq!(back, values);
+
+        let floats = [0.5f64, -1.25, f64::MAX];
+        let mut out = Vec::new();
+        out.write_f64_from_slice::<LittleEndian>(&floats).unwrap();
+        let mut back = [0.0; 3];
+        (&out[..]).read_f64_into::<LittleEndian>(&mut back).unwrap();
+        assert_eq!(back, floats);
+    }
+
+    #[test]
+    fn test_varint() {
+        let mut out = Vec::new();
+        out.write_uvarint(u64::MAX).unwrap();
+        out.write_varint(-3).unwrap();
+        out.write_sleb128(i64::MIN).unwrap();
+        assert_eq!(out.len(), 10 + 1 + 10);
+
+        let mut src = &out[..];
+        assert_eq!(src.read_uvarint().unwrap(), u64::MAX);
+        assert_eq!(src.read_varint().unwrap(), -3);
+        assert_eq!(src.read_sleb128().unwrap(), i64::MIN);
+        assert!(src.is_empty());
+
+        let err = (&[0x80, 0x80][..]).read_uvarint().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let err = (&[0xff; 12][..]).read_uvarint().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "encoding/binary: variable-length integer overflows 64 bits"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding/binary: invalid integer width")]
+    fn test_read_uint_width() {
+        let _ = (&[0; 9][..]).read_uint::<BigEndian>(9);
+    }
+}
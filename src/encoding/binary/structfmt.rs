@@ -0,0 +1,808 @@
+//! Pack and unpack with Python `struct` format strings.
+//!
+//! Formats are the ones of Python's `struct` module, so packet templates are
+//! shared verbatim between Python scripts and Rust code:
+//!
+//! | First character | Byte order | Size and alignment |
+//! |-----------------|------------|--------------------|
+//! | `@` (default)   | native     | native, aligned    |
+//! | `=`             | native     | standard           |
+//! | `<`             | little     | standard           |
+//! | `>`, `!`        | big        | standard           |
+//!
+//! | Format | Standard size | [`Value`] |
+//! |--------|---------------|-----------|
+//! | `x`    | 1, padding    | none      |
+//! | `c`    | 1             | [`Value::Bytes`] of length 1 |
+//! | `b` `B`| 1             | [`Value::Int`] |
+//! | `?`    | 1             | [`Value::Bool`] |
+//! | `h` `H`| 2             | [`Value::Int`] |
+//! | `i` `I` `l` `L` | 4    | [`Value::Int`] |
+//! | `q` `Q`| 8             | [`Value::Int`] |
+//! | `n` `N` `P` | native only | [`Value::Int`] |
+//! | `e`    | 2             | [`Value::Float`] |
+//! | `f`    | 4             | [`Value::Float`] |
+//! | `d`    | 8             | [`Value::Float`] |
+//! | `s`    | count         | [`Value::Bytes`] |
+//! | `p`    | count         | [`Value::Bytes`], Pascal string |
+//!
+//! A count before a format repeats it, `4h` is `hhhh`, except for `s` and
+//! `p` where it is the byte length and `x` where it is the padding length.
+//! Whitespace between formats is ignored. Native mode aligns every value to
+//! its C alignment, with no padding at the end.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::binary::structfmt::{self, Value};
+//! let values = [
+//!     Value::Int(0x01020304),
+//!     Value::Int(5),
+//!     Value::Int(-1),
+//!     Value::Bytes(b"abc".to_vec()),
+//! ];
+//! let b = structfmt::pack("<IHh4s", &values).unwrap();
+//! assert_eq!(b, b"\x04\x03\x02\x01\x05\x00\xff\xffabc\x00");
+//! assert_eq!(structfmt::calcsize("<IHh4s"), Ok(12));
+//! assert_eq!(structfmt::unpack("<IHh4s", &b).unwrap()[3], Value::Bytes(b"abc\x00".to_vec()));
+//! ```
+
+use super::{f16_bits_to_f32, f64_to_f16_bits, Endianness};
+use alloc::{vec, vec::Vec};
+use core::ffi::{c_int, c_long, c_longlong, c_short};
+use core::fmt;
+use core::mem::{align_of, size_of};
+
+/// Errors of format parsing, packing and unpacking
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StructError {
+    /// Unknown format character
+    BadChar { offset: usize, ch: char },
+    /// Repeat count with no format character after it
+    MissingFormat { offset: usize },
+    /// Format character allowed in native mode `@` only
+    NativeOnly { offset: usize, ch: char },
+    /// Repeat count or total size too large
+    TooLarge,
+    /// Number of values not matching the format
+    ValueCount { expected: usize, got: usize },
+    /// Value of the wrong kind for its format character
+    WrongType {
+        index: usize,
+        expected: &'static str,
+    },
+    /// Number out of the range of its format character
+    OutOfRange { index: usize },
+    /// Buffer shorter or longer than the format
+    BufferSize { needed: usize, available: usize },
+}
+
+impl fmt::Display for StructError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadChar { offset, ch } => write!(
+                f,
+                "encoding/binary: bad char {:?} in struct format at offset {}",
+                ch, offset
+            ),
+            Self::MissingFormat { offset } => write!(
+                f,
+                "encoding/binary: repeat count without format char at offset {}",
+                offset
+            ),
+            Self::NativeOnly { offset, ch } => write!(
+                f,
+                "encoding/binary: format char {:?} at offset {} needs native mode '@'",
+                ch, offset
+            ),
+            Self::TooLarge => write!(f, "encoding/binary: struct format too large"),
+            Self::ValueCount { expected, got } => write!(
+                f,
+                "encoding/binary: struct format expects {} values, got {}",
+                expected, got
+            ),
+            Self::WrongType { index, expected } => write!(
+                f,
+                "encoding/binary: struct value {} must be {}",
+                index, expected
+            ),
+            Self::OutOfRange { index } => write!(
+                f,
+                "encoding/binary: struct value {} out of range for its format",
+                index
+            ),
+            Self::BufferSize { needed, available } => write!(
+                f,
+                "encoding/binary: struct format needs a buffer of {} bytes, got {}",
+                needed, available
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StructError {}
+
+/// A packed or unpacked value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    /// `?`
+    Bool(bool),
+    /// Integer formats, wide enough for both `q` and `Q`
+    Int(i128),
+    /// `e`, `f` and `d`
+    Float(f64),
+    /// `c`, `s` and `p`
+    Bytes(Vec<u8>),
+}
+
+impl Value {
+    /// The integer, if an [`Value::Int`]
+    pub fn as_int(&self) -> Option<i128> {
+        match self {
+            Self::Int(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    /// The float, if a [`Value::Float`]
+    pub fn as_float(&self) -> Option<f64> {
+        match self {
+            Self::Float(x) => Some(*x),
+            _ => None,
+        }
+    }
+
+    /// The bool, if a [`Value::Bool`]
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// The bytes, if a [`Value::Bytes`]
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Self::Bytes(b) => Some(b),
+            _ => None,
+        }
+    }
+}
+
+/// Define `From` conversions into [`Value`]
+macro_rules! value_from {
+    ($($variant:ident($($ty:ty),*);)*) => {
+        $($(
+            impl From<$ty> for Value {
+                fn from(v: $ty) -> Self {
+                    Self::$variant(v.into())
+                }
+            }
+        )*)*
+    };
+}
+
+value_from! {
+    Bool(bool);
+    Int(i8, i16, i32, i64, u8, u16, u32, u64);
+    Float(f32, f64);
+    Bytes(Vec<u8>, &[u8]);
+}
+
+/// One format character with its count
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Item {
+    code: u8,
+    count: usize,
+    /// Offset of the first byte, after alignment
+    offset: usize,
+    /// Size of one value, 1 for `x`, `s` and `p`
+    size: usize,
+}
+
+/// A parsed format string
+///
+/// Parse once with [`Format::parse`] to pack and unpack many times.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::structfmt::{Format, Value};
+/// let format = Format::parse("!BBH").unwrap();
+/// assert_eq!(format.size(), 4);
+/// let mut buf = [0xff; 6];
+/// format.pack_into(&mut buf, 2, &[1u8.into(), 2u8.into(), 0x0304u16.into()]).unwrap();
+/// assert_eq!(buf, [0xff, 0xff, 1, 2, 3, 4]);
+/// assert_eq!(
+///     format.unpack_from(&buf, 2).unwrap(),
+///     [Value::Int(1), Value::Int(2), Value::Int(0x0304)]
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    endianness: Endianness,
+    items: Vec<Item>,
+    size: usize,
+    values: usize,
+}
+
+/// Size and alignment of a format character, `None` when unknown or
+/// native only outside of native mode
+fn size_align(code: u8, native: bool) -> Option<(usize, usize)> {
+    let standard = match code {
+        b'x' | b'c' | b'b' | b'B' | b'?' | b's' | b'p' => 1,
+        b'h' | b'H' | b'e' => 2,
+        b'i' | b'I' | b'l' | b'L' | b'f' => 4,
+        b'q' | b'Q' | b'd' => 8,
+        b'n' | b'N' | b'P' if native => 0,
+        _ => return None,
+    };
+    if !native {
+        return Some((standard, 1));
+    }
+    Some(match code {
+        b'h' | b'H' => (size_of::<c_short>(), align_of::<c_short>()),
+        b'i' | b'I' => (size_of::<c_int>(), align_of::<c_int>()),
+        b'l' | b'L' => (size_of::<c_long>(), align_of::<c_long>()),
+        b'q' | b'Q' => (size_of::<c_longlong>(), align_of::<c_longlong>()),
+        b'n' | b'N' | b'P' => (size_of::<usize>(), align_of::<usize>()),
+        b'e' => (2, 2),
+        b'f' => (size_of::<f32>(), align_of::<f32>()),
+        b'd' => (size_of::<f64>(), align_of::<f64>()),
+        _ => (1, 1),
+    })
+}
+
+impl Format {
+    /// Parse a format string
+    ///
+    /// - @param fmt: Python `struct` format
+    /// - @return: parsed format
+    pub fn parse(fmt: &str) -> Result<Self, StructError> {
+        let b = fmt.as_bytes();
+        let (endianness, native, mut i) = match b.first() {
+            Some(b'@') => (Endianness::native(), true, 1),
+            Some(b'=') => (Endianness::native(), false, 1),
+            Some(b'<') => (Endianness::Little, false, 1),
+            Some(b'>' | b'!') => (Endianness::Big, false, 1),
+            _ => (Endianness::native(), true, 0),
+        };
+        let mut items = Vec::new();
+        let mut size: usize = 0;
+        let mut values: usize = 0;
+        loop {
+            while i < b.len() && b[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            if i == b.len() {
+                break;
+            }
+            let start = i;
+            let mut count = None;
+            while i < b.len() && b[i].is_ascii_digit() {
+                let digit = usize::from(b[i] - b'0');
+                let n = count.unwrap_or(0usize);
+                count = Some(
+                    n.checked_mul(10)
+                        .and_then(|n| n.checked_add(digit))
+                        .ok_or(StructError::TooLarge)?,
+                );
+                i += 1;
+            }
+            let Some(&code) = b.get(i).filter(|c| !c.is_ascii_whitespace()) else {
+                return Err(StructError::MissingFormat { offset: start });
+            };
+            let (one, align) = size_align(code, native).ok_or_else(|| {
+                let ch = fmt[i..].chars().next().unwrap_or_default();
+                if matches!(code, b'n' | b'N' | b'P') {
+                    StructError::NativeOnly { offset: i, ch }
+                } else {
+                    StructError::BadChar { offset: i, ch }
+                }
+            })?;
+            i += 1;
+            let count = count.unwrap_or(1);
+            let offset = size
+                .checked_next_multiple_of(align)
+                .ok_or(StructError::TooLarge)?;
+            let total = one.checked_mul(count).ok_or(StructError::TooLarge)?;
+            size = offset.checked_add(total).ok_or(StructError::TooLarge)?;
+            values += match code {
+                b'x' => 0,
+                b's' | b'p' => 1,
+                _ => count,
+            };
+            items.push(Item {
+                code,
+                count,
+                offset,
+                size: one,
+            });
+        }
+        Ok(Self {
+            endianness,
+            items,
+            size,
+            values,
+        })
+    }
+
+    /// Packed size in bytes, Python's `calcsize`
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of values packed and unpacked
+    pub fn len(&self) -> usize {
+        self.values
+    }
+
+    /// Whether the format packs no values
+    pub fn is_empty(&self) -> bool {
+        self.values == 0
+    }
+
+    /// Pack values into a new buffer of [`size`](Self::size) bytes
+    pub fn pack(&self, values: &[Value]) -> Result<Vec<u8>, StructError> {
+        let mut out = vec![0; self.size];
+        self.write(&mut out, values)?;
+        Ok(out)
+    }
+
+    /// Pack values into `buf` from `offset`, padding bytes are zeroed
+    pub fn pack_into(
+        &self,
+        buf: &mut [u8],
+        offset: usize,
+        values: &[Value],
+    ) -> Result<(), StructError> {
+        let out = self.region(buf.len(), offset).map(|r| &mut buf[r])?;
+        out.fill(0);
+        self.write(out, values)
+    }
+
+    /// Unpack exactly [`size`](Self::size) bytes
+    pub fn unpack(&self, b: &[u8]) -> Result<Vec<Value>, StructError> {
+        if b.len() != self.size {
+            return Err(StructError::BufferSize {
+                needed: self.size,
+                available: b.len(),
+            });
+        }
+        Ok(self.read(b))
+    }
+
+    /// Unpack from `offset` of `buf`, ignoring the bytes after the format
+    pub fn unpack_from(&self, buf: &[u8], offset: usize) -> Result<Vec<Value>, StructError> {
+        let b = self.region(buf.len(), offset).map(|r| &buf[r])?;
+        Ok(self.read(b))
+    }
+
+    /// The range of a buffer of `len` bytes the format covers from `offset`
+    fn region(&self, len: usize, offset: usize) -> Result<core::ops::Range<usize>, StructError> {
+        match offset.checked_add(self.size) {
+            Some(end) if end <= len => Ok(offset..end),
+            _ => Err(StructError::BufferSize {
+                needed: offset.saturating_add(self.size),
+                available: len,
+            }),
+        }
+    }
+
+    /// Pack into `out`, exactly [`size`](Self::size) zeroed bytes
+    fn write(&self, out: &mut [u8], values: &[Value]) -> Result<(), StructError> {
+        if values.len() != self.values {
+            return Err(StructError::ValueCount {
+                expected: self.values,
+                got: values.len(),
+            });
+        }
+        let mut index = 0;
+        for item in &self.items {
+            let dst = &mut out[item.offset..item.offset + item.size * item.count];
+            match item.code {
+                b'x' => {}
+                b's' | b'p' => {
+                    let src = bytes(&values[index], index, "bytes")?;
+                    if item.code == b's' {
+                        let n = src.len().min(item.count);
+                        dst[..n].copy_from_slice(&src[..n]);
+                    } else if item.count > 0 {
+                        // the length byte saturates, the copy does not
+                        let n = src.len().min(item.count - 1);
+                        dst[0] = n.min(255) as u8;
+                        dst[1..=n].copy_from_slice(&src[..n]);
+                    }
+                    index += 1;
+                }
+                _ => {
+                    for chunk in dst.chunks_exact_mut(item.size) {
+                        self.write_one(item.code, &values[index], index, chunk)?;
+                        index += 1;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Pack one value of a fixed size format
+    fn write_one(
+        &self,
+        code: u8,
+        value: &Value,
+        index: usize,
+        b: &mut [u8],
+    ) -> Result<(), StructError> {
+        let e = self.endianness;
+        match code {
+            b'c' => match bytes(value, index, "bytes of length 1")? {
+                [c] => b[0] = *c,
+                _ => {
+                    return Err(StructError::WrongType {
+                        index,
+                        expected: "bytes of length 1",
+                    })
+                }
+            },
+            b'?' => match value {
+                Value::Bool(v) => b[0] = u8::from(*v),
+                _ => {
+                    return Err(StructError::WrongType {
+                        index,
+                        expected: "a bool",
+                    })
+                }
+            },
+            b'e' | b'f' | b'd' => {
+                let Value::Float(x) = *value else {
+                    return Err(StructError::WrongType {
+                        index,
+                        expected: "a float",
+                    });
+                };
+                match code {
+                    b'e' => {
+                        let h = f64_to_f16_bits(x);
+                        if x.is_finite() && h & 0x7fff == 0x7c00 {
+                            return Err(StructError::OutOfRange { index });
+                        }
+                        e.encode_u16(h, b);
+                    }
+                    b'f' => {
+                        if x.is_finite() && (x as f32).is_infinite() {
+                            return Err(StructError::OutOfRange { index });
+                        }
+                        e.encode_f32(x as f32, b);
+                    }
+                    _ => e.encode_f64(x, b),
+                }
+            }
+            _ => {
+                let Value::Int(n) = *value else {
+                    return Err(StructError::WrongType {
+                        index,
+                        expected: "an int",
+                    });
+                };
+                let bits = 8 * b.len() as u32;
+                let (min, max) = if code.is_ascii_lowercase() {
+                    (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+                } else {
+                    (0, (1i128 << bits) - 1)
+                };
+                if n < min || n > max {
+                    return Err(StructError::OutOfRange { index });
+                }
+                let raw = n as u64 & (u64::MAX >> (64 - bits));
+                e.encode_uint(raw, b.len(), b);
+            }
+        }
+        Ok(())
+    }
+
+    /// Unpack `b`, exactly [`size`](Self::size) bytes
+    fn read(&self, b: &[u8]) -> Vec<Value> {
+        let mut values = Vec::with_capacity(self.values);
+        for item in &self.items {
+            let src = &b[item.offset..item.offset + item.size * item.count];
+            match item.code {
+                b'x' => {}
+                b's' => values.push(Value::Bytes(src.to_vec())),
+                b'p' => match src.split_first() {
+                    Some((&n, data)) => {
+                        let n = usize::from(n).min(data.len());
+                        values.push(Value::Bytes(data[..n].to_vec()));
+                    }
+                    None => values.push(Value::Bytes(Vec::new())),
+                },
+                _ => {
+                    for chunk in src.chunks_exact(item.size) {
+                        values.push(self.read_one(item.code, chunk));
+                    }
+                }
+            }
+        }
+        values
+    }
+
+    /// Unpack one value of a fixed size format
+    fn read_one(&self, code: u8, b: &[u8]) -> Value {
+        let e = self.endianness;
+        match code {
+            b'c' => Value::Bytes(b.to_vec()),
+            b'?' => Value::Bool(b[0] != 0),
+            b'e' => Value::Float(f64::from(f16_bits_to_f32(e.decode_u16(b)))),
+            b'f' => Value::Float(f64::from(e.decode_f32(b))),
+            b'd' => Value::Float(e.decode_f64(b)),
+            _ => {
+                let raw = e.decode_uint(b, b.len());
+                if code.is_ascii_lowercase() {
+                    let shift = 64 - 8 * b.len() as u32;
+                    Value::Int(i128::from((raw << shift) as i64 >> shift))
+                } else {
+                    Value::Int(i128::from(raw))
+                }
+            }
+        }
+    }
+}
+
+/// The bytes of a value, or a [`StructError::WrongType`]
+fn bytes<'v>(
+    value: &'v Value,
+    index: usize,
+    expected: &'static str,
+) -> Result<&'v [u8], StructError> {
+    value
+        .as_bytes()
+        .ok_or(StructError::WrongType { index, expected })
+}
+
+/// Packed size of a format, Python's `struct.calcsize`
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::structfmt::{self, StructError};
+/// assert_eq!(structfmt::calcsize("<IHH6sq"), Ok(4 + 2 + 2 + 6 + 8));
+/// assert_eq!(
+///     structfmt::calcsize("<3"),
+///     Err(StructError::MissingFormat { offset: 1 })
+/// );
+/// ```
+pub fn calcsize(fmt: &str) -> Result<usize, StructError> {
+    Format::parse(fmt).map(|f| f.size())
+}
+
+/// Pack values with a format, Python's `struct.pack`
+pub fn pack(fmt: &str, values: &[Value]) -> Result<Vec<u8>, StructError> {
+    Format::parse(fmt)?.pack(values)
+}
+
+/// Unpack exactly the bytes of a format, Python's `struct.unpack`
+pub fn unpack(fmt: &str, b: &[u8]) -> Result<Vec<Value>, StructError> {
+    Format::parse(fmt)?.unpack(b)
+}
+
+#[cfg(test)]
+mod tests_structfmt {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_calcsize() {
+        assert_eq!(calcsize(""), Ok(0));
+        assert_eq!(calcsize("<IHH6sq"), Ok(22));
+        assert_eq!(calcsize("!BBH"), Ok(4));
+        assert_eq!(calcsize("> 2h x 3s ? e"), Ok(4 + 1 + 3 + 1 + 2));
+        assert_eq!(calcsize("=0s0p10x"), Ok(10));
+        // Native alignment, no padding at the end
+        assert_eq!(calcsize("@ci"), Ok(8));
+        assert_eq!(calcsize("ic"), Ok(5));
+        assert_eq!(calcsize("cd"), Ok(16));
+        assert_eq!(calcsize("c0i"), Ok(4));
+        assert_eq!(calcsize("cP"), Ok(2 * size_of::<usize>()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            calcsize("<Iz"),
+            Err(StructError::BadChar { offset: 2, ch: 'z' })
+        );
+        assert_eq!(
+            calcsize("<4 h"),
+            Err(StructError::MissingFormat { offset: 1 })
+        );
+        assert_eq!(
+            calcsize("!hP"),
+            Err(StructError::NativeOnly { offset: 2, ch: 'P' })
+        );
+        assert_eq!(
+            calcsize("<é"),
+            Err(StructError::BadChar {
+                offset: 1, ch: 'é'
+            })
+        );
+        assert_eq!(
+            calcsize("99999999999999999999s"),
+            Err(StructError::TooLarge)
+        );
+        assert_eq!(
+            calcsize("<18446744073709551615s1s"),
+            Err(StructError::TooLarge)
+        );
+        assert_eq!(
+            StructError::BadChar { offset: 2, ch: 'z' }.to_string(),
+            "encoding/binary: bad char 'z' in struct format at offset 2"
+        );
+    }
+
+    #[test]
+    fn test_pack() {
+        // struct.pack("<IHH6sq", 1, 2, 3, b"abc", -2)
+        let values = [
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Bytes(b"abc".to_vec()),
+            Value::Int(-2),
+        ];
+        let b = pack("<IHH6sq", &values).unwrap();
+        assert_eq!(
+            b,
+            b"\x01\x00\x00\x00\x02\x00\x03\x00abc\x00\x00\x00\xfe\xff\xff\xff\xff\xff\xff\xff"
+        );
+        let mut expected = values.to_vec();
+        expected[3] = Value::Bytes(b"abc\x00\x00\x00".to_vec());
+        assert_eq!(unpack("<IHH6sq", &b).unwrap(), expected);
+
+        // struct.pack(">?ceQ3p", True, b"z", 1.5, 2**64 - 1, b"hello")
+        let values = [
+            Value::Bool(true),
+            Value::Bytes(b"z".to_vec()),
+            Value::Float(1.5),
+            Value::Int(u64::MAX.into()),
+            Value::Bytes(b"hello".to_vec()),
+        ];
+        let b = pack(">?ceQ3p", &values).unwrap();
+        assert_eq!(b, b"\x01z\x3e\x00\xff\xff\xff\xff\xff\xff\xff\xff\x02he");
+        let mut expected = values.to_vec();
+        expected[4] = Value::Bytes(b"he".to_vec());
+        assert_eq!(unpack(">?ceQ3p", &b).unwrap(), expected);
+        assert_eq!(unpack("0p", &[]).unwrap(), [Value::Bytes(Vec::new())]);
+        assert_eq!(
+            unpack("1p2p", &[5, 5, b'a']).unwrap()[1],
+            Value::Bytes(b"a".to_vec())
+        );
+        // struct.pack("300p", b"a" * 299)
+        let b = pack("300p", &[Value::Bytes(vec![b'a'; 299])]).unwrap();
+        assert_eq!(b[0], 255);
+        assert_eq!(&b[256..260], b"aaaa");
+        assert_eq!(b[299], b'a');
+
+        // struct.pack("<e", 1 + 2**-11 + 2**-40), rounded once from f64
+        let x = 1.0 + 2f64.powi(-11) + 2f64.powi(-40);
+        assert_eq!(pack("<e", &[x.into()]).unwrap(), b"\x01\x3c");
+        assert_eq!(pack("<e", &[65519.99.into()]).unwrap(), b"\xff\x7b");
+
+        // struct.pack("!2b2x f d", -128, 127, 0.25, -3.0)
+        let b = pack(
+            "!2b2x f d",
+            &[(-128i8).into(), 127i8.into(), 0.25f32.into(), (-3.0).into()],
+        )
+        .unwrap();
+        assert_eq!(
+            b,
+            b"\x80\x7f\x00\x00\x3e\x80\x00\x00\xc0\x08\x00\x00\x00\x00\x00\x00"
+        );
+        assert_eq!(
+            unpack("!2b2x f d", &b).unwrap(),
+            [
+                Value::Int(-128),
+                Value::Int(127),
+                Value::Float(0.25),
+                Value::Float(-3.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_native() {
+        let format = Format::parse("@bi").unwrap();
+        assert_eq!(format.len(), 2);
+        let b = format.pack(&[Value::Int(1), Value::Int(2)]).unwrap();
+        let mut expected = [0; 8];
+        expected[0] = 1;
+        expected[4..].copy_from_slice(&2i32.to_ne_bytes());
+        assert_eq!(b, expected);
+        assert_eq!(format.unpack(&b).unwrap(), [Value::Int(1), Value::Int(2)]);
+    }
+
+    #[test]
+    fn test_pack_errors() {
+        let format = Format::parse("<Bh").unwrap();
+        assert_eq!(
+            format.pack(&[Value::Int(1)]),
+            Err(StructError::ValueCount {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert_eq!(
+            format.pack(&[Value::Int(256), Value::Int(0)]),
+            Err(StructError::OutOfRange { index: 0 })
+        );
+        assert_eq!(
+            format.pack(&[Value::Int(-1), Value::Int(0)]),
+            Err(StructError::OutOfRange { index: 0 })
+        );
+        assert_eq!(
+            format.pack(&[Value::Int(0), Value::Int(-32769)]),
+            Err(StructError::OutOfRange { index: 1 })
+        );
+        assert_eq!(
+            format.pack(&[Value::Int(0), Value::Float(1.0)]),
+            Err(StructError::WrongType {
+                index: 1,
+                expected: "an int"
+            })
+        );
+        assert_eq!(
+            pack("c", &[Value::Bytes(b"ab".to_vec())]),
+            Err(StructError::WrongType {
+                index: 0,
+                expected: "bytes of length 1"
+            })
+        );
+        assert_eq!(
+            pack("<f", &[Value::Float(1e300)]),
+            Err(StructError::OutOfRange { index: 0 })
+        );
+        assert_eq!(
+            pack("<e", &[Value::Float(70000.0)]),
+            Err(StructError::OutOfRange { index: 0 })
+        );
+        assert!(pack("<e", &[Value::Float(f64::INFINITY)]).is_ok());
+        assert_eq!(
+            unpack("<h", &[0; 3]),
+            Err(StructError::BufferSize {
+                needed: 2,
+                available: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_into_from() {
+        let format = Format::parse("<xH").unwrap();
+        let mut buf = [0xee; 5];
+        format
+            .pack_into(&mut buf, 1, &[Value::Int(0x0102)])
+            .unwrap();
+        assert_eq!(buf, [0xee, 0, 2, 1, 0xee]);
+        assert_eq!(format.unpack_from(&buf, 1).unwrap(), [Value::Int(0x0102)]);
+        assert_eq!(
+            format.unpack_from(&buf, 3),
+            Err(StructError::BufferSize {
+                needed: 6,
+                available: 5
+            })
+        );
+        assert_eq!(
+            format.pack_into(&mut buf, usize::MAX, &[Value::Int(0)]),
+            Err(StructError::BufferSize {
+                needed: usize::MAX,
+                available: 5
+            })
+        );
+    }
+}
@@ -0,0 +1,444 @@
+//! Zero-copy views of byte buffers as typed, fixed layout structs.
+//!
+//! [`U16`], [`U32`], [`I64`], [`F32`] and friends hold a number as bytes in
+//! a [`ByteOrder`], with alignment 1, so they sit anywhere in a buffer.
+//! Structs made only of them, bytes and arrays, declared with
+//! [`binary_struct!`](crate::binary_struct), are [`FromBytes`]: a `&[u8]`
+//! can be viewed as `&Header` without copying.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::binary_struct;
+//! use connx::encoding::binary::{self, BigEndian, U16, U32};
+//!
+//! binary_struct! {
+//!     /// Fixed layout packet header
+//!     #[derive(Debug)]
+//!     pub struct Header {
+//!         pub magic: U32<BigEndian>,
+//!         pub kind: u8,
+//!         pub flags: u8,
+//!         pub len: U16<BigEndian>,
+//!     }
+//! }
+//!
+//! let packet = [0xca, 0xfe, 0xba, 0xbe, 7, 0, 0x00, 0x02, 0xaa, 0xbb];
+//! let (header, payload) = binary::ref_from_prefix::<Header>(&packet).unwrap();
+//! assert_eq!(header.magic.get(), 0xcafebabe);
+//! assert_eq!(header.kind, 7);
+//! assert_eq!(header.len.get(), 2);
+//! assert_eq!(payload, [0xaa, 0xbb]);
+//! ```
+
+use super::{ByteOrder, Error};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
+
+/// Types any byte sequence of the right size is a valid value of
+///
+/// Implemented for `u8`, `i8`, the byte order types of this module, arrays
+/// of them and structs declared with [`binary_struct!`](crate::binary_struct).
+///
+/// # Safety
+///
+/// Implementors must have alignment 1, no padding bytes, and every byte
+/// pattern must be a valid value.
+pub unsafe trait FromBytes: Sized {}
+
+// SAFETY: alignment 1, no padding, all patterns valid
+unsafe impl FromBytes for u8 {}
+
+// SAFETY: alignment 1, no padding, all patterns valid
+unsafe impl FromBytes for i8 {}
+
+// SAFETY: arrays have the alignment of the element and no padding between
+// elements
+unsafe impl<T: FromBytes, const N: usize> FromBytes for [T; N] {}
+
+/// Compile time check that a [`FromBytes`] type has alignment 1
+struct AlignOne<T>(PhantomData<T>);
+
+impl<T> AlignOne<T> {
+    const CHECK: () = assert!(align_of::<T>() == 1, "encoding/binary: alignment not 1");
+}
+
+/// View the start of `b` as a `T`
+///
+/// - @param b: at least `size_of::<T>()` bytes
+/// - @return: the view and the bytes after it
+pub fn ref_from_prefix<T: FromBytes>(b: &[u8]) -> Result<(&T, &[u8]), Error> {
+    let () = AlignOne::<T>::CHECK;
+    super::check_len(size_of::<T>(), b.len())?;
+    let (head, rest) = b.split_at(size_of::<T>());
+    // SAFETY: `head` has the size of `T`, `T` has alignment 1 and accepts
+    // any bytes
+    Ok((unsafe { &*head.as_ptr().cast::<T>() }, rest))
+}
+
+/// View the start of `b` as a mutable `T`
+///
+/// - @param b: at least `size_of::<T>()` bytes
+/// - @return: the view and the bytes after it
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{self, LittleEndian, U32};
+/// let mut b = [0; 6];
+/// let (n, _) = binary::mut_from_prefix::<U32<LittleEndian>>(&mut b).unwrap();
+/// n.set(0x12345678);
+/// assert_eq!(b, [0x78, 0x56, 0x34, 0x12, 0, 0]);
+/// ```
+pub fn mut_from_prefix<T: FromBytes>(b: &mut [u8]) -> Result<(&mut T, &mut [u8]), Error> {
+    let () = AlignOne::<T>::CHECK;
+    super::check_len(size_of::<T>(), b.len())?;
+    let (head, rest) = b.split_at_mut(size_of::<T>());
+    // SAFETY: as in `ref_from_prefix`, and any value written through the
+    // view is valid bytes since `T` has no padding
+    Ok((unsafe { &mut *head.as_mut_ptr().cast::<T>() }, rest))
+}
+
+/// View the start of `b` as `count` consecutive `T`
+///
+/// - @param b: at least `count * size_of::<T>()` bytes
+/// - @param count: number of values
+/// - @return: the values and the bytes after them
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::{self, BigEndian, U16};
+/// let b = [0x00, 0x01, 0x00, 0x02, 0xff];
+/// let (values, rest) = binary::slice_from_prefix::<U16<BigEndian>>(&b, 2).unwrap();
+/// assert_eq!(values[1].get(), 2);
+/// assert_eq!(rest, [0xff]);
+/// ```
+pub fn slice_from_prefix<T: FromBytes>(b: &[u8], count: usize) -> Result<(&[T], &[u8]), Error> {
+    let () = AlignOne::<T>::CHECK;
+    let needed = size_of::<T>().saturating_mul(count);
+    super::check_len(needed, b.len())?;
+    let (head, rest) = b.split_at(needed);
+    // SAFETY: `head` holds `count` values of `T`, alignment 1, any bytes
+    Ok((
+        unsafe { core::slice::from_raw_parts(head.as_ptr().cast::<T>(), count) },
+        rest,
+    ))
+}
+
+/// The bytes of a [`FromBytes`] value
+///
+/// - @param value: value
+/// - @return: its `size_of::<T>()` bytes
+pub fn as_bytes<T: FromBytes>(value: &T) -> &[u8] {
+    let () = AlignOne::<T>::CHECK;
+    // SAFETY: `T` has no padding, so all its bytes are initialized
+    unsafe { core::slice::from_raw_parts((value as *const T).cast::<u8>(), size_of::<T>()) }
+}
+
+/// Define the byte order number types
+macro_rules! unaligned_types {
+    ($($name:ident, $ty:ty, $size:literal, $encode:ident, $decode:ident;)*) => {
+        $(
+            #[doc = concat!("`", stringify!($ty), "` stored as bytes in byte order `E`, with alignment 1")]
+            #[repr(transparent)]
+            pub struct $name<E>([u8; $size], PhantomData<E>);
+
+            // SAFETY: transparent over a byte array
+            unsafe impl<E> FromBytes for $name<E> {}
+
+            impl<E: ByteOrder> $name<E> {
+                /// Store a value
+                pub fn new(n: $ty) -> Self {
+                    let mut b = [0; $size];
+                    E::$encode(n, &mut b);
+                    Self(b, PhantomData)
+                }
+
+                /// The stored value
+                pub fn get(self) -> $ty {
+                    E::$decode(&self.0)
+                }
+
+                /// Replace the stored value
+                pub fn set(&mut self, n: $ty) {
+                    E::$encode(n, &mut self.0);
+                }
+            }
+
+            impl<E> $name<E> {
+                /// Wrap bytes already in byte order `E`
+                pub const fn from_bytes(b: [u8; $size]) -> Self {
+                    Self(b, PhantomData)
+                }
+
+                /// The raw bytes
+                pub const fn to_bytes(self) -> [u8; $size] {
+                    self.0
+                }
+            }
+
+            impl<E> Clone for $name<E> {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl<E> Copy for $name<E> {}
+
+            impl<E> Default for $name<E> {
+                fn default() -> Self {
+                    Self([0; $size], PhantomData)
+                }
+            }
+
+            /// Compares the bytes
+            impl<E> PartialEq for $name<E> {
+                fn eq(&self, other: &Self) -> bool {
+                    self.0 == other.0
+                }
+            }
+
+            impl<E> Eq for $name<E> {}
+
+            impl<E> Hash for $name<E> {
+                fn hash<H: Hasher>(&self, state: &mut H) {
+                    self.0.hash(state);
+                }
+            }
+
+            impl<E: ByteOrder> fmt::Debug for $name<E> {
+                fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    fmt::Debug::fmt(&self.get(), f)
+                }
+            }
+
+            impl<E: ByteOrder> From<$ty> for $name<E> {
+                fn from(n: $ty) -> Self {
+                    Self::new(n)
+                }
+            }
+
+            impl<E: ByteOrder> From<$name<E>> for $ty {
+                fn from(n: $name<E>) -> Self {
+                    n.get()
+                }
+            }
+        )*
+    };
+}
+
+unaligned_types! {
+    U16, u16, 2, encode_u16, decode_u16;
+    U32, u32, 4, encode_u32, decode_u32;
+    U64, u64, 8, encode_u64, decode_u64;
+    U128, u128, 16, encode_u128, decode_u128;
+    I16, i16, 2, encode_i16, decode_i16;
+    I32, i32, 4, encode_i32, decode_i32;
+    I64, i64, 8, encode_i64, decode_i64;
+    I128, i128, 16, encode_i128, decode_i128;
+    F32, f32, 4, encode_f32, decode_f32;
+    F64, f64, 8, encode_f64, decode_f64;
+}
+
+/// Declare a `#[repr(C)]` struct of [`FromBytes`] fields and implement
+/// [`FromBytes`] for it
+///
+/// Every field must be [`FromBytes`], checked at compile time, so the struct
+/// has alignment 1 and no padding.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::binary_struct;
+/// use connx::encoding::binary::{self, LittleEndian, I64, U32};
+///
+/// binary_struct! {
+///     /// Capture file record header
+///     pub struct Record {
+///         pub timestamp: I64<LittleEndian>,
+///         pub caplen: U32<LittleEndian>,
+///         pub tag: [u8; 4],
+///     }
+/// }
+///
+/// assert_eq!(core::mem::size_of::<Record>(), 16);
+/// let mut b = [0u8; 16];
+/// let (r, _) = binary::mut_from_prefix::<Record>(&mut b).unwrap();
+/// r.timestamp.set(-1);
+/// r.tag = *b"data";
+/// let (r, _) = binary::ref_from_prefix::<Record>(&b).unwrap();
+/// assert_eq!(r.timestamp.get(), -1);
+/// assert_eq!(&binary::as_bytes(r)[12..], b"data");
+/// ```
+///
+/// Fields that are not [`FromBytes`] do not compile:
+///
+/// ```compile_fail
+/// connx::binary_struct! {
+///     pub struct Bad {
+///         pub n: u32,
+///     }
+/// }
+/// ```
+///
+/// Neither do attributes adding alignment, and so padding:
+///
+/// ```compile_fail
+/// connx::binary_struct! {
+///     #[repr(align(4))]
+///     pub struct Padded {
+///         pub n: u8,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! binary_struct {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident: $ty:ty),* $(,)?
+        }
+    ) => {
+        $(#[$attr])*
+        #[repr(C)]
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $ty,)*
+        }
+
+        const _: () = {
+            fn assert_from_bytes<T: $crate::encoding::binary::FromBytes>() {}
+            $(let _ = assert_from_bytes::<$ty>;)*
+            // Attributes such as `repr(align(N))` may still add padding
+            assert!(
+                ::core::mem::align_of::<$name>() == 1,
+                "encoding/binary: binary_struct alignment not 1"
+            );
+            assert!(
+                ::core::mem::size_of::<$name>() == 0 $(+ ::core::mem::size_of::<$ty>())*,
+                "encoding/binary: binary_struct has padding"
+            );
+        };
+
+        // SAFETY: every field accepts any bytes, and the checks above
+        // guarantee alignment 1 and no padding
+        unsafe impl $crate::encoding::binary::FromBytes for $name {}
+    };
+}
+
+#[cfg(test)]
+mod tests_unaligned {
+    use super::*;
+    use crate::encoding::binary::{BigEndian, LittleEndian, Reader};
+    #[cfg(feature = "alloc")]
+    use alloc::format;
+
+    crate::binary_struct! {
+        #[derive(Debug, Clone, Copy, PartialEq, Default)]
+        struct Header {
+            magic: [u8; 2],
+            version: u8,
+            len: U16<BigEndian>,
+            offset: I32<LittleEndian>,
+            scale: F32<BigEndian>,
+            id: U64<LittleEndian>,
+        }
+    }
+
+    #[test]
+    fn test_types() {
+        assert_eq!(size_of::<U16<BigEndian>>(), 2);
+        assert_eq!(align_of::<U64<BigEndian>>(), 1);
+        assert_eq!(size_of::<I128<LittleEndian>>(), 16);
+
+        let n = U32::<BigEndian>::new(0x01020304);
+        assert_eq!(n.to_bytes(), [1, 2, 3, 4]);
+        assert_eq!(
+            U32::<LittleEndian>::from_bytes([1, 2, 3, 4]).get(),
+            0x04030201
+        );
+        let mut x: I16<LittleEndian> = (-2).into();
+        assert_eq!(x.to_bytes(), [0xfe, 0xff]);
+        x.set(3);
+        assert_eq!(i16::from(x), 3);
+        #[cfg(feature = "alloc")]
+        assert_eq!(format!("{:?}", F64::<BigEndian>::new(1.5)), "1.5");
+        assert_eq!(U128::<BigEndian>::default().get(), 0);
+    }
+
+    #[test]
+    fn test_views() {
+        assert_eq!(size_of::<Header>(), 2 + 1 + 2 + 4 + 4 + 8);
+        assert_eq!(align_of::<Header>(), 1);
+
+        let mut b = [0u8; 24];
+        // Unaligned on purpose
+        let (h, rest) = mut_from_prefix::<Header>(&mut b[1..]).unwrap();
+        assert_eq!(rest.len(), 2);
+        h.magic = *b"CX";
+        h.version = 1;
+        h.len.set(0x0102);
+        h.offset.set(-2);
+        h.scale.set(0.5);
+        h.id.set(7);
+        assert_eq!(
+            b[1..22],
+            [
+                b'C', b'X', 1, 0x01, 0x02, 0xfe, 0xff, 0xff, 0xff, 0x3f, 0x00, 0x00, 0x00, 7, 0, 0,
+                0, 0, 0, 0, 0
+            ]
+        );
+
+        let (h, _) = ref_from_prefix::<Header>(&b[1..]).unwrap();
+        assert_eq!(h.len.get(), 0x0102);
+        assert_eq!(h.offset.get(), -2);
+        assert_eq!(h.scale.get(), 0.5);
+        assert_eq!(as_bytes(h), &b[1..22]);
+        let copy = *h;
+        assert_eq!(copy, *h);
+
+        assert_eq!(
+            ref_from_prefix::<Header>(&b[..20]).map(|(h, _)| *h),
+            Err(Error::UnexpectedEof {
+                needed: 21,
+                available: 20
+            })
+        );
+        assert_eq!(
+            slice_from_prefix::<Header>(&b, 2).map(|(h, _)| h.len()),
+            Err(Error::UnexpectedEof {
+                needed: 42,
+                available: 24
+            })
+        );
+        assert_eq!(
+            slice_from_prefix::<U16<BigEndian>>(&b, usize::MAX).map(|(v, _)| v.len()),
+            Err(Error::UnexpectedEof {
+                needed: usize::MAX,
+                available: 24
+            })
+        );
+    }
+
+    #[test]
+    fn test_reader() {
+        let b = [0xff, 0x00, 0x05, 0x00, 0x06, 0x01];
+        let mut r = Reader::new(&b);
+        assert_eq!(r.get_u8(), Ok(0xff));
+        let values = r.get_slice::<U16<BigEndian>>(2).unwrap();
+        assert_eq!(values[0].get(), 5);
+        assert_eq!(values[1].get(), 6);
+        assert_eq!(r.get_ref::<u8>(), Ok(&1));
+        assert!(r.get_ref::<U16<BigEndian>>().is_err());
+    }
+}
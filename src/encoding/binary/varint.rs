@@ -0,0 +1,377 @@
+//! Variable-length integers: LEB128, zigzag and signed LEB128.
+//!
+//! Unsigned values use LEB128, 7 bits per byte, least significant group
+//! first, the high bit set on every byte but the last. This is the protobuf
+//! varint, the WebAssembly `u32`/`u64` and Go's `binary.Uvarint`.
+//!
+//! Signed values come in two flavors:
+//!
+//! - [`encode_varint`]: zigzag mapped then LEB128, protobuf `sint64` and Go's
+//!   `binary.Varint`. Small negative values stay short.
+//! - [`encode_sleb128`]: two's complement signed LEB128, WebAssembly `s64`
+//!   and DWARF.
+//!
+//! Decoding returns the value and the bytes consumed. Encodings past 64 bits
+//! are an [`Error::VarintOverflow`], a truncated one an
+//! [`Error::UnexpectedEof`].
+//!
+//! # Examples
+//!
+//! Delta encoded timestamps:
+//!
+//! ```
+//! use connx::encoding::binary::varint;
+//! let timestamps: [i64; 4] = [1_700_000_000, 1_700_000_003, 1_700_000_001, 1_700_000_061];
+//! let mut b = [0; 4 * varint::MAX_VARINT_LEN64];
+//! let mut len = 0;
+//! let mut prev = 0;
+//! for t in timestamps {
+//!     len += varint::encode_varint(t - prev, &mut b[len..]);
+//!     prev = t;
+//! }
+//! assert_eq!(len, 5 + 1 + 1 + 1);
+//!
+//! let mut pos = 0;
+//! let mut prev = 0;
+//! for t in timestamps {
+//!     let (delta, n) = varint::decode_varint(&b[pos..len]).unwrap();
+//!     prev += delta;
+//!     pos += n;
+//!     assert_eq!(prev, t);
+//! }
+//! ```
+
+pub use super::Error;
+
+/// Maximum encoded length of a 16 bits value
+pub const MAX_VARINT_LEN16: usize = 3;
+
+/// Maximum encoded length of a 32 bits value
+pub const MAX_VARINT_LEN32: usize = 5;
+
+/// Maximum encoded length of a 64 bits value
+pub const MAX_VARINT_LEN64: usize = 10;
+
+/// Map a signed value to an unsigned one, small magnitudes to small values
+///
+/// 0, -1, 1, -2, 2... become 0, 1, 2, 3, 4...
+///
+/// - @param n: signed value
+/// - @return: zigzag value
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint;
+/// assert_eq!(varint::zigzag_encode(-1), 1);
+/// assert_eq!(varint::zigzag_encode(1), 2);
+/// assert_eq!(varint::zigzag_encode(i64::MIN), u64::MAX);
+/// ```
+pub const fn zigzag_encode(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+/// Reverse of [`zigzag_encode`]
+///
+/// - @param n: zigzag value
+/// - @return: signed value
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint;
+/// assert_eq!(varint::zigzag_decode(3), -2);
+/// assert_eq!(varint::zigzag_decode(u64::MAX), i64::MIN);
+/// ```
+pub const fn zigzag_decode(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+/// Encoded length of an unsigned value
+///
+/// - @param n: value
+/// - @return: 1 to [`MAX_VARINT_LEN64`]
+pub const fn uvarint_len(n: u64) -> usize {
+    let bits = 64 - (n | 1).leading_zeros() as usize;
+    bits.div_ceil(7)
+}
+
+/// Encode an unsigned value as LEB128
+///
+/// - @param n: value
+/// - @param b: output, [`MAX_VARINT_LEN64`] bytes always suffice
+/// - @return: bytes written
+///
+/// # Panics
+///
+/// Panics if `b` is shorter than [`uvarint_len`] of `n`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint;
+/// let mut b = [0; varint::MAX_VARINT_LEN64];
+/// let n = varint::encode_uvarint(300, &mut b);
+/// assert_eq!(b[..n], [0xac, 0x02]);
+/// ```
+pub fn encode_uvarint(mut n: u64, b: &mut [u8]) -> usize {
+    let mut i = 0;
+    while n >= 0x80 {
+        b[i] = n as u8 | 0x80;
+        n >>= 7;
+        i += 1;
+    }
+    b[i] = n as u8;
+    i + 1
+}
+
+/// Decode a LEB128 unsigned value
+///
+/// Bytes after the value are ignored.
+///
+/// - @param b: input
+/// - @return: value and bytes consumed
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint::{self, Error};
+/// assert_eq!(varint::decode_uvarint(&[0xac, 0x02, 0xff]), Ok((300, 2)));
+/// assert_eq!(
+///     varint::decode_uvarint(&[0xac]),
+///     Err(Error::UnexpectedEof { needed: 2, available: 1 })
+/// );
+/// assert_eq!(varint::decode_uvarint(&[0xff; 10]), Err(Error::VarintOverflow));
+/// ```
+pub fn decode_uvarint(b: &[u8]) -> Result<(u64, usize), Error> {
+    let mut n = 0;
+    for (i, &byte) in b.iter().enumerate() {
+        // The tenth byte holds the last bit of a u64
+        if i == MAX_VARINT_LEN64 - 1 && byte > 1 {
+            return Err(Error::VarintOverflow);
+        }
+        n |= u64::from(byte & 0x7f) << (7 * i);
+        if byte < 0x80 {
+            return Ok((n, i + 1));
+        }
+    }
+    Err(Error::UnexpectedEof {
+        needed: b.len() + 1,
+        available: b.len(),
+    })
+}
+
+/// Encode a signed value, zigzag mapped then LEB128
+///
+/// - @param n: value
+/// - @param b: output, [`MAX_VARINT_LEN64`] bytes always suffice
+/// - @return: bytes written
+///
+/// # Panics
+///
+/// Panics if `b` is too short.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint;
+/// let mut b = [0; varint::MAX_VARINT_LEN64];
+/// let n = varint::encode_varint(-65, &mut b);
+/// assert_eq!(b[..n], [0x81, 0x01]);
+/// assert_eq!(varint::decode_varint(&b[..n]), Ok((-65, 2)));
+/// ```
+pub fn encode_varint(n: i64, b: &mut [u8]) -> usize {
+    encode_uvarint(zigzag_encode(n), b)
+}
+
+/// Decode a zigzag LEB128 signed value
+///
+/// - @param b: input
+/// - @return: value and bytes consumed
+pub fn decode_varint(b: &[u8]) -> Result<(i64, usize), Error> {
+    decode_uvarint(b).map(|(n, len)| (zigzag_decode(n), len))
+}
+
+/// Encode a signed value as two's complement signed LEB128
+///
+/// - @param n: value
+/// - @param b: output, [`MAX_VARINT_LEN64`] bytes always suffice
+/// - @return: bytes written
+///
+/// # Panics
+///
+/// Panics if `b` is too short.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::binary::varint;
+/// let mut b = [0; varint::MAX_VARINT_LEN64];
+/// let n = varint::encode_sleb128(-123456, &mut b);
+/// assert_eq!(b[..n], [0xc0, 0xbb, 0x78]);
+/// assert_eq!(varint::decode_sleb128(&b[..n]), Ok((-123456, 3)));
+/// ```
+pub fn encode_sleb128(mut n: i64, b: &mut [u8]) -> usize {
+    let mut i = 0;
+    loop {
+        let byte = (n & 0x7f) as u8;
+        n >>= 7;
+        // Done once the rest is the sign extension of bit 6
+        if (n == 0 && byte & 0x40 == 0) || (n == -1 && byte & 0x40 != 0) {
+            b[i] = byte;
+            return i + 1;
+        }
+        b[i] = byte | 0x80;
+        i += 1;
+    }
+}
+
+/// Decode a two's complement signed LEB128 value
+///
+/// - @param b: input
+/// - @return: value and bytes consumed
+pub fn decode_sleb128(b: &[u8]) -> Result<(i64, usize), Error> {
+    let mut n = 0;
+    for (i, &byte) in b.iter().enumerate() {
+        if i == MAX_VARINT_LEN64 - 1 {
+            // Bit 63, then the sign extension, no continuation
+            if byte != 0 && byte != 0x7f {
+                return Err(Error::VarintOverflow);
+            }
+            return Ok((n | i64::from(byte) << 63, i + 1));
+        }
+        n |= i64::from(byte & 0x7f) << (7 * i);
+        if byte < 0x80 {
+            let shift = 7 * (i + 1);
+            if byte & 0x40 != 0 {
+                n |= -1 << shift;
+            }
+            return Ok((n, i + 1));
+        }
+    }
+    Err(Error::UnexpectedEof {
+        needed: b.len() + 1,
+        available: b.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests_varint {
+    use super::*;
+
+    #[test]
+    fn test_uvarint() {
+        let cases: [(u64, &[u8]); 7] = [
+            (0, &[0x00]),
+            (1, &[0x01]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x01]),
+            (16384, &[0x80, 0x80, 0x01]),
+            (u32::MAX as u64, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+            (
+                u64::MAX,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ];
+        for (n, expected) in cases {
+            let mut b = [0; MAX_VARINT_LEN64];
+            let len = encode_uvarint(n, &mut b);
+            assert_eq!(&b[..len], expected);
+            assert_eq!(uvarint_len(n), len);
+            assert_eq!(decode_uvarint(expected), Ok((n, len)));
+        }
+        assert_eq!(uvarint_len(u16::MAX as u64), MAX_VARINT_LEN16);
+        assert_eq!(uvarint_len(u32::MAX as u64), MAX_VARINT_LEN32);
+    }
+
+    #[test]
+    fn test_uvarint_errors() {
+        assert_eq!(
+            decode_uvarint(&[]),
+            Err(Error::UnexpectedEof {
+                needed: 1,
+                available: 0
+            })
+        );
+        assert_eq!(
+            decode_uvarint(&[0x80, 0x80]),
+            Err(Error::UnexpectedEof {
+                needed: 3,
+                available: 2
+            })
+        );
+        let mut b = [0xff; 11];
+        b[9] = 0x02;
+        assert_eq!(decode_uvarint(&b), Err(Error::VarintOverflow));
+        b[9] = 0x81;
+        assert_eq!(decode_uvarint(&b), Err(Error::VarintOverflow));
+        // Non canonical, but within 64 bits
+        assert_eq!(decode_uvarint(&[0x80, 0x00]), Ok((0, 2)));
+    }
+
+    #[test]
+    fn test_varint() {
+        for (n, z) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (i64::MAX, u64::MAX - 1)] {
+            assert_eq!(zigzag_encode(n), z);
+            assert_eq!(zigzag_decode(z), n);
+        }
+        let mut b = [0; MAX_VARINT_LEN64];
+        for n in [0, -1, 63, -64, 64, i64::MIN, i64::MAX] {
+            let len = encode_varint(n, &mut b);
+            assert_eq!(decode_varint(&b[..len]), Ok((n, len)));
+        }
+        assert_eq!(encode_varint(-64, &mut b), 1);
+        assert_eq!(encode_varint(64, &mut b), 2);
+    }
+
+    #[test]
+    fn test_sleb128() {
+        let cases: [(i64, &[u8]); 7] = [
+            (0, &[0x00]),
+            (-1, &[0x7f]),
+            (63, &[0x3f]),
+            (64, &[0xc0, 0x00]),
+            (-64, &[0x40]),
+            (-65, &[0xbf, 0x7f]),
+            (
+                i64::MIN,
+                &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f],
+            ),
+        ];
+        for (n, expected) in cases {
+            let mut b = [0; MAX_VARINT_LEN64];
+            let len = encode_sleb128(n, &mut b);
+            assert_eq!(&b[..len], expected);
+            assert_eq!(decode_sleb128(expected), Ok((n, len)));
+        }
+        let mut b = [0; MAX_VARINT_LEN64];
+        let len = encode_sleb128(i64::MAX, &mut b);
+        assert_eq!(len, 10);
+        assert_eq!(decode_sleb128(&b[..len]), Ok((i64::MAX, 10)));
+
+        let mut b = [0x80; 10];
+        b[9] = 0x01;
+        assert_eq!(decode_sleb128(&b), Err(Error::VarintOverflow));
+        b[9] = 0xff;
+        assert_eq!(decode_sleb128(&b), Err(Error::VarintOverflow));
+        assert_eq!(
+            decode_sleb128(&[0xc0]),
+            Err(Error::UnexpectedEof {
+                needed: 2,
+                available: 1
+            })
+        );
+    }
+}
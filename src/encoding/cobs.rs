@@ -0,0 +1,568 @@
+//! Consistent Overhead Byte Stuffing (COBS) and its reduced variant COBS/R.
+//!
+//! COBS removes every zero byte from the data, so that a zero can delimit
+//! frames on a serial link, at the cost of at most one byte per 254 bytes.
+//! COBS/R often saves that byte again by storing the last data byte in the
+//! final length code.
+//!
+//! # Examples
+//!
+//! COBS encode basic usage:
+//!
+//! ```
+//! use connx::encoding::cobs;
+//! assert_eq!(cobs::encode_to_vec(&[0x11, 0x22, 0x00, 0x33]), [0x03, 0x11, 0x22, 0x02, 0x33]);
+//! ```
+//!
+//! COBS decode basic usage:
+//!
+//! ```
+//! use connx::encoding::cobs;
+//! assert_eq!(cobs::decode_to_vec(&[0x03, 0x11, 0x22, 0x02, 0x33]), Ok(vec![0x11, 0x22, 0x00, 0x33]));
+//! ```
+//!
+//! Decoding frames from a byte stream:
+//!
+//! ```
+//! use connx::encoding::cobs;
+//! let mut decoder = cobs::FrameDecoder::new();
+//! assert!(decoder.push(&[0x03, 0x11]).is_empty());
+//! let frames = decoder.push(&[0x22, 0x02, 0x33, 0x00, 0x01, 0x00]);
+//! assert_eq!(frames, [Ok(vec![0x11, 0x22, 0x00, 0x33]), Ok(vec![])]);
+//! ```
+
+use crate::encoding::framing::FrameBuffer;
+
+/// Frame delimiter, never present in encoded data
+pub const DELIMITER: u8 = 0x00;
+
+/// Errors when COBS decode
+#[derive(Debug, PartialEq)]
+pub enum CobsError {
+    /// Zero byte at this offset inside encoded data
+    UnexpectedDelimiter(usize),
+    /// A length code points past the end of the data
+    Truncated,
+    /// Frame longer than the limit of the frame decoder
+    FrameTooLong,
+}
+
+impl std::fmt::Display for CobsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedDelimiter(offset) => {
+                write!(
+                    f,
+                    "encoding/cobs: unexpected zero byte at offset {}",
+                    offset
+                )
+            }
+            Self::Truncated => write!(f, "encoding/cobs: truncated data"),
+            Self::FrameTooLong => write!(f, "encoding/cobs: frame too long"),
+        }
+    }
+}
+
+impl std::error::Error for CobsError {}
+
+/// Calculate the maximum COBS encoded length, without the delimiter
+///
+/// - @param n: raw bytes length
+/// - @return: upper bound of the encoded length, (n + n/254 + 1)
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// assert_eq!(cobs::encode_len(0), 1);
+/// assert_eq!(cobs::encode_len(253), 254);
+/// assert_eq!(cobs::encode_len(254), 256);
+/// ```
+#[inline]
+pub fn encode_len(n: usize) -> usize {
+    n + n / 254 + 1
+}
+
+/// Calculate the maximum COBS decoded length
+///
+/// - @param n: encoded length, without the delimiter
+/// - @return: upper bound of the decoded length, (n)
+#[inline]
+pub fn decode_len(n: usize) -> usize {
+    n
+}
+
+/// COBS encode, returns (encoded size, index of the final code, final code)
+fn encode_blocks(dst: &mut [u8], src: &[u8]) -> (usize, usize, u8) {
+    let mut code_idx = 0;
+    let mut code: u8 = 1;
+    let mut dst_idx = 1;
+    for (i, &b) in src.iter().enumerate() {
+        if b != 0 {
+            dst[dst_idx] = b;
+            dst_idx += 1;
+            code += 1;
+        }
+        if b == 0 || code == 0xFF {
+            dst[code_idx] = code;
+            code = 1;
+            code_idx = dst_idx;
+            // a full block at the very end is not followed by an empty one
+            if b == 0 || i + 1 < src.len() {
+                dst_idx += 1;
+            }
+        }
+    }
+    if code_idx < dst_idx {
+        dst[code_idx] = code;
+    }
+    (dst_idx, code_idx, code)
+}
+
+/// Encode bytes to COBS bytes, without the trailing delimiter
+///
+/// - @param dst: encoded bytes, at least `encode_len(src.len())`
+/// - @param src: raw bytes
+/// - @return: encoded byte size
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// let src = [0x00, 0x00];
+/// let mut dst = vec![0; cobs::encode_len(src.len())];
+/// let dst_size = cobs::encode(&mut dst, &src);
+/// assert_eq!(&dst[..dst_size], [0x01, 0x01, 0x01]);
+/// ```
+pub fn encode(dst: &mut [u8], src: &[u8]) -> usize {
+    encode_blocks(dst, src).0
+}
+
+/// Encode bytes to COBS/R bytes, without the trailing delimiter
+///
+/// - @param dst: encoded bytes, at least `encode_len(src.len())`
+/// - @param src: raw bytes
+/// - @return: encoded byte size
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// let src = [0x11, 0x22, 0x00, 0x33];
+/// let mut dst = vec![0; cobs::encode_len(src.len())];
+/// let dst_size = cobs::encode_r(&mut dst, &src);
+/// assert_eq!(&dst[..dst_size], [0x03, 0x11, 0x22, 0x33]);
+/// ```
+pub fn encode_r(dst: &mut [u8], src: &[u8]) -> usize {
+    let (dst_size, code_idx, code) = encode_blocks(dst, src);
+    // move the last byte into the final code if the decoder can tell it
+    // apart from a length, i.e. when it points past the end
+    if code_idx < dst_size && code > 1 && dst[dst_size - 1] >= code {
+        dst[code_idx] = dst[dst_size - 1];
+        return dst_size - 1;
+    }
+    dst_size
+}
+
+fn decode_blocks(dst: &mut [u8], src: &[u8], reduced: bool) -> Result<usize, CobsError> {
+    let mut src_idx = 0;
+    let mut dst_idx = 0;
+    while src_idx < src.len() {
+        let code = src[src_idx];
+        if code == 0 {
+            return Err(CobsError::UnexpectedDelimiter(src_idx));
+        }
+        src_idx += 1;
+
+        let n = code as usize - 1;
+        let block_end = src_idx + n;
+        let copy_end = if block_end <= src.len() {
+            block_end
+        } else if reduced {
+            src.len()
+        } else {
+            return Err(CobsError::Truncated);
+        };
+        if let Some(offset) = src[src_idx..copy_end].iter().position(|&b| b == 0) {
+            return Err(CobsError::UnexpectedDelimiter(src_idx + offset));
+        }
+        dst[dst_idx..dst_idx + copy_end - src_idx].copy_from_slice(&src[src_idx..copy_end]);
+        dst_idx += copy_end - src_idx;
+        src_idx = copy_end;
+
+        if block_end > src.len() {
+            // COBS/R: the code was the last data byte
+            dst[dst_idx] = code;
+            dst_idx += 1;
+        } else if code != 0xFF && src_idx < src.len() {
+            dst[dst_idx] = 0;
+            dst_idx += 1;
+        }
+    }
+    Ok(dst_idx)
+}
+
+/// Decode COBS bytes to raw bytes, without the trailing delimiter
+///
+/// - @param dst: decoded raw bytes, at least `decode_len(src.len())`
+/// - @param src: COBS bytes
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// let src = [0x01, 0x01, 0x01];
+/// let mut dst = vec![0; cobs::decode_len(src.len())];
+/// let dst_size = cobs::decode(&mut dst, &src).unwrap();
+/// assert_eq!(&dst[..dst_size], [0x00, 0x00]);
+/// ```
+pub fn decode(dst: &mut [u8], src: &[u8]) -> Result<usize, CobsError> {
+    decode_blocks(dst, src, false)
+}
+
+/// Decode COBS/R bytes to raw bytes, without the trailing delimiter
+///
+/// - @param dst: decoded raw bytes, at least `decode_len(src.len())`
+/// - @param src: COBS/R bytes
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// let src = [0x03, 0x11, 0x22, 0x33];
+/// let mut dst = vec![0; cobs::decode_len(src.len())];
+/// let dst_size = cobs::decode_r(&mut dst, &src).unwrap();
+/// assert_eq!(&dst[..dst_size], [0x11, 0x22, 0x00, 0x33]);
+/// ```
+pub fn decode_r(dst: &mut [u8], src: &[u8]) -> Result<usize, CobsError> {
+    decode_blocks(dst, src, true)
+}
+
+/// Decode COBS bytes in place, the decoded data is never longer than the
+/// encoded data
+///
+/// - @param buf: COBS bytes, overwritten with the raw bytes
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// let mut buf = [0x03, 0x11, 0x22, 0x02, 0x33];
+/// let n = cobs::decode_in_place(&mut buf).unwrap();
+/// assert_eq!(&buf[..n], [0x11, 0x22, 0x00, 0x33]);
+/// ```
+pub fn decode_in_place(buf: &mut [u8]) -> Result<usize, CobsError> {
+    let mut src_idx = 0;
+    let mut dst_idx = 0;
+    while src_idx < buf.len() {
+        let code = buf[src_idx];
+        if code == 0 {
+            return Err(CobsError::UnexpectedDelimiter(src_idx));
+        }
+        src_idx += 1;
+
+        let n = code as usize - 1;
+        if src_idx + n > buf.len() {
+            return Err(CobsError::Truncated);
+        }
+        if let Some(offset) = buf[src_idx..src_idx + n].iter().position(|&b| b == 0) {
+            return Err(CobsError::UnexpectedDelimiter(src_idx + offset));
+        }
+        // each code byte turns into at most one zero, so dst_idx <= src_idx
+        buf.copy_within(src_idx..src_idx + n, dst_idx);
+        dst_idx += n;
+        src_idx += n;
+
+        if code != 0xFF && src_idx < buf.len() {
+            buf[dst_idx] = 0;
+            dst_idx += 1;
+        }
+    }
+    Ok(dst_idx)
+}
+
+/// Encode bytes to COBS bytes, without the trailing delimiter
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// assert_eq!(cobs::encode_to_vec(&[]), [0x01]);
+/// ```
+pub fn encode_to_vec(src: &[u8]) -> Vec<u8> {
+    let mut dst = vec![0; encode_len(src.len())];
+    let dst_size = encode(&mut dst, src);
+    dst.truncate(dst_size);
+    dst
+}
+
+/// Encode bytes to a COBS frame, terminated by [`DELIMITER`]
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs;
+/// assert_eq!(cobs::encode_frame(&[0x00]), [0x01, 0x01, 0x00]);
+/// ```
+pub fn encode_frame(src: &[u8]) -> Vec<u8> {
+    let mut dst = vec![0; encode_len(src.len()) + 1];
+    let dst_size = encode(&mut dst, src);
+    dst.truncate(dst_size + 1);
+    dst[dst_size] = DELIMITER;
+    dst
+}
+
+/// Decode COBS bytes to raw bytes, without the trailing delimiter
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::cobs::{self, CobsError};
+/// assert_eq!(cobs::decode_to_vec(&[0x01]), Ok(vec![]));
+/// assert_eq!(cobs::decode_to_vec(&[0x05, 0x11]), Err(CobsError::Truncated));
+/// ```
+pub fn decode_to_vec(src: &[u8]) -> Result<Vec<u8>, CobsError> {
+    let mut dst = vec![0; decode_len(src.len())];
+    let dst_size = decode(&mut dst, src)?;
+    dst.truncate(dst_size);
+    Ok(dst)
+}
+
+/// Splits a byte stream into zero-delimited frames and decodes them.
+///
+/// Bytes are buffered until a delimiter arrives, empty frames between two
+/// delimiters are skipped. A frame exceeding the length limit is reported
+/// once and dropped up to the next delimiter.
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    frames: FrameBuffer,
+    reduced: bool,
+}
+
+impl FrameDecoder {
+    /// Frame decoder for COBS frames
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frame decoder for COBS/R frames
+    pub fn new_reduced() -> Self {
+        Self {
+            reduced: true,
+            ..Self::default()
+        }
+    }
+
+    /// Limit the encoded length of a frame
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.frames.set_max_len(max_len);
+        self
+    }
+
+    /// Bytes of the incomplete frame buffered so far
+    pub fn pending(&self) -> usize {
+        self.frames.pending()
+    }
+
+    /// Feed received bytes, returns every frame completed by them
+    pub fn push(&mut self, data: &[u8]) -> Vec<Result<Vec<u8>, CobsError>> {
+        let reduced = self.reduced;
+        self.frames.push(
+            data,
+            DELIMITER,
+            || CobsError::FrameTooLong,
+            |frame| {
+                let mut dst = vec![0; decode_len(frame.len())];
+                let dst_size = decode_blocks(&mut dst, frame, reduced)?;
+                dst.truncate(dst_size);
+                Ok(dst)
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests_cobs {
+    use super::*;
+
+    fn test_base(data: &[u8], encoded_expect: &[u8]) {
+        let encoded = encode_to_vec(data);
+        assert_eq!(encoded, encoded_expect);
+        assert!(encoded.len() <= encode_len(data.len()));
+        assert_eq!(decode_to_vec(&encoded).unwrap(), data);
+
+        let mut buf = encoded.clone();
+        let n = decode_in_place(&mut buf).unwrap();
+        assert_eq!(&buf[..n], data);
+    }
+
+    fn test_reduced(data: &[u8], encoded_expect: &[u8]) {
+        let mut encoded = vec![0; encode_len(data.len())];
+        let n = encode_r(&mut encoded, data);
+        assert_eq!(&encoded[..n], encoded_expect);
+        let mut decoded = vec![0; decode_len(n)];
+        let m = decode_r(&mut decoded, &encoded[..n]).unwrap();
+        assert_eq!(&decoded[..m], data);
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(&[], &[0x01]);
+        test_base(&[0x00], &[0x01, 0x01]);
+        test_base(&[0x00, 0x00], &[0x01, 0x01, 0x01]);
+        test_base(&[0x00, 0x11, 0x00], &[0x01, 0x02, 0x11, 0x01]);
+        test_base(&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x02, 0x33]);
+        test_base(&[0x11, 0x22, 0x33, 0x44], &[0x05, 0x11, 0x22, 0x33, 0x44]);
+        test_base(&[0x11, 0x00, 0x00, 0x00], &[0x02, 0x11, 0x01, 0x01, 0x01]);
+    }
+
+    #[test]
+    fn test_encode_long_blocks() {
+        // 01..FE
+        let data: Vec<u8> = (1..=254).collect();
+        let mut expect = vec![0xFF];
+        expect.extend_from_slice(&data);
+        test_base(&data, &expect);
+
+        // 00 01..FE
+        let data: Vec<u8> = (0..=254).collect();
+        let mut expect = vec![0x01, 0xFF];
+        expect.extend(1..=254);
+        test_base(&data, &expect);
+
+        // 01..FF
+        let data: Vec<u8> = (1..=255).collect();
+        let mut expect = vec![0xFF];
+        expect.extend(1..=254);
+        expect.extend_from_slice(&[0x02, 0xFF]);
+        test_base(&data, &expect);
+
+        // 02..FF 00
+        let mut data: Vec<u8> = (2..=255).collect();
+        data.push(0);
+        let mut expect = vec![0xFF];
+        expect.extend(2..=255);
+        expect.extend_from_slice(&[0x01, 0x01]);
+        test_base(&data, &expect);
+    }
+
+    #[test]
+    fn test_reduced_01() {
+        test_reduced(&[], &[0x01]);
+        test_reduced(&[0x00], &[0x01, 0x01]);
+        test_reduced(&[0x01], &[0x02, 0x01]);
+        test_reduced(&[0x02], &[0x02]);
+        test_reduced(&[0x03], &[0x03]);
+        test_reduced(&[0x7E], &[0x7E]);
+        test_reduced(&[0x11, 0x22, 0x00, 0x33], &[0x03, 0x11, 0x22, 0x33]);
+        test_reduced(&[0x11, 0x22, 0x00, 0x02], &[0x03, 0x11, 0x22, 0x02]);
+        test_reduced(&[0x11, 0x22, 0x00, 0x01], &[0x03, 0x11, 0x22, 0x02, 0x01]);
+        test_reduced(&[0x11, 0x00, 0x00, 0x00], &[0x02, 0x11, 0x01, 0x01, 0x01]);
+        let data: Vec<u8> = (1..=254).collect();
+        let mut expect = vec![0xFF];
+        expect.extend_from_slice(&data);
+        test_reduced(&data, &expect);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode_to_vec(&[0x03, 0x11]), Err(CobsError::Truncated));
+        assert_eq!(
+            decode_to_vec(&[0x00]),
+            Err(CobsError::UnexpectedDelimiter(0))
+        );
+        assert_eq!(
+            decode_to_vec(&[0x03, 0x11, 0x00]),
+            Err(CobsError::UnexpectedDelimiter(2))
+        );
+        assert_eq!(
+            decode_in_place(&mut [0x03, 0x11]),
+            Err(CobsError::Truncated)
+        );
+        assert_eq!(
+            decode_in_place(&mut [0x02, 0x00]),
+            Err(CobsError::UnexpectedDelimiter(1))
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_lengths() {
+        for len in [0, 1, 253, 254, 255, 507, 508, 509, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i % 7) as u8 * 37).collect();
+            let encoded = encode_to_vec(&data);
+            assert!(!encoded.contains(&0));
+            assert!(encoded.len() <= encode_len(len));
+            assert_eq!(decode_to_vec(&encoded).unwrap(), data);
+
+            let mut reduced = vec![0; encode_len(len)];
+            let n = encode_r(&mut reduced, &data);
+            assert!(n <= encoded.len());
+            let mut decoded = vec![0; decode_len(n)];
+            let m = decode_r(&mut decoded, &reduced[..n]).unwrap();
+            assert_eq!(&decoded[..m], &data[..]);
+        }
+    }
+
+    #[test]
+    fn test_frame_decoder() {
+        let mut stream = Vec::new();
+        stream.push(0x00);
+        stream.extend(encode_frame(&[0x11, 0x00, 0x22]));
+        stream.extend(encode_frame(&[0x00; 3]));
+        stream.extend_from_slice(&[0x00, 0x00]);
+        stream.extend(encode_frame(&[0x33]));
+
+        // feed one byte at a time
+        let mut decoder = FrameDecoder::new();
+        let frames: Vec<_> = stream.iter().flat_map(|b| decoder.push(&[*b])).collect();
+        assert_eq!(
+            frames,
+            [
+                Ok(vec![0x11, 0x00, 0x22]),
+                Ok(vec![0x00; 3]),
+                Ok(vec![0x33])
+            ]
+        );
+        assert_eq!(decoder.pending(), 0);
+
+        // a corrupt frame does not affect the next one
+        let mut decoder = FrameDecoder::new();
+        let frames = decoder.push(&[0x05, 0x11, 0x00, 0x02, 0x22, 0x00, 0x03]);
+        assert_eq!(frames, [Err(CobsError::Truncated), Ok(vec![0x22])]);
+        assert_eq!(decoder.pending(), 1);
+    }
+
+    #[test]
+    fn test_frame_decoder_limits() {
+        let mut decoder = FrameDecoder::new().with_max_len(4);
+        let mut stream = encode_frame(&[0x11; 10]);
+        stream.extend(encode_frame(&[0x22; 2]));
+        let frames = decoder.push(&stream);
+        assert_eq!(frames, [Err(CobsError::FrameTooLong), Ok(vec![0x22; 2])]);
+
+        let mut decoder = FrameDecoder::new_reduced();
+        let frames = decoder.push(&[0x03, 0x11, 0x22, 0x33, 0x00]);
+        assert_eq!(frames, [Ok(vec![0x11, 0x22, 0x00, 0x33])]);
+    }
+}
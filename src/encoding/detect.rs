@@ -0,0 +1,311 @@
+//! Guess the encoding of an unknown string.
+//!
+//! [`detect`] runs the real decoders of hex, base32, base64, base58 and
+//! base85 over the input and ranks the formats which decode successfully.
+//! The score is a plausibility heuristic from 0 to 100: formats with a
+//! smaller alphabet are preferred, characters only one format uses count
+//! for it, non-canonical input counts against it, and decoding to readable
+//! text counts for it.
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::{self, detect::Format};
+//! let candidates = encoding::detect("aGVsbG8gd29ybGQ=");
+//! assert_eq!(candidates[0].format, Format::Base64);
+//! assert_eq!(candidates[0].decoded, b"hello world");
+//! ```
+
+mod base85;
+
+use crate::encoding::{base16, base32, base64, radix};
+
+/// Formats recognized by [`detect`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Hexadecimal, either case
+    Hex,
+    /// RFC4648 base32, padded or not, either case
+    Base32,
+    /// RFC4648 base32hex, padded or not, either case
+    Base32Hex,
+    /// RFC4648 base64, padded
+    Base64,
+    /// RFC4648 base64, without padding
+    Base64Unpadded,
+    /// RFC4648 base64url, padded
+    Base64Url,
+    /// RFC4648 base64url, without padding
+    Base64UrlUnpadded,
+    /// Base58 with the Bitcoin alphabet
+    Base58,
+    /// Ascii85, optionally within `<~` `~>`
+    Base85,
+}
+
+impl Format {
+    /// Human readable name of the format
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Base32 => "base32",
+            Self::Base32Hex => "base32hex",
+            Self::Base64 => "base64",
+            Self::Base64Unpadded => "base64 (unpadded)",
+            Self::Base64Url => "base64url",
+            Self::Base64UrlUnpadded => "base64url (unpadded)",
+            Self::Base58 => "base58",
+            Self::Base85 => "base85",
+        }
+    }
+
+    /// Score of the format before looking at the decoded data, formats with
+    /// fewer valid strings come first
+    const fn base_score(&self) -> i32 {
+        match self {
+            Self::Hex => 70,
+            Self::Base64 | Self::Base64Url => 60,
+            Self::Base32 => 55,
+            Self::Base64Unpadded | Self::Base64UrlUnpadded => 45,
+            Self::Base32Hex => 40,
+            Self::Base58 => 35,
+            Self::Base85 => 25,
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// One plausible interpretation of the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    /// Encoding the input was decoded as
+    pub format: Format,
+    /// Plausibility from 0 to 100, higher is more likely
+    pub score: u8,
+    /// Input decoded as `format`
+    pub decoded: Vec<u8>,
+}
+
+impl Candidate {
+    /// Length of the decoded data, in bytes
+    pub fn decoded_len(&self) -> usize {
+        self.decoded.len()
+    }
+}
+
+/// Decode with one format, returns the data and whether the input is the
+/// canonical encoding of it
+fn try_decode(format: Format, src: &str) -> Option<(Vec<u8>, bool)> {
+    let bytes = src.as_bytes();
+    let padded = src.len() % 4 == 0;
+    match format {
+        Format::Hex => {
+            let mut dst = vec![0; base16::decode_len(bytes.len())];
+            let n = base16::decode(&mut dst, bytes).ok()?;
+            dst.truncate(n);
+            Some((dst, true))
+        }
+        Format::Base32 | Format::Base32Hex => {
+            let (encode_map, decode_map) = if format == Format::Base32 {
+                (base32::ENCODE_STD, base32::DECODE_STD_MAP)
+            } else {
+                (base32::ENCODE_HEX, base32::DECODE_HEX_MAP)
+            };
+            let unpadded = src.trim_end_matches('=');
+            if unpadded.len() != src.len() && src.len() % 8 != 0 {
+                return None;
+            }
+            let upper = unpadded.to_ascii_uppercase();
+            let dst = base32::decode_unpadded(upper.as_bytes(), decode_map).ok()?;
+            let canonical =
+                base32::encode_unpadded(&dst, encode_map).eq_ignore_ascii_case(unpadded.as_bytes());
+            Some((dst, canonical))
+        }
+        Format::Base64 | Format::Base64Unpadded | Format::Base64Url | Format::Base64UrlUnpadded => {
+            let url = matches!(format, Format::Base64Url | Format::Base64UrlUnpadded);
+            let want_padded = matches!(format, Format::Base64 | Format::Base64Url);
+            if padded != want_padded {
+                return None;
+            }
+            let (encode_map, decode_map) = if url {
+                (base64::ENCODE_URL, base64::DECODE_URL_MAP)
+            } else {
+                (base64::ENCODE_STD, base64::DECODE_STD_MAP)
+            };
+            let (dst, encoded) = if padded {
+                let dst = base64::decode_bytes_with_map(bytes, decode_map).ok()?;
+                let encoded = base64::encode_bytes_with_map(&dst, encode_map);
+                (dst, encoded)
+            } else {
+                let dst = base64::decode_unpadded(bytes, decode_map).ok()?;
+                let encoded = base64::encode_unpadded(&dst, encode_map);
+                (dst, encoded)
+            };
+            Some((dst, encoded == bytes))
+        }
+        Format::Base58 => {
+            let dst = radix::decode_str(src, &radix::BASE58).ok()?;
+            Some((dst, true))
+        }
+        Format::Base85 => {
+            let dst = base85::decode_str(src).ok()?;
+            let body = src.strip_prefix("<~").unwrap_or(src);
+            let body = body.strip_suffix("~>").unwrap_or(body);
+            let canonical = base85::encode_to_string(&dst) == body;
+            Some((dst, canonical))
+        }
+    }
+}
+
+/// Whether the data is UTF-8 text without control characters
+fn is_text(data: &[u8]) -> bool {
+    match std::str::from_utf8(data) {
+        Ok(s) => {
+            !s.is_empty()
+                && s.chars()
+                    .all(|c| !c.is_control() || matches!(c, '\t' | '\r' | '\n'))
+        }
+        Err(_) => false,
+    }
+}
+
+fn score(format: Format, src: &str, decoded: &[u8], canonical: bool) -> u8 {
+    let has = |chars: &str| src.contains(|c| chars.contains(c));
+    let has_lower = src.bytes().any(|b| b.is_ascii_lowercase());
+    let has_upper = src.bytes().any(|b| b.is_ascii_uppercase());
+
+    let mut score = format.base_score();
+    match format {
+        Format::Hex if has_lower && has_upper => score -= 10,
+        Format::Base32 | Format::Base32Hex if has_lower && has_upper => score -= 10,
+        Format::Base64 | Format::Base64Unpadded if has("+/") => score += 10,
+        Format::Base64Url | Format::Base64UrlUnpadded => {
+            // without a distinctive character, the standard alphabet is more common
+            score += if has("-_") { 10 } else { -5 };
+        }
+        Format::Base85 if src.starts_with("<~") => score += 30,
+        _ => {}
+    }
+    if matches!(format, Format::Base64 | Format::Base64Url) && src.ends_with('=') {
+        score += 10;
+    }
+    if !canonical {
+        score -= 30;
+    }
+    if is_text(decoded) {
+        score += 20;
+    }
+    if src.len() < 4 {
+        score -= 20;
+    }
+    score.clamp(0, 100) as u8
+}
+
+/// Guess the encoding of a string
+///
+/// Surrounding white space is ignored. Every format whose decoder accepts the
+/// input is returned, the most plausible first.
+///
+/// - @param src: string of unknown encoding
+/// - @return: candidates ranked by score, empty if no format fits
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::{self, detect::Format};
+/// let candidates = encoding::detect("68656c6c6f");
+/// assert_eq!(candidates[0].format, Format::Hex);
+/// assert_eq!(candidates[0].decoded_len(), 5);
+/// assert!(encoding::detect("not encoded!").is_empty());
+/// ```
+pub fn detect(src: &str) -> Vec<Candidate> {
+    const FORMATS: [Format; 9] = [
+        Format::Hex,
+        Format::Base32,
+        Format::Base32Hex,
+        Format::Base64,
+        Format::Base64Unpadded,
+        Format::Base64Url,
+        Format::Base64UrlUnpadded,
+        Format::Base58,
+        Format::Base85,
+    ];
+
+    let src = src.trim();
+    if src.is_empty() {
+        return Vec::new();
+    }
+    let mut candidates: Vec<Candidate> = FORMATS
+        .into_iter()
+        .filter_map(|format| {
+            let (decoded, canonical) = try_decode(format, src)?;
+            Some(Candidate {
+                format,
+                score: score(format, src, &decoded, canonical),
+                decoded,
+            })
+        })
+        .collect();
+    // stable sort keeps the format order for equal scores
+    candidates.sort_by_key(|c| std::cmp::Reverse(c.score));
+    candidates
+}
+
+#[cfg(test)]
+mod tests_detect {
+    use super::*;
+
+    fn top(src: &str) -> Candidate {
+        detect(src).into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn test_detect_top() {
+        assert_eq!(top("68656c6c6f").format, Format::Hex);
+        assert_eq!(top("DEADBEEF").format, Format::Hex);
+        assert_eq!(top("NBSWY3DP").format, Format::Base32);
+        assert_eq!(top("nbswy3dpeb3w64tmmq======").format, Format::Base32);
+        assert_eq!(top("D1IMOR3F").format, Format::Base32Hex);
+        assert_eq!(top("aGVsbG8=").format, Format::Base64);
+        assert_eq!(top("aGVsbG8").format, Format::Base64Unpadded);
+        assert_eq!(top("-_-_").format, Format::Base64Url);
+        assert_eq!(top("P_9-").format, Format::Base64Url);
+        assert_eq!(top("StV1DL6CwTryKyV").format, Format::Base58);
+        assert_eq!(top("<~BOu!rDZ~>").format, Format::Base85);
+        assert_eq!(top("9jqo^BlbD-BleB1DJ+*+F(f,q").format, Format::Base85);
+    }
+
+    #[test]
+    fn test_detect_decoded() {
+        let candidate = top("  aGVsbG8gd29ybGQ=\n");
+        assert_eq!(candidate.decoded, b"hello world");
+        assert_eq!(candidate.decoded_len(), 11);
+        assert!(candidate.score > 80);
+    }
+
+    #[test]
+    fn test_detect_ranked() {
+        let candidates = detect("cafebabe");
+        let formats: Vec<Format> = candidates.iter().map(|c| c.format).collect();
+        assert_eq!(formats[0], Format::Hex);
+        assert!(formats.contains(&Format::Base64));
+        assert!(formats.contains(&Format::Base85));
+        assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_detect_none() {
+        assert!(detect("").is_empty());
+        assert!(detect("   ").is_empty());
+        assert!(detect("日本語").is_empty());
+    }
+}
@@ -0,0 +1,141 @@
+//! Ascii85, the base85 encoding of btoa and PostScript, for [`detect`](super::detect).
+//!
+//! Every 4 bytes are written as 5 characters from `!` to `u`, a group of
+//! four zero bytes is shortened to `z`, and a final partial group of n bytes
+//! is written as n + 1 characters.
+
+/// First character of the encoding, digit zero
+const FIRST_CHAR: u8 = b'!';
+
+/// Last character of the encoding, digit 84
+const LAST_CHAR: u8 = b'u';
+
+/// Errors when base85 decode
+#[derive(Debug, PartialEq)]
+pub enum Base85Error {
+    InvalidByte(u8),
+    /// Final group of a single character
+    InvalidLength,
+    /// Group value does not fit in 4 bytes
+    Overflow,
+}
+
+impl std::fmt::Display for Base85Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidByte(b) => write!(f, "encoding/base85: invalid byte: {}", b),
+            Self::InvalidLength => write!(f, "encoding/base85: invalid input length"),
+            Self::Overflow => write!(f, "encoding/base85: group overflow"),
+        }
+    }
+}
+
+impl std::error::Error for Base85Error {}
+
+/// Encode bytes to a base85 string, without `<~` `~>` delimiters
+///
+/// - @param src: raw bytes
+/// - @return: base85 encoded string
+pub fn encode_to_string(src: &[u8]) -> String {
+    let mut dst = String::with_capacity(src.len().div_ceil(4) * 5);
+    for chunk in src.chunks(4) {
+        let mut group = [0u8; 4];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let mut val = u32::from_be_bytes(group);
+        if val == 0 && chunk.len() == 4 {
+            dst.push('z');
+            continue;
+        }
+
+        let mut digits = [0u8; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = FIRST_CHAR + (val % 85) as u8;
+            val /= 85;
+        }
+        dst.extend(digits[..chunk.len() + 1].iter().map(|&b| b as char));
+    }
+    dst
+}
+
+/// Decode one group of up to 5 digits, missing digits are taken as `u`
+fn decode_group(dst: &mut Vec<u8>, digits: &[u8]) -> Result<(), Base85Error> {
+    if digits.len() == 1 {
+        return Err(Base85Error::InvalidLength);
+    }
+    let mut val: u32 = 0;
+    for i in 0..5 {
+        let digit = digits.get(i).map_or(84, |&b| b - FIRST_CHAR);
+        val = val
+            .checked_mul(85)
+            .and_then(|v| v.checked_add(u32::from(digit)))
+            .ok_or(Base85Error::Overflow)?;
+    }
+    dst.extend_from_slice(&val.to_be_bytes()[..digits.len() - 1]);
+    Ok(())
+}
+
+/// Decode a base85 string to raw bytes
+///
+/// The `<~` `~>` delimiters are optional and white space is skipped.
+///
+/// - @param src: base85 string
+/// - @return: decoded raw bytes if successfully decoded.
+pub fn decode_str(src: &str) -> Result<Vec<u8>, Base85Error> {
+    let src = src.trim();
+    let src = src.strip_prefix("<~").unwrap_or(src);
+    let src = src.strip_suffix("~>").unwrap_or(src);
+
+    let mut dst = Vec::with_capacity(src.len() / 5 * 4 + 4);
+    let mut group = [0u8; 5];
+    let mut n = 0;
+    for b in src.bytes() {
+        match b {
+            b'z' if n == 0 => dst.extend_from_slice(&[0; 4]),
+            FIRST_CHAR..=LAST_CHAR => {
+                group[n] = b;
+                n += 1;
+                if n == 5 {
+                    decode_group(&mut dst, &group)?;
+                    n = 0;
+                }
+            }
+            b if b.is_ascii_whitespace() => {}
+            _ => return Err(Base85Error::InvalidByte(b)),
+        }
+    }
+    if n > 0 {
+        decode_group(&mut dst, &group[..n])?;
+    }
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests_base85 {
+    use super::*;
+
+    fn test_base(data: &[u8], encoded_expect: &str) {
+        let encoded = encode_to_string(data);
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(decode_str(&encoded), Ok(data.to_vec()));
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(b"", "");
+        test_base(b".", "/c");
+        test_base(b"hello", "BOu!rDZ");
+        test_base(b"\0\0\0\0x", "zGQ");
+        test_base(b"\0\0\0", "!!!!");
+        test_base(b"\xff\xff\xff\xff", "s8W-!");
+        test_base(b"Man is distinguished", "9jqo^BlbD-BleB1DJ+*+F(f,q");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode_str("BOu!r"), Ok(b"hell".to_vec()));
+        assert_eq!(decode_str("BOu!rD"), Err(Base85Error::InvalidLength));
+        assert_eq!(decode_str("!z"), Err(Base85Error::InvalidByte(b'z')));
+        assert_eq!(decode_str("BOv"), Err(Base85Error::InvalidByte(b'v')));
+        assert_eq!(decode_str("uuuuu"), Err(Base85Error::Overflow));
+    }
+}
@@ -0,0 +1,63 @@
+//! Delimiter splitting shared by the COBS and SLIP frame decoders.
+
+/// Bytes of the frame in progress, with an optional length limit.
+///
+/// A frame exceeding the limit is reported once and dropped up to the next
+/// delimiter, empty frames between two delimiters are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct FrameBuffer {
+    buf: Vec<u8>,
+    max_len: Option<usize>,
+    overflow: bool,
+}
+
+impl FrameBuffer {
+    /// Limit the encoded length of a frame
+    pub(crate) fn set_max_len(&mut self, max_len: usize) {
+        self.max_len = Some(max_len);
+    }
+
+    /// Bytes of the incomplete frame buffered so far
+    pub(crate) fn pending(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Split `data` on `delimiter` and decode every frame completed by it
+    ///
+    /// - @param data: received bytes
+    /// - @param delimiter: frame delimiter
+    /// - @param too_long: error reported for a frame exceeding the limit
+    /// - @param decode: decoder of a complete frame, without the delimiter
+    /// - @return: decoded frames and errors, in stream order
+    pub(crate) fn push<T, E>(
+        &mut self,
+        data: &[u8],
+        delimiter: u8,
+        too_long: impl Fn() -> E,
+        mut decode: impl FnMut(&[u8]) -> Result<T, E>,
+    ) -> Vec<Result<T, E>> {
+        let mut frames = Vec::new();
+        for chunk in data.split_inclusive(|&b| b == delimiter) {
+            let (body, complete) = match chunk.split_last() {
+                Some((&last, body)) if last == delimiter => (body, true),
+                _ => (chunk, false),
+            };
+            if !self.overflow {
+                self.buf.extend_from_slice(body);
+                if self.max_len.is_some_and(|max_len| self.buf.len() > max_len) {
+                    self.buf.clear();
+                    self.overflow = true;
+                    frames.push(Err(too_long()));
+                }
+            }
+            if complete {
+                if !self.overflow && !self.buf.is_empty() {
+                    frames.push(decode(&self.buf));
+                }
+                self.buf.clear();
+                self.overflow = false;
+            }
+        }
+        frames
+    }
+}
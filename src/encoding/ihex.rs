@@ -0,0 +1,304 @@
+//! Intel HEX implemented, the firmware file format of record types 00 to 05.
+//!
+//! Every line is a record `:LLAAAATT<data>CC` of hex digits: data length,
+//! 16 bit address offset, record type, data and a two's complement checksum.
+//! Addresses above 64 KiB are reached with extended segment (02) or extended
+//! linear (04) address records.
+//!
+//! # Examples
+//!
+//! Intel HEX encode basic usage:
+//!
+//! ```
+//! use connx::encoding::{ihex, image::MemoryImage};
+//! let mut image = MemoryImage::new();
+//! image.write(0x0100, b"hello");
+//! assert_eq!(ihex::encode(&image, 16), ":0501000068656C6C6FE6\n:00000001FF\n");
+//! ```
+//!
+//! Intel HEX decode basic usage:
+//!
+//! ```
+//! use connx::encoding::ihex;
+//! let image = ihex::decode(":0501000068656C6C6FE6\n:00000001FF\n").unwrap();
+//! assert_eq!(image.get(0x0104), Some(b'o'));
+//! ```
+
+use crate::encoding::base16;
+use crate::encoding::image::MemoryImage;
+
+/// Default number of data bytes per record
+pub const RECORD_LEN: usize = 16;
+
+const DATA: u8 = 0x00;
+const END_OF_FILE: u8 = 0x01;
+const EXTENDED_SEGMENT_ADDRESS: u8 = 0x02;
+const START_SEGMENT_ADDRESS: u8 = 0x03;
+const EXTENDED_LINEAR_ADDRESS: u8 = 0x04;
+const START_LINEAR_ADDRESS: u8 = 0x05;
+
+/// Errors when Intel HEX decode, with the 1-based line number
+#[derive(Debug, PartialEq)]
+pub enum IhexError {
+    /// Missing `:`, bad hex digits, or length not matching the record
+    InvalidRecord(usize),
+    InvalidChecksum(usize),
+    /// Record type other than 00 to 05
+    InvalidType(usize),
+    /// Data extends past the 32 bit address space
+    AddressOverflow(usize),
+    /// No end of file record
+    MissingEof,
+}
+
+impl std::fmt::Display for IhexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRecord(line) => {
+                write!(f, "encoding/ihex: invalid record at line {}", line)
+            }
+            Self::InvalidChecksum(line) => {
+                write!(f, "encoding/ihex: invalid checksum at line {}", line)
+            }
+            Self::InvalidType(line) => {
+                write!(f, "encoding/ihex: invalid record type at line {}", line)
+            }
+            Self::AddressOverflow(line) => {
+                write!(f, "encoding/ihex: address overflow at line {}", line)
+            }
+            Self::MissingEof => write!(f, "encoding/ihex: missing end of file record"),
+        }
+    }
+}
+
+impl std::error::Error for IhexError {}
+
+/// Append one record line, computing its checksum
+fn write_record(dst: &mut String, ty: u8, offset: u16, data: &[u8]) {
+    let mut record = Vec::with_capacity(data.len() + 5);
+    record.push(data.len() as u8);
+    record.extend_from_slice(&offset.to_be_bytes());
+    record.push(ty);
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+    record.push(sum.wrapping_neg());
+
+    let mut hex = vec![0; base16::encode_len(record.len())];
+    base16::encode(&mut hex, &record);
+    hex.make_ascii_uppercase();
+    dst.push(':');
+    dst.push_str(std::str::from_utf8(&hex).unwrap());
+    dst.push('\n');
+}
+
+/// Encode a memory image to Intel HEX
+///
+/// Extended linear address records are written when the upper 16 bits of
+/// the address change, the start address as a start linear address record.
+///
+/// - @param image: memory image
+/// - @param record_len: data bytes per record, 1 to 255
+/// - @return: Intel HEX text, uppercase with LF line breaks
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::{ihex, image::MemoryImage};
+/// let mut image = MemoryImage::new();
+/// image.write(0x0800_0000, &[0xAA, 0xBB]);
+/// image.start_address = Some(0x0800_0000);
+/// let lines: Vec<String> = ihex::encode(&image, 16).lines().map(String::from).collect();
+/// assert_eq!(lines, [":020000040800F2", ":02000000AABB99", ":0400000508000000EF", ":00000001FF"]);
+/// ```
+///
+/// # Panics
+///
+/// Panics if `record_len` is not in 1..=255.
+pub fn encode(image: &MemoryImage, record_len: usize) -> String {
+    assert!(
+        (1..=255).contains(&record_len),
+        "encoding/ihex: invalid record length"
+    );
+    let mut dst = String::with_capacity(image.len() * 2 + image.len() / record_len * 12 + 16);
+    let mut upper: u16 = 0;
+    for (addr, data) in image.segments() {
+        let mut addr = addr;
+        let mut data = data;
+        while !data.is_empty() {
+            let addr_upper = (addr >> 16) as u16;
+            if addr_upper != upper {
+                write_record(
+                    &mut dst,
+                    EXTENDED_LINEAR_ADDRESS,
+                    0,
+                    &addr_upper.to_be_bytes(),
+                );
+                upper = addr_upper;
+            }
+            // a record never crosses a 64 KiB boundary
+            let to_boundary = 0x1_0000 - (addr & 0xFFFF) as usize;
+            let n = data.len().min(record_len).min(to_boundary);
+            write_record(&mut dst, DATA, addr as u16, &data[..n]);
+            data = &data[n..];
+            addr = addr.wrapping_add(n as u32);
+        }
+    }
+    if let Some(start) = image.start_address {
+        write_record(&mut dst, START_LINEAR_ADDRESS, 0, &start.to_be_bytes());
+    }
+    write_record(&mut dst, END_OF_FILE, 0, &[]);
+    dst
+}
+
+/// Parse one record line to its bytes, checksum verified
+fn read_record(line: &str, line_no: usize) -> Result<Vec<u8>, IhexError> {
+    let hex = line
+        .strip_prefix(':')
+        .ok_or(IhexError::InvalidRecord(line_no))?;
+    let mut record = vec![0; base16::decode_len(hex.len())];
+    base16::decode(&mut record, hex.as_bytes()).map_err(|_| IhexError::InvalidRecord(line_no))?;
+    if record.len() < 5 || record.len() != record[0] as usize + 5 {
+        return Err(IhexError::InvalidRecord(line_no));
+    }
+    if record.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)) != 0 {
+        return Err(IhexError::InvalidChecksum(line_no));
+    }
+    record.pop();
+    Ok(record)
+}
+
+/// Decode Intel HEX to a memory image
+///
+/// Blank lines are skipped and both LF and CRLF are accepted, lines after
+/// the end of file record are ignored. A start segment address (03) is kept
+/// as `CS << 16 | IP`.
+///
+/// - @param src: Intel HEX text
+/// - @return: memory image if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::ihex::{self, IhexError};
+/// let image = ihex::decode(":020000021000EC\n:0100000041BE\n:00000001FF").unwrap();
+/// assert_eq!(image.get(0x10000), Some(b'A'));
+/// assert_eq!(ihex::decode(":0100000041BF\n"), Err(IhexError::InvalidChecksum(1)));
+/// ```
+pub fn decode(src: &str) -> Result<MemoryImage, IhexError> {
+    let mut image = MemoryImage::new();
+    let mut base: u32 = 0;
+    for (idx, line) in src.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let record = read_record(line, line_no)?;
+        let offset = u16::from_be_bytes([record[1], record[2]]);
+        let data = &record[4..];
+        let invalid = || IhexError::InvalidRecord(line_no);
+        match record[3] {
+            DATA => {
+                let addr = u64::from(base) + u64::from(offset);
+                if addr + data.len() as u64 > 1 << 32 {
+                    return Err(IhexError::AddressOverflow(line_no));
+                }
+                image.write(addr as u32, data);
+            }
+            END_OF_FILE => return Ok(image),
+            EXTENDED_SEGMENT_ADDRESS => {
+                let segment: [u8; 2] = data.try_into().map_err(|_| invalid())?;
+                base = u32::from(u16::from_be_bytes(segment)) << 4;
+            }
+            EXTENDED_LINEAR_ADDRESS => {
+                let upper: [u8; 2] = data.try_into().map_err(|_| invalid())?;
+                base = u32::from(u16::from_be_bytes(upper)) << 16;
+            }
+            START_SEGMENT_ADDRESS | START_LINEAR_ADDRESS => {
+                let start: [u8; 4] = data.try_into().map_err(|_| invalid())?;
+                image.start_address = Some(u32::from_be_bytes(start));
+            }
+            _ => return Err(IhexError::InvalidType(line_no)),
+        }
+    }
+    Err(IhexError::MissingEof)
+}
+
+#[cfg(test)]
+mod tests_ihex {
+    use super::*;
+
+    const WIKIPEDIA: &str = "\
+:10010000214601360121470136007EFE09D2190140
+:100110002146017E17C20001FF5F16002148011928
+:10012000194E79234623965778239EDA3F01B2CAA7
+:100130003F0156702B5E712B722B732146013421C7
+:00000001FF
+";
+
+    #[test]
+    fn test_roundtrip() {
+        let image = decode(WIKIPEDIA).unwrap();
+        let segments: Vec<(u32, usize)> = image.segments().map(|(a, d)| (a, d.len())).collect();
+        assert_eq!(segments, [(0x0100, 64)]);
+        assert_eq!(image.get(0x0100), Some(0x21));
+        assert_eq!(image.get(0x013F), Some(0x21));
+        assert_eq!(encode(&image, RECORD_LEN), WIKIPEDIA);
+    }
+
+    #[test]
+    fn test_extended_linear_boundary() {
+        let mut image = MemoryImage::new();
+        image.write(0x0001_FFFE, b"abcd");
+        let encoded = encode(&image, RECORD_LEN);
+        let lines: Vec<&str> = encoded.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                ":020000040001F9",
+                ":02FFFE0061623E",
+                ":020000040002F8",
+                ":02000000636437",
+                ":00000001FF"
+            ]
+        );
+        assert_eq!(decode(&encoded).unwrap(), image);
+    }
+
+    #[test]
+    fn test_start_address() {
+        let image = decode(":0400000300003800C1\n:00000001FF\n").unwrap();
+        assert_eq!(image.start_address, Some(0x0000_3800));
+        let image = decode(":04000005000000CD2A\r\n:00000001FF\r\n").unwrap();
+        assert_eq!(image.start_address, Some(0xCD));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(""), Err(IhexError::MissingEof));
+        assert_eq!(decode("0100000041BE"), Err(IhexError::InvalidRecord(1)));
+        assert_eq!(decode(":0100000041"), Err(IhexError::InvalidRecord(1)));
+        assert_eq!(decode(":030000000041BE"), Err(IhexError::InvalidRecord(1)));
+        assert_eq!(decode(":01000000G1BE"), Err(IhexError::InvalidRecord(1)));
+        assert_eq!(
+            decode("\n:0100000041BF"),
+            Err(IhexError::InvalidChecksum(2))
+        );
+        assert_eq!(decode(":00000006FA"), Err(IhexError::InvalidType(1)));
+        assert_eq!(decode(":0100000441BA"), Err(IhexError::InvalidRecord(1)));
+        assert_eq!(
+            decode(":02000004FFFFFC\n:02FFFF0041427D\n"),
+            Err(IhexError::AddressOverflow(2))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid record length")]
+    fn test_encode_record_len() {
+        encode(&MemoryImage::new(), 256);
+    }
+}
@@ -0,0 +1,183 @@
+//! Sparse memory image, the content of firmware files.
+//!
+//! The image maps 32 bit addresses to bytes, stored as sorted contiguous
+//! segments. Overlapping or adjacent writes are merged into one segment, a
+//! later write replaces the bytes of an earlier one. It is read and written
+//! by [`ihex`] and [`srec`].
+//!
+//! [`ihex`]: crate::encoding::ihex
+//! [`srec`]: crate::encoding::srec
+//!
+//! # Examples
+//!
+//! Basic usage:
+//!
+//! ```
+//! use connx::encoding::image::MemoryImage;
+//! let mut image = MemoryImage::new();
+//! image.write(0x1000, b"abc");
+//! image.write(0x1003, b"def");
+//! image.write(0x8000, b"xyz");
+//! let segments: Vec<(u32, &[u8])> = image.segments().collect();
+//! assert_eq!(segments, [(0x1000, &b"abcdef"[..]), (0x8000, &b"xyz"[..])]);
+//! ```
+
+use std::collections::BTreeMap;
+
+/// Addresses past the end of the 32 bit address space
+const ADDRESS_SPACE: u64 = 1 << 32;
+
+/// Sparse memory image, address to bytes
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryImage {
+    segments: BTreeMap<u32, Vec<u8>>,
+    /// Execution start address, if the file defines one
+    pub start_address: Option<u32>,
+}
+
+impl MemoryImage {
+    /// Create an empty image
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the image holds no byte
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Number of bytes in the image, gaps excluded
+    pub fn len(&self) -> usize {
+        self.segments.values().map(Vec::len).sum()
+    }
+
+    /// Write bytes at an address, merging them with the segments they touch
+    ///
+    /// # Panics
+    ///
+    /// Panics if the data extends past the 32 bit address space.
+    pub fn write(&mut self, addr: u32, data: &[u8]) {
+        let start = u64::from(addr);
+        let end = start + data.len() as u64;
+        assert!(end <= ADDRESS_SPACE, "encoding/image: address overflow");
+        if data.is_empty() {
+            return;
+        }
+
+        // segments overlapping or adjacent to [start, end)
+        let upper = end.min(ADDRESS_SPACE - 1) as u32;
+        let touched: Vec<u32> = self
+            .segments
+            .range(..=upper)
+            .rev()
+            .take_while(|(&seg_start, seg)| u64::from(seg_start) + seg.len() as u64 >= start)
+            .map(|(&seg_start, _)| seg_start)
+            .collect();
+
+        // reuse the lowest segment if it starts first, which makes appends cheap
+        let (base, mut buf) = match touched.last() {
+            Some(&first) if first <= addr => (first, self.segments.remove(&first).unwrap()),
+            _ => (addr, Vec::new()),
+        };
+        for &seg_start in touched.iter().rev() {
+            if let Some(seg) = self.segments.remove(&seg_start) {
+                let offset = (seg_start - base) as usize;
+                if buf.len() < offset + seg.len() {
+                    buf.resize(offset + seg.len(), 0);
+                }
+                buf[offset..offset + seg.len()].copy_from_slice(&seg);
+            }
+        }
+        let offset = (addr - base) as usize;
+        if buf.len() < offset + data.len() {
+            buf.resize(offset + data.len(), 0);
+        }
+        buf[offset..offset + data.len()].copy_from_slice(data);
+        self.segments.insert(base, buf);
+    }
+
+    /// Read the byte at an address
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::image::MemoryImage;
+    /// let mut image = MemoryImage::new();
+    /// image.write(0x10, b"ab");
+    /// assert_eq!(image.get(0x11), Some(b'b'));
+    /// assert_eq!(image.get(0x12), None);
+    /// ```
+    pub fn get(&self, addr: u32) -> Option<u8> {
+        let (&seg_start, seg) = self.segments.range(..=addr).next_back()?;
+        seg.get((addr - seg_start) as usize).copied()
+    }
+
+    /// Contiguous segments in address order
+    pub fn segments(&self) -> impl Iterator<Item = (u32, &[u8])> {
+        self.segments
+            .iter()
+            .map(|(&addr, data)| (addr, data.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests_image {
+    use super::*;
+
+    fn segments(image: &MemoryImage) -> Vec<(u32, Vec<u8>)> {
+        image.segments().map(|(a, d)| (a, d.to_vec())).collect()
+    }
+
+    #[test]
+    fn test_write_merge() {
+        let mut image = MemoryImage::new();
+        assert!(image.is_empty());
+        image.write(10, b"cd");
+        image.write(20, b"xy");
+        image.write(8, b"ab");
+        assert_eq!(
+            segments(&image),
+            [(8, b"abcd".to_vec()), (20, b"xy".to_vec())]
+        );
+
+        // bridge the gap, overwriting both sides
+        image.write(11, b"123456789z");
+        assert_eq!(segments(&image), [(8, b"abc123456789zy".to_vec())]);
+        assert_eq!(image.len(), 14);
+
+        image.write(0, b"0");
+        image.write(9, b"B");
+        assert_eq!(
+            segments(&image),
+            [(0, b"0".to_vec()), (8, b"aBc123456789zy".to_vec())]
+        );
+    }
+
+    #[test]
+    fn test_write_cover() {
+        let mut image = MemoryImage::new();
+        image.write(4, b"ab");
+        image.write(8, b"cd");
+        image.write(2, b"0123456789");
+        assert_eq!(segments(&image), [(2, b"0123456789".to_vec())]);
+        image.write(0, b"");
+        assert_eq!(image.len(), 10);
+    }
+
+    #[test]
+    fn test_address_space_end() {
+        let mut image = MemoryImage::new();
+        image.write(0xFFFF_FFFE, b"ab");
+        image.write(0xFFFF_FFFC, b"xy");
+        assert_eq!(segments(&image), [(0xFFFF_FFFC, b"xyab".to_vec())]);
+        assert_eq!(image.get(0xFFFF_FFFF), Some(b'b'));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_address_overflow() {
+        MemoryImage::new().write(0xFFFF_FFFF, b"ab");
+    }
+}
@@ -0,0 +1,335 @@
+//! Multibase implemented, self-describing base encodings.
+//!
+//! The encoded string starts with one code character naming its base, so a
+//! decoder can tell `f68656c6c6f` (hex) from `maGVsbG8` (base64) without any
+//! side channel. Encoding and decoding are dispatched to the [`base16`],
+//! [`base32`], [`base64`] and [`radix`] modules.
+//!
+//! [`base16`]: crate::encoding::base16
+//! [`base32`]: crate::encoding::base32
+//! [`base64`]: crate::encoding::base64
+//! [`radix`]: crate::encoding::radix
+//!
+//! # Examples
+//!
+//! Multibase encode basic usage:
+//!
+//! ```
+//! use connx::encoding::multibase::{self, Base};
+//! assert_eq!(multibase::encode(Base::Base58Btc, b"yes mani !"), "z7paNL19xttacUY");
+//! ```
+//!
+//! Multibase decode basic usage:
+//!
+//! ```
+//! use connx::encoding::multibase::{self, Base};
+//! assert_eq!(multibase::decode("bpfsxgidnmfxgsibb"), Ok((Base::Base32, b"yes mani !".to_vec())));
+//! assert_eq!(multibase::decode("MeWVzIG1hbmkgIQ=="), Ok((Base::Base64Pad, b"yes mani !".to_vec())));
+//! ```
+
+use crate::encoding::base16::{self, Base16Error};
+use crate::encoding::base32::{self, Base32Error};
+use crate::encoding::base64::{self, Base64Error};
+use crate::encoding::radix::{self, RadixError};
+
+/// Bases supported by multibase, with their code character
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Base {
+    /// `f`, hexadecimal lowercase
+    Base16,
+    /// `F`, hexadecimal uppercase
+    Base16Upper,
+    /// `b`, RFC4648 base32 lowercase, no padding
+    Base32,
+    /// `B`, RFC4648 base32 uppercase, no padding
+    Base32Upper,
+    /// `v`, RFC4648 base32hex lowercase, no padding
+    Base32Hex,
+    /// `V`, RFC4648 base32hex uppercase, no padding
+    Base32HexUpper,
+    /// `z`, base58 with the Bitcoin alphabet
+    Base58Btc,
+    /// `m`, RFC4648 base64, no padding
+    Base64,
+    /// `M`, RFC4648 base64, with padding
+    Base64Pad,
+    /// `u`, RFC4648 base64url, no padding
+    Base64Url,
+    /// `U`, RFC4648 base64url, with padding
+    Base64UrlPad,
+}
+
+impl Base {
+    /// Every supported base
+    pub const ALL: [Base; 11] = [
+        Base::Base16,
+        Base::Base16Upper,
+        Base::Base32,
+        Base::Base32Upper,
+        Base::Base32Hex,
+        Base::Base32HexUpper,
+        Base::Base58Btc,
+        Base::Base64,
+        Base::Base64Pad,
+        Base::Base64Url,
+        Base::Base64UrlPad,
+    ];
+
+    /// Code character prefixed to the encoded data
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::multibase::Base;
+    /// assert_eq!(Base::Base58Btc.code(), 'z');
+    /// ```
+    pub const fn code(&self) -> char {
+        match self {
+            Self::Base16 => 'f',
+            Self::Base16Upper => 'F',
+            Self::Base32 => 'b',
+            Self::Base32Upper => 'B',
+            Self::Base32Hex => 'v',
+            Self::Base32HexUpper => 'V',
+            Self::Base58Btc => 'z',
+            Self::Base64 => 'm',
+            Self::Base64Pad => 'M',
+            Self::Base64Url => 'u',
+            Self::Base64UrlPad => 'U',
+        }
+    }
+
+    /// Look up a base by its code character
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::multibase::Base;
+    /// assert_eq!(Base::from_code('u'), Some(Base::Base64Url));
+    /// assert_eq!(Base::from_code('Q'), None);
+    /// ```
+    pub fn from_code(code: char) -> Option<Base> {
+        Self::ALL.into_iter().find(|base| base.code() == code)
+    }
+}
+
+/// Errors when multibase decode
+#[derive(Debug, PartialEq)]
+pub enum MultibaseError {
+    /// Input has no code character
+    EmptyInput,
+    /// Code character of an unsupported base
+    UnknownCode(char),
+    Base16(Base16Error),
+    Base32(Base32Error),
+    Base58(RadixError),
+    Base64(Base64Error),
+}
+
+impl std::fmt::Display for MultibaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::EmptyInput => write!(f, "encoding/multibase: empty input"),
+            Self::UnknownCode(c) => write!(f, "encoding/multibase: unknown code: {:?}", c),
+            Self::Base16(e) => e.fmt(f),
+            Self::Base32(e) => e.fmt(f),
+            Self::Base58(e) => e.fmt(f),
+            Self::Base64(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MultibaseError {}
+
+impl From<Base16Error> for MultibaseError {
+    fn from(e: Base16Error) -> Self {
+        Self::Base16(e)
+    }
+}
+
+impl From<Base32Error> for MultibaseError {
+    fn from(e: Base32Error) -> Self {
+        Self::Base32(e)
+    }
+}
+
+impl From<RadixError> for MultibaseError {
+    fn from(e: RadixError) -> Self {
+        Self::Base58(e)
+    }
+}
+
+impl From<Base64Error> for MultibaseError {
+    fn from(e: Base64Error) -> Self {
+        Self::Base64(e)
+    }
+}
+
+/// Encode bytes with a base, prefixed by its code character
+///
+/// - @param base: target base
+/// - @param src: raw bytes
+/// - @return: multibase encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::multibase::{self, Base};
+/// assert_eq!(multibase::encode(Base::Base16Upper, b"hello"), "F68656C6C6F");
+/// assert_eq!(multibase::encode(Base::Base64Url, b"hello"), "uaGVsbG8");
+/// ```
+pub fn encode(base: Base, src: &[u8]) -> String {
+    let body = match base {
+        Base::Base16 | Base::Base16Upper => {
+            let mut dst = vec![0; base16::encode_len(src.len())];
+            base16::encode(&mut dst, src);
+            if base == Base::Base16Upper {
+                dst.make_ascii_uppercase();
+            }
+            dst
+        }
+        Base::Base32 => base32::encode_unpadded(src, base32::ENCODE_STD).to_ascii_lowercase(),
+        Base::Base32Upper => base32::encode_unpadded(src, base32::ENCODE_STD),
+        Base::Base32Hex => base32::encode_unpadded(src, base32::ENCODE_HEX).to_ascii_lowercase(),
+        Base::Base32HexUpper => base32::encode_unpadded(src, base32::ENCODE_HEX),
+        Base::Base58Btc => radix::encode_to_string(src, &radix::BASE58).into_bytes(),
+        Base::Base64 => base64::encode_unpadded(src, base64::ENCODE_STD),
+        Base::Base64Pad => base64::encode_bytes_with_map(src, base64::ENCODE_STD),
+        Base::Base64Url => base64::encode_unpadded(src, base64::ENCODE_URL),
+        Base::Base64UrlPad => base64::encode_bytes_with_map(src, base64::ENCODE_URL),
+    };
+
+    let mut dst = String::with_capacity(body.len() + 1);
+    dst.push(base.code());
+    dst.push_str(std::str::from_utf8(&body).unwrap());
+    dst
+}
+
+/// Decode a multibase string, the base is chosen by the code character
+///
+/// Hex and base32 are decoded case-insensitively, as the multibase
+/// specification recommends.
+///
+/// - @param src: multibase encoded string
+/// - @return: the base and the decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::multibase::{self, Base, MultibaseError};
+/// assert_eq!(multibase::decode("f68656c6c6f"), Ok((Base::Base16, b"hello".to_vec())));
+/// assert_eq!(multibase::decode("Qm"), Err(MultibaseError::UnknownCode('Q')));
+/// ```
+pub fn decode(src: &str) -> Result<(Base, Vec<u8>), MultibaseError> {
+    let mut chars = src.chars();
+    let code = chars.next().ok_or(MultibaseError::EmptyInput)?;
+    let base = Base::from_code(code).ok_or(MultibaseError::UnknownCode(code))?;
+    let body = chars.as_str().as_bytes();
+
+    let data = match base {
+        Base::Base16 | Base::Base16Upper => {
+            let mut dst = vec![0; base16::decode_len(body.len())];
+            let n = base16::decode(&mut dst, body)?;
+            dst.truncate(n);
+            dst
+        }
+        Base::Base32 | Base::Base32Upper => {
+            base32::decode_unpadded(&body.to_ascii_uppercase(), base32::DECODE_STD_MAP)?
+        }
+        Base::Base32Hex | Base::Base32HexUpper => {
+            base32::decode_unpadded(&body.to_ascii_uppercase(), base32::DECODE_HEX_MAP)?
+        }
+        Base::Base58Btc => radix::decode_str(chars.as_str(), &radix::BASE58)?,
+        Base::Base64 => base64::decode_unpadded(body, base64::DECODE_STD_MAP)?,
+        Base::Base64Pad => base64::decode_bytes_with_map(body, base64::DECODE_STD_MAP)?,
+        Base::Base64Url => base64::decode_unpadded(body, base64::DECODE_URL_MAP)?,
+        Base::Base64UrlPad => base64::decode_bytes_with_map(body, base64::DECODE_URL_MAP)?,
+    };
+    Ok((base, data))
+}
+
+#[cfg(test)]
+mod tests_multibase {
+    use super::*;
+
+    fn test_base(base: Base, data: &[u8], encoded_expect: &str) {
+        let encoded = encode(base, data);
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(decode(&encoded), Ok((base, data.to_vec())));
+    }
+
+    #[test]
+    fn test_spec_vectors() {
+        let data = b"yes mani !";
+        test_base(Base::Base16, data, "f796573206d616e692021");
+        test_base(Base::Base16Upper, data, "F796573206D616E692021");
+        test_base(Base::Base32, data, "bpfsxgidnmfxgsibb");
+        test_base(Base::Base32Upper, data, "BPFSXGIDNMFXGSIBB");
+        test_base(Base::Base32Hex, data, "vf5in683dc5n6i811");
+        test_base(Base::Base32HexUpper, data, "VF5IN683DC5N6I811");
+        test_base(Base::Base58Btc, data, "z7paNL19xttacUY");
+        test_base(Base::Base64, data, "meWVzIG1hbmkgIQ");
+        test_base(Base::Base64Pad, data, "MeWVzIG1hbmkgIQ==");
+        test_base(Base::Base64Url, data, "ueWVzIG1hbmkgIQ");
+        test_base(Base::Base64UrlPad, data, "UeWVzIG1hbmkgIQ==");
+    }
+
+    #[test]
+    fn test_empty_and_leading_zeros() {
+        for base in Base::ALL {
+            test_base(base, b"", &base.code().to_string());
+        }
+        test_base(Base::Base58Btc, b"\x00\x00yes", "z11hmzS");
+        test_base(Base::Base32, b"\x00", "baa");
+    }
+
+    #[test]
+    fn test_decode_case_insensitive() {
+        assert_eq!(
+            decode("F68656c6C6f"),
+            Ok((Base::Base16Upper, b"hello".to_vec()))
+        );
+        assert_eq!(
+            decode("bPFSXGIDNMFXGSIBB"),
+            Ok((Base::Base32, b"yes mani !".to_vec()))
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(""), Err(MultibaseError::EmptyInput));
+        assert_eq!(decode("é"), Err(MultibaseError::UnknownCode('é')));
+        assert_eq!(
+            decode("f6"),
+            Err(MultibaseError::Base16(Base16Error::OddLength))
+        );
+        assert_eq!(
+            decode("babc"),
+            Err(MultibaseError::Base32(Base32Error::InvalidLength))
+        );
+        assert_eq!(
+            decode("z0"),
+            Err(MultibaseError::Base58(RadixError::InvalidByte(b'0')))
+        );
+        assert_eq!(
+            decode("maGVsbG8="),
+            Err(MultibaseError::Base64(Base64Error::InvalidLength))
+        );
+        assert_eq!(
+            decode("MaGVsbG8"),
+            Err(MultibaseError::Base64(Base64Error::InvalidLength))
+        );
+        assert!(matches!(
+            decode("uaGV+bG8"),
+            Err(MultibaseError::Base64(Base64Error::InvalidByte(_)))
+        ));
+    }
+}
@@ -0,0 +1,440 @@
+//! RFC3986 implemented, percent encoding.
+//!
+//! Bytes in the chosen [`AsciiSet`] and every non-ASCII byte are written as
+//! `%XX` with uppercase hex digits, all other bytes are copied as is.
+//! `application/x-www-form-urlencoded` additionally writes space as `+`.
+//!
+//! # Examples
+//!
+//! Percent encode basic usage:
+//!
+//! ```
+//! use connx::encoding::percent;
+//! let segment = percent::encode(b"a b/c", &percent::PATH_SEGMENT);
+//! assert_eq!(format!("/files/{}", segment), "/files/a%20b%2Fc");
+//! ```
+//!
+//! Percent decode basic usage:
+//!
+//! ```
+//! use connx::encoding::percent;
+//! assert_eq!(percent::decode_str("a%20b%2Fc").unwrap(), "a b/c");
+//! ```
+
+use std::borrow::Cow;
+
+use crate::encoding::base16::{from_hex_char, HEX_TABLE_UPPER};
+
+/// Errors when percent decode
+#[derive(Debug, PartialEq)]
+pub enum PercentError {
+    /// `%` at this offset is not followed by two hex digits
+    InvalidEscape(usize),
+    /// Decoded bytes are not valid UTF-8
+    InvalidUtf8,
+}
+
+impl std::fmt::Display for PercentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidEscape(offset) => {
+                write!(f, "encoding/percent: invalid escape at offset {}", offset)
+            }
+            Self::InvalidUtf8 => write!(f, "encoding/percent: invalid utf-8"),
+        }
+    }
+}
+
+impl std::error::Error for PercentError {}
+
+/// Set of ASCII bytes which must be percent encoded.
+///
+/// Non-ASCII bytes are always encoded, whatever the set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsciiSet {
+    mask: [u32; 4],
+}
+
+impl AsciiSet {
+    /// Set without any byte, only non-ASCII bytes are encoded
+    pub const EMPTY: AsciiSet = AsciiSet { mask: [0; 4] };
+
+    /// Add a byte to the set
+    pub const fn add(mut self, b: u8) -> Self {
+        assert!(b.is_ascii(), "encoding/percent: byte is not ascii");
+        self.mask[(b >> 5) as usize] |= 1 << (b & 0x1F);
+        self
+    }
+
+    /// Remove a byte from the set
+    pub const fn remove(mut self, b: u8) -> Self {
+        assert!(b.is_ascii(), "encoding/percent: byte is not ascii");
+        self.mask[(b >> 5) as usize] &= !(1 << (b & 0x1F));
+        self
+    }
+
+    /// Add every byte of `chars` to the set
+    pub const fn add_all(mut self, chars: &[u8]) -> Self {
+        let mut i = 0;
+        while i < chars.len() {
+            self = self.add(chars[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Remove every byte of `chars` from the set
+    pub const fn remove_all(mut self, chars: &[u8]) -> Self {
+        let mut i = 0;
+        while i < chars.len() {
+            self = self.remove(chars[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Bytes in either set
+    pub const fn union(self, other: AsciiSet) -> Self {
+        AsciiSet {
+            mask: [
+                self.mask[0] | other.mask[0],
+                self.mask[1] | other.mask[1],
+                self.mask[2] | other.mask[2],
+                self.mask[3] | other.mask[3],
+            ],
+        }
+    }
+
+    /// Whether the ASCII byte `b` is in the set
+    #[inline]
+    pub const fn contains(&self, b: u8) -> bool {
+        b.is_ascii() && self.mask[(b >> 5) as usize] & (1 << (b & 0x1F)) != 0
+    }
+
+    #[inline]
+    fn should_encode(&self, b: u8) -> bool {
+        !b.is_ascii() || self.contains(b)
+    }
+}
+
+/// C0 control bytes and DEL
+pub const CONTROLS: AsciiSet = AsciiSet {
+    mask: [0xFFFF_FFFF, 0, 0, 0x8000_0000],
+};
+
+/// Everything but ASCII letters and digits
+pub const NON_ALPHANUMERIC: AsciiSet = CONTROLS.add_all(b" !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~");
+
+/// Everything but the RFC3986 unreserved characters `A-Z a-z 0-9 - . _ ~`
+pub const COMPONENT: AsciiSet = NON_ALPHANUMERIC.remove_all(b"-._~");
+
+/// RFC3986 path segment, keeps sub-delims, `:` and `@`
+pub const PATH_SEGMENT: AsciiSet = COMPONENT.remove_all(b"!$&'()*+,;=:@");
+
+/// Key or value of a query string, keeps `/` and `?` but encodes `&`, `=`,
+/// `+` and `#` so that the component can not break the query apart
+pub const QUERY_COMPONENT: AsciiSet = COMPONENT.remove_all(b"!$'()*,;:@/?");
+
+/// RFC3986 userinfo, keeps sub-delims and `:`
+pub const USERINFO: AsciiSet = COMPONENT.remove_all(b"!$&'()*+,;=:");
+
+/// RFC3986 fragment, keeps sub-delims, `:`, `@`, `/` and `?`
+pub const FRAGMENT: AsciiSet = COMPONENT.remove_all(b"!$&'()*+,;=:@/?");
+
+/// `application/x-www-form-urlencoded`, keeps `* - . _`, space is written as
+/// `+` by [`encode_form`]
+pub const FORM: AsciiSet = NON_ALPHANUMERIC.remove_all(b"*-._");
+
+/// Lazy percent encoder returned by [`encode`] and [`encode_form`].
+///
+/// Implements `Display`, so it can be written into a `format!` or any
+/// `fmt::Write` without an intermediate `String`.
+#[derive(Debug, Clone, Copy)]
+pub struct PercentEncode<'a> {
+    src: &'a [u8],
+    set: &'a AsciiSet,
+    space_as_plus: bool,
+}
+
+impl<'a> PercentEncode<'a> {
+    /// Encoded string, borrowed from the input when nothing needs escaping
+    pub fn to_cow(&self) -> Cow<'a, str> {
+        if self.src.iter().any(|&b| self.set.should_encode(b)) {
+            Cow::Owned(self.to_string())
+        } else {
+            // only ascii bytes are left untouched, so this is valid utf-8
+            Cow::Borrowed(std::str::from_utf8(self.src).unwrap())
+        }
+    }
+}
+
+impl std::fmt::Display for PercentEncode<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rest = self.src;
+        while !rest.is_empty() {
+            let plain = rest
+                .iter()
+                .position(|&b| self.set.should_encode(b))
+                .unwrap_or(rest.len());
+            if plain > 0 {
+                f.write_str(std::str::from_utf8(&rest[..plain]).unwrap())?;
+                rest = &rest[plain..];
+                continue;
+            }
+
+            let b = rest[0];
+            if b == b' ' && self.space_as_plus {
+                f.write_str("+")?;
+            } else {
+                let escape = [
+                    b'%',
+                    HEX_TABLE_UPPER[(b >> 4) as usize],
+                    HEX_TABLE_UPPER[(b & 0x0F) as usize],
+                ];
+                f.write_str(std::str::from_utf8(&escape).unwrap())?;
+            }
+            rest = &rest[1..];
+        }
+        Ok(())
+    }
+}
+
+/// Percent encode bytes lazily
+///
+/// - @param src: raw bytes
+/// - @param set: ASCII bytes which must be encoded
+/// - @return: `Display` adapter writing the encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::percent;
+/// let encoded = percent::encode("name=café".as_bytes(), &percent::QUERY_COMPONENT);
+/// assert_eq!(encoded.to_string(), "name%3Dcaf%C3%A9");
+/// ```
+pub fn encode<'a>(src: &'a [u8], set: &'a AsciiSet) -> PercentEncode<'a> {
+    PercentEncode {
+        src,
+        set,
+        space_as_plus: false,
+    }
+}
+
+/// Percent encode bytes as `application/x-www-form-urlencoded`
+///
+/// - @param src: raw bytes
+/// - @return: `Display` adapter writing the encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::percent;
+/// assert_eq!(percent::encode_form(b"a b&c").to_string(), "a+b%26c");
+/// ```
+pub fn encode_form(src: &[u8]) -> PercentEncode<'_> {
+    PercentEncode {
+        src,
+        set: &FORM,
+        space_as_plus: true,
+    }
+}
+
+/// Percent encode bytes, without allocation when nothing needs escaping
+///
+/// - @param src: raw bytes
+/// - @param set: ASCII bytes which must be encoded
+/// - @return: encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use std::borrow::Cow;
+/// use connx::encoding::percent;
+/// let plain = percent::encode_to_cow(b"v1.2", &percent::PATH_SEGMENT);
+/// assert!(matches!(plain, Cow::Borrowed("v1.2")));
+/// let escaped = percent::encode_to_cow(b"a/b", &percent::PATH_SEGMENT);
+/// assert_eq!(escaped, "a%2Fb");
+/// ```
+pub fn encode_to_cow<'a>(src: &'a [u8], set: &'a AsciiSet) -> Cow<'a, str> {
+    encode(src, set).to_cow()
+}
+
+fn decode_with(src: &[u8], plus_as_space: bool) -> Result<Cow<'_, [u8]>, PercentError> {
+    let first = src
+        .iter()
+        .position(|&b| b == b'%' || (plus_as_space && b == b'+'));
+    let Some(first) = first else {
+        return Ok(Cow::Borrowed(src));
+    };
+
+    let mut dst = Vec::with_capacity(src.len());
+    dst.extend_from_slice(&src[..first]);
+    let mut src_idx = first;
+    while src_idx < src.len() {
+        match src[src_idx] {
+            b'%' => {
+                let escape = src
+                    .get(src_idx + 1..src_idx + 3)
+                    .ok_or(PercentError::InvalidEscape(src_idx))?;
+                let hi =
+                    from_hex_char(escape[0]).map_err(|_| PercentError::InvalidEscape(src_idx))?;
+                let lo =
+                    from_hex_char(escape[1]).map_err(|_| PercentError::InvalidEscape(src_idx))?;
+                dst.push(hi << 4 | lo);
+                src_idx += 3;
+            }
+            b'+' if plus_as_space => {
+                dst.push(b' ');
+                src_idx += 1;
+            }
+            b => {
+                dst.push(b);
+                src_idx += 1;
+            }
+        }
+    }
+    Ok(Cow::Owned(dst))
+}
+
+/// Decode percent encoded bytes, without allocation when nothing is escaped
+///
+/// - @param src: percent encoded bytes
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::percent::{self, PercentError};
+/// assert_eq!(percent::decode(b"a%2Fb+c").unwrap().as_ref(), b"a/b+c");
+/// assert_eq!(percent::decode(b"100%"), Err(PercentError::InvalidEscape(3)));
+/// ```
+pub fn decode(src: &[u8]) -> Result<Cow<'_, [u8]>, PercentError> {
+    decode_with(src, false)
+}
+
+/// Decode `application/x-www-form-urlencoded` bytes, `+` is decoded as space
+///
+/// - @param src: form encoded bytes
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::percent;
+/// assert_eq!(percent::decode_form(b"a+b%26c").unwrap().as_ref(), b"a b&c");
+/// ```
+pub fn decode_form(src: &[u8]) -> Result<Cow<'_, [u8]>, PercentError> {
+    decode_with(src, true)
+}
+
+/// Decode percent encoded string, the decoded bytes must be UTF-8
+///
+/// - @param src: percent encoded string
+/// - @return: decoded string if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::percent;
+/// assert_eq!(percent::decode_str("caf%C3%A9").unwrap(), "café");
+/// ```
+pub fn decode_str(src: &str) -> Result<Cow<'_, str>, PercentError> {
+    match decode(src.as_bytes())? {
+        Cow::Borrowed(_) => Ok(Cow::Borrowed(src)),
+        Cow::Owned(dst) => String::from_utf8(dst)
+            .map(Cow::Owned)
+            .map_err(|_| PercentError::InvalidUtf8),
+    }
+}
+
+#[cfg(test)]
+mod tests_percent {
+    use super::*;
+
+    fn test_base(data: &[u8], set: &AsciiSet, encoded_expect: &str) {
+        let encoded = encode(data, set).to_string();
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(encode_to_cow(data, set), encoded_expect);
+        let decoded = decode(encoded.as_bytes()).unwrap();
+        assert_eq!(decoded.as_ref(), data);
+    }
+
+    #[test]
+    fn test_sets() {
+        assert!(CONTROLS.contains(0x00));
+        assert!(CONTROLS.contains(0x1F));
+        assert!(CONTROLS.contains(0x7F));
+        assert!(!CONTROLS.contains(b' '));
+        assert!(!CONTROLS.contains(0x80));
+        for b in 0..=0x7Fu8 {
+            assert_eq!(NON_ALPHANUMERIC.contains(b), !b.is_ascii_alphanumeric());
+        }
+        assert_eq!(AsciiSet::EMPTY.add(b'a').remove(b'a'), AsciiSet::EMPTY);
+        assert_eq!(
+            AsciiSet::EMPTY.add(b'a').union(AsciiSet::EMPTY.add(b'b')),
+            AsciiSet::EMPTY.add_all(b"ab")
+        );
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(b"", &COMPONENT, "");
+        test_base(b"abc-._~XYZ019", &COMPONENT, "abc-._~XYZ019");
+        test_base(b" !\"#", &COMPONENT, "%20%21%22%23");
+        test_base(b"\x00\x7f\x80\xff", &CONTROLS, "%00%7F%80%FF");
+        test_base("é".as_bytes(), &AsciiSet::EMPTY, "%C3%A9");
+    }
+
+    #[test]
+    fn test_encode_components() {
+        let src = b"a b/c?d#e&f=g+h@i:j[k]";
+        test_base(src, &PATH_SEGMENT, "a%20b%2Fc%3Fd%23e&f=g+h@i:j%5Bk%5D");
+        test_base(
+            src,
+            &QUERY_COMPONENT,
+            "a%20b/c?d%23e%26f%3Dg%2Bh@i:j%5Bk%5D",
+        );
+        test_base(src, &USERINFO, "a%20b%2Fc%3Fd%23e&f=g+h%40i:j%5Bk%5D");
+        test_base(src, &FRAGMENT, "a%20b/c?d%23e&f=g+h@i:j%5Bk%5D");
+    }
+
+    #[test]
+    fn test_form() {
+        assert_eq!(encode_form(b"").to_string(), "");
+        assert_eq!(encode_form(b"a b+c*~").to_string(), "a+b%2Bc*%7E");
+        assert_eq!(decode_form(b"a+b%2Bc*%7E").unwrap().as_ref(), b"a b+c*~");
+    }
+
+    #[test]
+    fn test_cow() {
+        assert!(matches!(
+            encode_to_cow(b"plain", &COMPONENT),
+            Cow::Borrowed("plain")
+        ));
+        assert!(matches!(decode(b"plain"), Ok(Cow::Borrowed(b"plain"))));
+        assert!(matches!(decode_str("plain"), Ok(Cow::Borrowed("plain"))));
+        assert!(matches!(decode_form(b"a%20b"), Ok(Cow::Owned(_))));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(decode(b"%"), Err(PercentError::InvalidEscape(0)));
+        assert_eq!(decode(b"ab%4"), Err(PercentError::InvalidEscape(2)));
+        assert_eq!(decode(b"ab%4g"), Err(PercentError::InvalidEscape(2)));
+        assert_eq!(decode(b"%%41"), Err(PercentError::InvalidEscape(0)));
+        assert_eq!(decode_str("%FF"), Err(PercentError::InvalidUtf8));
+        assert_eq!(decode(b"%41%4a%4A").unwrap().as_ref(), b"AJJ");
+    }
+}
@@ -0,0 +1,425 @@
+//! RFC3492 implemented, punycode encoding of internationalized domain labels.
+//!
+//! Punycode is the bootstring encoding with the parameters for IDNA: the
+//! ASCII characters of a label are copied, followed by the other code points
+//! encoded as generalized variable-length integers in base 36.
+//! [`to_ascii`] and [`to_unicode`] apply it label by label to a domain name,
+//! with the `xn--` prefix.
+//!
+//! The labels are only lowercased, the full IDNA mapping tables (UTS #46)
+//! are not applied.
+//!
+//! # Examples
+//!
+//! Punycode encode basic usage:
+//!
+//! ```
+//! use connx::encoding::punycode;
+//! assert_eq!(punycode::encode("bücher").unwrap(), "bcher-kva");
+//! assert_eq!(punycode::decode("bcher-kva").unwrap(), "bücher");
+//! ```
+//!
+//! Domain name conversion:
+//!
+//! ```
+//! use connx::encoding::punycode;
+//! assert_eq!(punycode::to_ascii("Bücher.Example").unwrap(), "xn--bcher-kva.example");
+//! assert_eq!(punycode::to_unicode("xn--bcher-kva.example").unwrap(), "bücher.example");
+//! ```
+
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 0x80;
+const DELIMITER: char = '-';
+
+/// Prefix of punycode encoded domain labels
+pub const ACE_PREFIX: &str = "xn--";
+
+/// Maximum length of a domain label, in bytes
+pub const MAX_LABEL_LEN: usize = 63;
+
+/// Errors when punycode encode and decode
+#[derive(Debug, PartialEq)]
+pub enum PunycodeError {
+    /// Byte which is not a base 36 digit in the encoded part
+    InvalidDigit(u8),
+    /// Non-ASCII character before the last delimiter
+    NonBasic(char),
+    /// Input ends in the middle of a variable-length integer
+    Truncated,
+    /// Arithmetic overflow, the input is too long or malformed
+    Overflow,
+    /// Decoded value is not a valid non-ASCII code point
+    InvalidCodePoint(u32),
+    /// Domain name contains an empty label
+    EmptyLabel,
+    /// Encoded label exceeds [`MAX_LABEL_LEN`]
+    LabelTooLong,
+}
+
+impl std::fmt::Display for PunycodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidDigit(b) => write!(f, "encoding/punycode: invalid digit: {}", b),
+            Self::NonBasic(c) => write!(f, "encoding/punycode: non-basic character: {:?}", c),
+            Self::Truncated => write!(f, "encoding/punycode: truncated input"),
+            Self::Overflow => write!(f, "encoding/punycode: overflow"),
+            Self::InvalidCodePoint(n) => {
+                write!(f, "encoding/punycode: invalid code point: U+{:04X}", n)
+            }
+            Self::EmptyLabel => write!(f, "encoding/punycode: empty label"),
+            Self::LabelTooLong => write!(f, "encoding/punycode: label too long"),
+        }
+    }
+}
+
+impl std::error::Error for PunycodeError {}
+
+#[inline]
+fn threshold(k: u32, bias: u32) -> u32 {
+    k.saturating_sub(bias).clamp(TMIN, TMAX)
+}
+
+fn adapt(mut delta: u32, num_points: u32, first_time: bool) -> u32 {
+    delta /= if first_time { DAMP } else { 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + (BASE - TMIN + 1) * delta / (delta + SKEW)
+}
+
+#[inline]
+fn encode_digit(d: u32) -> char {
+    // 0..25 => a..z, 26..35 => 0..9
+    let d = d as u8;
+    if d < 26 {
+        (b'a' + d) as char
+    } else {
+        (b'0' + d - 26) as char
+    }
+}
+
+#[inline]
+fn decode_digit(b: u8) -> Result<u32, PunycodeError> {
+    match b {
+        b'a'..=b'z' => Ok(u32::from(b - b'a')),
+        b'A'..=b'Z' => Ok(u32::from(b - b'A')),
+        b'0'..=b'9' => Ok(u32::from(b - b'0') + 26),
+        _ => Err(PunycodeError::InvalidDigit(b)),
+    }
+}
+
+/// Encode a unicode string to punycode, without the `xn--` prefix
+///
+/// - @param src: unicode string
+/// - @return: punycode string if no overflow happened.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::punycode;
+/// assert_eq!(punycode::encode("münchen").unwrap(), "mnchen-3ya");
+/// assert_eq!(punycode::encode("abc").unwrap(), "abc-");
+/// ```
+pub fn encode(src: &str) -> Result<String, PunycodeError> {
+    let input: Vec<u32> = src.chars().map(u32::from).collect();
+    let mut output: String = src.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push(DELIMITER);
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic;
+    while (handled as usize) < input.len() {
+        // smallest code point not handled yet
+        let m = input.iter().copied().filter(|&c| c >= n).min().unwrap();
+        delta = (m - n)
+            .checked_mul(handled + 1)
+            .and_then(|d| delta.checked_add(d))
+            .ok_or(PunycodeError::Overflow)?;
+        n = m;
+
+        for &c in &input {
+            if c < n {
+                delta = delta.checked_add(1).ok_or(PunycodeError::Overflow)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1).ok_or(PunycodeError::Overflow)?;
+        n += 1;
+    }
+    Ok(output)
+}
+
+/// Decode punycode to a unicode string, without the `xn--` prefix
+///
+/// - @param src: punycode string
+/// - @return: unicode string if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::punycode::{self, PunycodeError};
+/// assert_eq!(punycode::decode("mnchen-3ya").unwrap(), "münchen");
+/// assert_eq!(punycode::decode("mnchen-3y!"), Err(PunycodeError::InvalidDigit(b'!')));
+/// ```
+pub fn decode(src: &str) -> Result<String, PunycodeError> {
+    let (basic, extended) = match src.rfind(DELIMITER) {
+        Some(idx) => (&src[..idx], &src[idx + 1..]),
+        None => ("", src),
+    };
+    if let Some(c) = basic.chars().find(|c| !c.is_ascii()) {
+        return Err(PunycodeError::NonBasic(c));
+    }
+    let mut output: Vec<char> = basic.chars().collect();
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut digits = extended.bytes();
+    while digits.len() > 0 {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = decode_digit(digits.next().ok_or(PunycodeError::Truncated)?)?;
+            i = digit
+                .checked_mul(w)
+                .and_then(|d| i.checked_add(d))
+                .ok_or(PunycodeError::Overflow)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t).ok_or(PunycodeError::Overflow)?;
+            k += BASE;
+        }
+
+        let len = output.len() as u32 + 1;
+        bias = adapt(i - old_i, len, old_i == 0);
+        n = n.checked_add(i / len).ok_or(PunycodeError::Overflow)?;
+        i %= len;
+        if n < INITIAL_N {
+            return Err(PunycodeError::InvalidCodePoint(n));
+        }
+        let c = char::from_u32(n).ok_or(PunycodeError::InvalidCodePoint(n))?;
+        output.insert(i as usize, c);
+        i += 1;
+    }
+    Ok(output.into_iter().collect())
+}
+
+/// Split a domain name on the full stop and its ideographic variants
+fn labels(domain: &str) -> impl Iterator<Item = &str> {
+    domain.split(['.', '\u{3002}', '\u{FF0E}', '\u{FF61}'])
+}
+
+/// Check a label, the last label may be empty (fully qualified name)
+fn check_label(label: &str, is_last: bool) -> Result<(), PunycodeError> {
+    if label.is_empty() && !is_last {
+        return Err(PunycodeError::EmptyLabel);
+    }
+    if label.len() > MAX_LABEL_LEN {
+        return Err(PunycodeError::LabelTooLong);
+    }
+    Ok(())
+}
+
+#[inline]
+fn strip_ace_prefix(label: &str) -> Option<&str> {
+    let prefix = label.get(..ACE_PREFIX.len())?;
+    if prefix.eq_ignore_ascii_case(ACE_PREFIX) {
+        Some(&label[ACE_PREFIX.len()..])
+    } else {
+        None
+    }
+}
+
+/// Convert a domain name to its ASCII form, lowercased, non-ASCII labels
+/// encoded with the `xn--` prefix
+///
+/// Labels already carrying the `xn--` prefix are checked to decode.
+///
+/// - @param domain: domain name, labels separated by `.`
+/// - @return: ASCII domain name if every label is valid.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::punycode::{self, PunycodeError};
+/// assert_eq!(punycode::to_ascii("例え.テスト").unwrap(), "xn--r8jz45g.xn--zckzah");
+/// assert_eq!(punycode::to_ascii("a..b"), Err(PunycodeError::EmptyLabel));
+/// ```
+pub fn to_ascii(domain: &str) -> Result<String, PunycodeError> {
+    let count = labels(domain).count();
+    let mut dst = String::with_capacity(domain.len() + 8);
+    for (idx, label) in labels(domain).enumerate() {
+        let label = label.to_lowercase();
+        let encoded = if label.is_ascii() {
+            if let Some(encoded) = strip_ace_prefix(&label) {
+                decode(encoded)?;
+            }
+            label
+        } else {
+            format!("{}{}", ACE_PREFIX, encode(&label)?)
+        };
+        check_label(&encoded, idx + 1 == count)?;
+        if idx > 0 {
+            dst.push('.');
+        }
+        dst.push_str(&encoded);
+    }
+    Ok(dst)
+}
+
+/// Convert a domain name to its unicode form, `xn--` labels are decoded
+///
+/// - @param domain: domain name, labels separated by `.`
+/// - @return: unicode domain name if every label is valid.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::punycode;
+/// assert_eq!(punycode::to_unicode("www.XN--R8JZ45G.jp").unwrap(), "www.例え.jp");
+/// ```
+pub fn to_unicode(domain: &str) -> Result<String, PunycodeError> {
+    let count = labels(domain).count();
+    let mut dst = String::with_capacity(domain.len());
+    for (idx, label) in labels(domain).enumerate() {
+        check_label(label, idx + 1 == count)?;
+        if idx > 0 {
+            dst.push('.');
+        }
+        match strip_ace_prefix(label) {
+            Some(encoded) => dst.push_str(&decode(encoded)?),
+            None => dst.push_str(label),
+        }
+    }
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests_punycode {
+    use super::*;
+
+    fn test_base(data: &str, encoded_expect: &str) {
+        let encoded = encode(data).unwrap();
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_rfc3492_samples() {
+        // (A) Arabic (Egyptian)
+        test_base(
+            "\u{0644}\u{064A}\u{0647}\u{0645}\u{0627}\u{0628}\u{062A}\u{0643}\u{0644}\
+             \u{0645}\u{0648}\u{0634}\u{0639}\u{0631}\u{0628}\u{064A}\u{061F}",
+            "egbpdaj6bu4bxfgehfvwxn",
+        );
+        // (B) Chinese (simplified)
+        test_base("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye");
+        // (J) Spanish
+        test_base(
+            "PorquénopuedensimplementehablarenEspañol",
+            "PorqunopuedensimplementehablarenEspaol-fmd56a",
+        );
+        // (L) 3<nen>B<gumi><kinpachi><sensei>
+        test_base("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b");
+        // (R) <sono><supiido><de>
+        test_base("そのスピードで", "d9juau41awczczp");
+        // (S) -> $1.00 <-
+        test_base("-> $1.00 <-", "-> $1.00 <--");
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base("", "");
+        test_base("a", "a-");
+        test_base("ü", "tda");
+        test_base("bücher", "bcher-kva");
+        test_base("😀", "e28h");
+    }
+
+    #[test]
+    fn test_decode_uppercase_digits() {
+        assert_eq!(decode("BCHER-KVA").unwrap(), "BüCHER");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode("ü-abc"), Err(PunycodeError::NonBasic('ü')));
+        assert_eq!(decode("bcher-kv"), Err(PunycodeError::Truncated));
+        assert_eq!(decode("a-z"), Err(PunycodeError::Truncated));
+        assert_eq!(decode("a-9999999999"), Err(PunycodeError::Overflow));
+        // surrogate code point U+D800
+        assert_eq!(decode("ib9b"), Err(PunycodeError::InvalidCodePoint(0xD800)));
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("").unwrap(), "");
+        assert_eq!(to_ascii("Example.COM").unwrap(), "example.com");
+        assert_eq!(
+            to_ascii("bücher.example.").unwrap(),
+            "xn--bcher-kva.example."
+        );
+        assert_eq!(to_ascii("MÜNCHEN。de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(to_ascii("xn--mnchen-3ya.de").unwrap(), "xn--mnchen-3ya.de");
+        assert_eq!(to_ascii(".de"), Err(PunycodeError::EmptyLabel));
+        assert_eq!(
+            to_ascii("xn--a-9999999999.de"),
+            Err(PunycodeError::Overflow)
+        );
+        assert_eq!(to_ascii(&"ü".repeat(60)), Err(PunycodeError::LabelTooLong));
+        assert_eq!(to_ascii(&"a".repeat(64)), Err(PunycodeError::LabelTooLong));
+    }
+
+    #[test]
+    fn test_to_unicode() {
+        assert_eq!(to_unicode("example.com").unwrap(), "example.com");
+        assert_eq!(
+            to_unicode("xn--bcher-kva.example.").unwrap(),
+            "bücher.example."
+        );
+        assert_eq!(to_unicode("a..b"), Err(PunycodeError::EmptyLabel));
+        assert_eq!(
+            to_unicode("xn--bcher-kv.example"),
+            Err(PunycodeError::Truncated)
+        );
+    }
+}
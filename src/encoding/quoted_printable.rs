@@ -0,0 +1,379 @@
+//! RFC2045 implemented, quoted-printable encoding.
+//!
+//! Printable ASCII is copied as is, every other byte is written as `=XX`
+//! with uppercase hex digits. Encoded lines are at most 76 characters long,
+//! longer lines are split with a soft line break (`=` followed by CRLF).
+//!
+//! # Examples
+//!
+//! Quoted-printable encode basic usage:
+//!
+//! ```
+//! use connx::encoding::quoted_printable::{self, Mode};
+//! let encoded = quoted_printable::encode_to_string("café\n".as_bytes(), Mode::Text);
+//! assert_eq!(encoded, "caf=C3=A9\r\n");
+//! ```
+//!
+//! Quoted-printable decode basic usage:
+//!
+//! ```
+//! use connx::encoding::quoted_printable;
+//! let decoded = quoted_printable::decode(b"caf=C3=A9=\r\n!").unwrap();
+//! assert_eq!(decoded, "café!".as_bytes());
+//! ```
+
+use crate::encoding::base16::{from_hex_char, HEX_TABLE_UPPER};
+
+/// Maximum encoded line length, without the CRLF
+pub const MAX_LINE_LEN: usize = 76;
+
+/// Errors when quoted-printable decode
+#[derive(Debug, PartialEq)]
+pub enum QuotedPrintableError {
+    /// Byte which is not allowed in quoted-printable text
+    InvalidByte(u8),
+    /// `=` at this offset is neither a soft line break nor `=XX`
+    InvalidEscape(usize),
+    /// Encoded line longer than [`MAX_LINE_LEN`]
+    LineTooLong,
+}
+
+impl std::fmt::Display for QuotedPrintableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidByte(b) => write!(f, "encoding/quoted_printable: invalid byte: {}", b),
+            Self::InvalidEscape(offset) => write!(
+                f,
+                "encoding/quoted_printable: invalid escape at offset {}",
+                offset
+            ),
+            Self::LineTooLong => write!(f, "encoding/quoted_printable: line too long"),
+        }
+    }
+}
+
+impl std::error::Error for QuotedPrintableError {}
+
+/// How line breaks of the raw data are handled when encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Raw data is text, CRLF and bare LF are written as CRLF hard line breaks
+    Text,
+    /// Raw data is binary, CR and LF are escaped as `=0D` and `=0A`
+    Binary,
+}
+
+/// Length of the line break starting at `src[idx]` in text mode, 0 if none
+#[inline]
+fn line_break_len(src: &[u8], idx: usize) -> usize {
+    match src[idx] {
+        b'\n' => 1,
+        b'\r' if src.get(idx + 1) == Some(&b'\n') => 2,
+        _ => 0,
+    }
+}
+
+/// Encode bytes to quoted-printable bytes
+///
+/// - @param src: raw bytes
+/// - @param mode: text or binary line break handling
+/// - @return: quoted-printable encoded bytes
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::quoted_printable::{self, Mode};
+/// assert_eq!(quoted_printable::encode(b"a=b \r\n", Mode::Text), b"a=3Db=20\r\n");
+/// assert_eq!(quoted_printable::encode(b"a=b \r\n", Mode::Binary), b"a=3Db =0D=0A");
+/// ```
+pub fn encode(src: &[u8], mode: Mode) -> Vec<u8> {
+    let mut dst = Vec::with_capacity(src.len() + src.len() / 2);
+    let mut line_len = 0;
+    let mut src_idx = 0;
+    while src_idx < src.len() {
+        if mode == Mode::Text {
+            let n = line_break_len(src, src_idx);
+            if n > 0 {
+                dst.extend_from_slice(b"\r\n");
+                line_len = 0;
+                src_idx += n;
+                continue;
+            }
+        }
+
+        // whitespace is only safe when something follows it on the same line
+        let next = src_idx + 1;
+        let at_line_end =
+            next == src.len() || (mode == Mode::Text && line_break_len(src, next) > 0);
+        let b = src[src_idx];
+        let literal = match b {
+            b' ' | b'\t' => !at_line_end,
+            b'=' => false,
+            33..=126 => true,
+            _ => false,
+        };
+        let width = if literal { 1 } else { 3 };
+
+        // keep one column for the `=` of the soft line break, unless this is
+        // the last character of the line
+        let limit = if at_line_end {
+            MAX_LINE_LEN
+        } else {
+            MAX_LINE_LEN - 1
+        };
+        if line_len + width > limit {
+            dst.extend_from_slice(b"=\r\n");
+            line_len = 0;
+        }
+
+        if literal {
+            dst.push(b);
+        } else {
+            dst.push(b'=');
+            dst.push(HEX_TABLE_UPPER[(b >> 4) as usize]);
+            dst.push(HEX_TABLE_UPPER[(b & 0x0F) as usize]);
+        }
+        line_len += width;
+        src_idx += 1;
+    }
+    dst
+}
+
+/// Encode bytes to quoted-printable string
+///
+/// - @param src: raw bytes
+/// - @param mode: text or binary line break handling
+/// - @return: quoted-printable encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::quoted_printable::{self, Mode};
+/// let encoded = quoted_printable::encode_to_string(b"hello\tworld", Mode::Text);
+/// assert_eq!(encoded, "hello\tworld");
+/// ```
+pub fn encode_to_string(src: &[u8], mode: Mode) -> String {
+    String::from_utf8(encode(src, mode)).unwrap()
+}
+
+#[inline]
+fn from_hex_upper(b: u8) -> Option<u8> {
+    if b.is_ascii_lowercase() {
+        return None;
+    }
+    from_hex_char(b).ok()
+}
+
+fn decode_with(src: &[u8], lenient: bool) -> Result<Vec<u8>, QuotedPrintableError> {
+    let mut dst = Vec::with_capacity(src.len());
+    let mut pos = 0;
+    while pos < src.len() {
+        // split off one line, without its line break
+        let (line_end, next) = match src[pos..].iter().position(|&b| b == b'\n') {
+            Some(n) if n > 0 && src[pos + n - 1] == b'\r' => (pos + n - 1, pos + n + 1),
+            Some(n) if lenient => (pos + n, pos + n + 1),
+            Some(_) => return Err(QuotedPrintableError::InvalidByte(b'\n')),
+            None => (src.len(), src.len()),
+        };
+        if !lenient && line_end - pos > MAX_LINE_LEN {
+            return Err(QuotedPrintableError::LineTooLong);
+        }
+
+        // trailing whitespace is transport padding, it must be deleted
+        let mut line = &src[pos..line_end];
+        while let [rest @ .., b' ' | b'\t'] = line {
+            line = rest;
+        }
+
+        let mut soft_break = false;
+        let mut i = 0;
+        while i < line.len() {
+            let b = line[i];
+            if b == b'=' {
+                if i + 1 == line.len() {
+                    soft_break = true;
+                    break;
+                }
+                let escaped = match line.get(i + 1..i + 3) {
+                    Some(&[hi, lo]) if lenient => {
+                        from_hex_char(hi).ok().zip(from_hex_char(lo).ok())
+                    }
+                    Some(&[hi, lo]) => from_hex_upper(hi).zip(from_hex_upper(lo)),
+                    _ => None,
+                };
+                match escaped {
+                    Some((hi, lo)) => {
+                        dst.push(hi << 4 | lo);
+                        i += 3;
+                    }
+                    None if lenient => {
+                        dst.push(b'=');
+                        i += 1;
+                    }
+                    None => return Err(QuotedPrintableError::InvalidEscape(pos + i)),
+                }
+            } else if lenient || b == b'\t' || (32..=126).contains(&b) {
+                dst.push(b);
+                i += 1;
+            } else {
+                return Err(QuotedPrintableError::InvalidByte(b));
+            }
+        }
+
+        if !soft_break {
+            dst.extend_from_slice(&src[line_end..next]);
+        }
+        pos = next;
+    }
+    Ok(dst)
+}
+
+/// Decode quoted-printable bytes to raw bytes, strictly following RFC2045
+///
+/// Hard line breaks must be CRLF and are kept as CRLF, escapes must use
+/// uppercase hex digits and lines must not exceed [`MAX_LINE_LEN`].
+///
+/// - @param src: quoted-printable bytes
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::quoted_printable::{self, QuotedPrintableError};
+/// assert_eq!(quoted_printable::decode(b"a=3Db=20\r\n").unwrap(), b"a=b \r\n");
+/// assert_eq!(quoted_printable::decode(b"a=3db"), Err(QuotedPrintableError::InvalidEscape(1)));
+/// ```
+pub fn decode(src: &[u8]) -> Result<Vec<u8>, QuotedPrintableError> {
+    decode_with(src, false)
+}
+
+/// Decode quoted-printable bytes to raw bytes, accepting what real-world
+/// mail contains
+///
+/// Bare LF line breaks, lowercase hex digits, overlong lines and 8bit bytes
+/// are accepted, a malformed `=` escape is kept literally. Line breaks are
+/// copied as they are found.
+///
+/// - @param src: quoted-printable bytes
+/// - @return: decoded raw bytes
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::quoted_printable;
+/// assert_eq!(quoted_printable::decode_lenient(b"a=3db=\nc = d\n"), b"a=bc = d\n");
+/// ```
+pub fn decode_lenient(src: &[u8]) -> Vec<u8> {
+    decode_with(src, true).unwrap()
+}
+
+#[cfg(test)]
+mod tests_quoted_printable {
+    use super::*;
+
+    fn test_base(data: &[u8], mode: Mode, encoded_expect: &str) {
+        let encoded = encode_to_string(data, mode);
+        assert_eq!(encoded, encoded_expect);
+        for line in encoded.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_LEN);
+        }
+        let decoded = decode(encoded.as_bytes()).unwrap();
+        assert_eq!(decode_lenient(encoded.as_bytes()), decoded);
+        if mode == Mode::Binary {
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(b"", Mode::Text, "");
+        test_base(b"hello world", Mode::Text, "hello world");
+        test_base(b"a=b", Mode::Text, "a=3Db");
+        test_base(b"\x00\x7f\x80\xff", Mode::Binary, "=00=7F=80=FF");
+        test_base("Grüße".as_bytes(), Mode::Binary, "Gr=C3=BC=C3=9Fe");
+    }
+
+    #[test]
+    fn test_trailing_whitespace() {
+        test_base(b"a ", Mode::Text, "a=20");
+        test_base(b"a\t", Mode::Text, "a=09");
+        test_base(b"a \r\nb", Mode::Text, "a=20\r\nb");
+        test_base(b"a \nb", Mode::Text, "a=20\r\nb");
+        test_base(b"a \r\nb", Mode::Binary, "a =0D=0Ab");
+    }
+
+    #[test]
+    fn test_text_line_breaks() {
+        assert_eq!(encode(b"a\nb\r\nc\rd", Mode::Text), b"a\r\nb\r\nc=0Dd");
+        assert_eq!(decode(b"a\r\nb\r\nc=0Dd").unwrap(), b"a\r\nb\r\nc\rd");
+    }
+
+    #[test]
+    fn test_soft_line_breaks() {
+        let data = [b'x'; 200];
+        let encoded = encode_to_string(&data, Mode::Text);
+        let lines: Vec<&str> = encoded.split("\r\n").collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0].len(), 76);
+        assert!(lines[0].ends_with('='));
+        assert_eq!(lines[1].len(), 76);
+        assert_eq!(lines[2].len(), 50);
+        assert_eq!(decode(encoded.as_bytes()).unwrap(), data);
+
+        // exactly 76 characters fit when no soft break is needed
+        let data = [b'x'; 76];
+        assert_eq!(encode(&data, Mode::Text), data);
+
+        // escapes are never split across lines
+        let mut data = vec![b'x'; 74];
+        data.push(0xff);
+        data.push(b'y');
+        let encoded = encode_to_string(&data, Mode::Text);
+        assert_eq!(encoded, format!("{}=\r\n=FFy", "x".repeat(74)));
+        assert_eq!(decode(encoded.as_bytes()).unwrap(), data);
+    }
+
+    #[test]
+    fn test_decode_strict_errors() {
+        assert_eq!(decode(b"a=3"), Err(QuotedPrintableError::InvalidEscape(1)));
+        assert_eq!(decode(b"a=GG"), Err(QuotedPrintableError::InvalidEscape(1)));
+        assert_eq!(decode(b"a=ff"), Err(QuotedPrintableError::InvalidEscape(1)));
+        assert_eq!(
+            decode(b"a\nb"),
+            Err(QuotedPrintableError::InvalidByte(b'\n'))
+        );
+        assert_eq!(
+            decode(b"a\rb"),
+            Err(QuotedPrintableError::InvalidByte(b'\r'))
+        );
+        assert_eq!(
+            decode(b"caf\xc3\xa9"),
+            Err(QuotedPrintableError::InvalidByte(0xc3))
+        );
+        assert_eq!(decode(&[b'x'; 77]), Err(QuotedPrintableError::LineTooLong));
+    }
+
+    #[test]
+    fn test_decode_transport_padding() {
+        assert_eq!(decode(b"a  \r\nb").unwrap(), b"a\r\nb");
+        assert_eq!(decode(b"a= \t\r\nb").unwrap(), b"ab");
+        assert_eq!(decode(b"a=").unwrap(), b"a");
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        assert_eq!(decode_lenient(b"a=3d=C3=a9"), b"a=\xc3\xa9");
+        assert_eq!(decode_lenient(b"50% = half=\nmore\n"), b"50% = halfmore\n");
+        assert_eq!(decode_lenient(b"a=Gb=4"), b"a=Gb=4");
+        assert_eq!(decode_lenient("café".as_bytes()), "café".as_bytes());
+        assert_eq!(decode_lenient(&[b'x'; 100]), [b'x'; 100]);
+    }
+}
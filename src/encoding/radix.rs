@@ -0,0 +1,639 @@
+//! Arbitrary radix encoding, base36, base62 or any custom alphabet.
+//!
+//! The input bytes are treated as one big-endian unsigned integer and
+//! rewritten in the target radix (2 to 256). Every leading zero byte is kept
+//! as one leading zero digit (the first character of the alphabet), so the
+//! encoding round-trips exactly, like base58 does.
+//!
+//! # Examples
+//!
+//! Base62 encode basic usage:
+//!
+//! ```
+//! use connx::encoding::radix;
+//! let encoded = radix::encode_to_string(b"hello", &radix::BASE62);
+//! assert_eq!(encoded, "7tQLFHz");
+//! ```
+//!
+//! Base36 decode basic usage, base36 is case-insensitive:
+//!
+//! ```
+//! use connx::encoding::radix;
+//! assert_eq!(radix::decode_str("5PZCSZU7", &radix::BASE36), Ok(b"hello".to_vec()));
+//! assert_eq!(radix::decode_str("5pzcszu7", &radix::BASE36), Ok(b"hello".to_vec()));
+//! ```
+
+/// Marks a byte which is not part of the alphabet in the decoding map
+const INVALID: u16 = 0xFFFF;
+
+/// Inputs longer than this use the chunked conversion
+const CHUNKED_THRESHOLD: usize = 32;
+
+/// Errors when radix encode and decode
+#[derive(Debug, PartialEq)]
+pub enum RadixError {
+    InvalidByte(u8),
+}
+
+impl std::fmt::Display for RadixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidByte(b) => write!(f, "encoding/radix: invalid byte: {}", b),
+        }
+    }
+}
+
+impl std::error::Error for RadixError {}
+
+/// Digits of a radix encoding, together with the reverse lookup table.
+///
+/// The radix is the number of characters, the first character is the zero
+/// digit.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    encode_map: [u8; 256],
+    decode_map: [u16; 256],
+    radix: usize,
+}
+
+impl Alphabet {
+    /// Build an alphabet from its digits
+    ///
+    /// - @param chars: digits in ascending order, 2 to 256 distinct bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chars` is shorter than 2 bytes, longer than 256 bytes or
+    /// contains the same byte twice.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::radix::{self, Alphabet};
+    /// let octal = Alphabet::new(b"01234567");
+    /// assert_eq!(octal.radix(), 8);
+    /// assert_eq!(radix::encode_to_string(&[0x01, 0xff], &octal), "777");
+    /// ```
+    pub const fn new(chars: &[u8]) -> Self {
+        assert!(
+            chars.len() >= 2 && chars.len() <= 256,
+            "encoding/radix: alphabet must have 2 to 256 characters"
+        );
+        let mut encode_map = [0u8; 256];
+        let mut decode_map = [INVALID; 256];
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i] as usize;
+            assert!(
+                decode_map[c] == INVALID,
+                "encoding/radix: duplicated character in alphabet"
+            );
+            encode_map[i] = chars[i];
+            decode_map[c] = i as u16;
+            i += 1;
+        }
+        Self {
+            encode_map,
+            decode_map,
+            radix: chars.len(),
+        }
+    }
+
+    /// Also accept the other ASCII case of every letter when decoding.
+    ///
+    /// Letters whose other case is a digit of its own are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::radix::{self, Alphabet};
+    /// let hex = Alphabet::new(b"0123456789abcdef").ignore_ascii_case();
+    /// assert_eq!(radix::decode_str("1FF", &hex), Ok(vec![0x01, 0xff]));
+    /// ```
+    pub const fn ignore_ascii_case(mut self) -> Self {
+        let mut i = 0;
+        while i < self.radix {
+            let c = self.encode_map[i];
+            let other = if c.is_ascii_lowercase() {
+                c.to_ascii_uppercase()
+            } else {
+                c.to_ascii_lowercase()
+            };
+            if self.decode_map[other as usize] == INVALID {
+                self.decode_map[other as usize] = i as u16;
+            }
+            i += 1;
+        }
+        self
+    }
+
+    /// Number of digits in the alphabet
+    #[inline]
+    pub const fn radix(&self) -> usize {
+        self.radix
+    }
+
+    /// Digits of the alphabet, in ascending order
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.encode_map[..self.radix]
+    }
+
+    /// Whether every digit is ASCII, which the string functions require
+    #[inline]
+    pub fn is_ascii(&self) -> bool {
+        self.as_bytes().is_ascii()
+    }
+
+    #[inline]
+    fn digit_of(&self, b: u8) -> Result<u8, RadixError> {
+        let out = self.decode_map[b as usize];
+        if out == INVALID {
+            return Err(RadixError::InvalidByte(b));
+        }
+        Ok(out as u8)
+    }
+}
+
+/// Base36 alphabet, lowercase when encoding and case-insensitive when decoding
+pub const BASE36: Alphabet =
+    Alphabet::new(b"0123456789abcdefghijklmnopqrstuvwxyz").ignore_ascii_case();
+
+/// Base58 alphabet used by Bitcoin, without `0`, `O`, `I` and `l`
+pub const BASE58: Alphabet =
+    Alphabet::new(b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz");
+
+/// Base62 alphabet, in ASCII order
+pub const BASE62: Alphabet =
+    Alphabet::new(b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz");
+
+/// Calculate the maximum radix encoded string length
+///
+/// - @param n: raw bytes length
+/// - @param radix: 2 to 256
+/// - @return: upper bound of the encoded string length
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// assert_eq!(radix::encode_len(0, 62), 0);
+/// assert_eq!(radix::encode_len(16, 2), 128);
+/// assert_eq!(radix::encode_len(16, 36), 26);
+/// assert_eq!(radix::encode_len(16, 256), 16);
+/// ```
+#[inline]
+pub fn encode_len(n: usize, radix: usize) -> usize {
+    (n * 8).div_ceil(radix.ilog2() as usize)
+}
+
+/// Calculate the maximum radix decoded data length
+///
+/// - @param n: radix encoded string length
+/// - @return: upper bound of the decoded raw bytes length, (n)
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// assert_eq!(radix::decode_len(0), 0);
+/// assert_eq!(radix::decode_len(7), 7);
+/// ```
+#[inline]
+pub fn decode_len(n: usize) -> usize {
+    n
+}
+
+/// Largest power of radix which fits in a u32, returns (radix^k, k)
+fn big_radix(radix: u64) -> (u64, usize) {
+    let mut big = radix;
+    let mut k = 1;
+    while big * radix <= u64::from(u32::MAX) {
+        big *= radix;
+        k += 1;
+    }
+    (big, k)
+}
+
+/// Convert big-endian bytes to little-endian digits, one byte at a time.
+/// O(n^2), kept as the reference implementation.
+fn to_digits_simple(src: &[u8], radix: u32) -> Vec<u8> {
+    let mut digits: Vec<u8> = Vec::with_capacity(encode_len(src.len(), radix as usize));
+    for &b in src {
+        let mut carry = u32::from(b);
+        for d in digits.iter_mut() {
+            carry += u32::from(*d) << 8;
+            *d = (carry % radix) as u8;
+            carry /= radix;
+        }
+        while carry > 0 {
+            digits.push((carry % radix) as u8);
+            carry /= radix;
+        }
+    }
+    digits
+}
+
+/// Convert big-endian bytes to little-endian digits, working on limbs of
+/// radix^k and consuming four input bytes per pass.
+fn to_digits_chunked(src: &[u8], radix: u32) -> Vec<u8> {
+    let radix = u64::from(radix);
+    let (big, k) = big_radix(radix);
+
+    // little-endian limbs in base radix^k
+    let mut limbs: Vec<u32> = Vec::with_capacity(src.len() / 3 + 1);
+    for chunk in src.chunks(4) {
+        let mul = 1u64 << (8 * chunk.len());
+        let mut carry = chunk.iter().fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+        for limb in limbs.iter_mut() {
+            let acc = u64::from(*limb) * mul + carry;
+            *limb = (acc % big) as u32;
+            carry = acc / big;
+        }
+        while carry > 0 {
+            limbs.push((carry % big) as u32);
+            carry /= big;
+        }
+    }
+
+    let mut digits: Vec<u8> = Vec::with_capacity(limbs.len() * k);
+    for limb in limbs {
+        let mut val = u64::from(limb);
+        for _ in 0..k {
+            digits.push((val % radix) as u8);
+            val /= radix;
+        }
+    }
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+/// Convert big-endian digits to little-endian bytes, one digit at a time.
+/// O(n^2), kept as the reference implementation.
+fn to_bytes_simple(digits: &[u8], radix: u32) -> Vec<u8> {
+    let mut bytes: Vec<u8> = Vec::with_capacity(digits.len());
+    for &d in digits {
+        let mut carry = u32::from(d);
+        for b in bytes.iter_mut() {
+            carry += u32::from(*b) * radix;
+            *b = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    bytes
+}
+
+/// Convert big-endian digits to little-endian bytes, working on 32 bit limbs
+/// and consuming k digits per pass.
+fn to_bytes_chunked(digits: &[u8], radix: u32) -> Vec<u8> {
+    let radix = u64::from(radix);
+    let (_, k) = big_radix(radix);
+
+    // little-endian limbs in base 2^32
+    let mut limbs: Vec<u32> = Vec::with_capacity(digits.len() / 4 + 1);
+    for chunk in digits.chunks(k) {
+        let mul = radix.pow(chunk.len() as u32);
+        let mut carry = chunk
+            .iter()
+            .fold(0u64, |acc, &d| acc * radix + u64::from(d));
+        for limb in limbs.iter_mut() {
+            let acc = u64::from(*limb) * mul + carry;
+            *limb = acc as u32;
+            carry = acc >> 32;
+        }
+        while carry > 0 {
+            limbs.push(carry as u32);
+            carry >>= 32;
+        }
+    }
+
+    let mut bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+    while bytes.last() == Some(&0) {
+        bytes.pop();
+    }
+    bytes
+}
+
+/// Encode bytes to radix bytes
+///
+/// - @param dst: encoded radix bytes, at least `encode_len(src.len(), alphabet.radix())`
+/// - @param src: raw bytes
+/// - @param alphabet: radix alphabet
+/// - @return: encoded byte size
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// let src = b"\x00\x00hello";
+/// let mut dst = vec![0; radix::encode_len(src.len(), radix::BASE62.radix())];
+/// let dst_size = radix::encode(&mut dst, src, &radix::BASE62);
+/// assert_eq!(&dst[..dst_size], b"007tQLFHz");
+/// ```
+pub fn encode(dst: &mut [u8], src: &[u8], alphabet: &Alphabet) -> usize {
+    let zeros = src.iter().take_while(|&&b| b == 0).count();
+    let src = &src[zeros..];
+    let radix = alphabet.radix as u32;
+    let digits = if src.len() > CHUNKED_THRESHOLD {
+        to_digits_chunked(src, radix)
+    } else {
+        to_digits_simple(src, radix)
+    };
+
+    dst[..zeros].fill(alphabet.encode_map[0]);
+    for (i, &d) in digits.iter().rev().enumerate() {
+        dst[zeros + i] = alphabet.encode_map[d as usize];
+    }
+    zeros + digits.len()
+}
+
+/// Decode radix bytes to raw bytes
+///
+/// - @param dst: decoded raw bytes, at least `decode_len(src.len())`
+/// - @param src: radix bytes
+/// - @param alphabet: radix alphabet
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// let src = b"007tQLFHz";
+/// let mut dst = vec![0; radix::decode_len(src.len())];
+/// let dst_size = radix::decode(&mut dst, src, &radix::BASE62).unwrap();
+/// assert_eq!(&dst[..dst_size], b"\x00\x00hello");
+/// ```
+pub fn decode(dst: &mut [u8], src: &[u8], alphabet: &Alphabet) -> Result<usize, RadixError> {
+    let digits = src
+        .iter()
+        .map(|&c| alphabet.digit_of(c))
+        .collect::<Result<Vec<u8>, RadixError>>()?;
+    let zeros = digits.iter().take_while(|&&d| d == 0).count();
+    let digits = &digits[zeros..];
+    let radix = alphabet.radix as u32;
+    let bytes = if digits.len() > CHUNKED_THRESHOLD {
+        to_bytes_chunked(digits, radix)
+    } else {
+        to_bytes_simple(digits, radix)
+    };
+
+    dst[..zeros].fill(0);
+    for (i, &b) in bytes.iter().rev().enumerate() {
+        dst[zeros + i] = b;
+    }
+    Ok(zeros + bytes.len())
+}
+
+/// Encode bytes to radix bytes
+///
+/// Unlike [`encode_to_string`], any alphabet is accepted.
+///
+/// - @param src: raw bytes
+/// - @param alphabet: radix alphabet
+/// - @return: encoded radix bytes
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix::{self, Alphabet};
+/// let chars: Vec<u8> = (0x80..=0xff).collect();
+/// let high = Alphabet::new(&chars);
+/// assert_eq!(radix::encode_bytes(&[0x01, 0x02], &high), [0x82, 0x82]);
+/// ```
+pub fn encode_bytes(src: &[u8], alphabet: &Alphabet) -> Vec<u8> {
+    let mut dst = vec![0u8; encode_len(src.len(), alphabet.radix)];
+    let dst_size = encode(&mut dst, src, alphabet);
+    dst.truncate(dst_size);
+    dst
+}
+
+/// Encode bytes to radix string
+///
+/// - @param src: raw bytes
+/// - @param alphabet: radix alphabet
+/// - @return: encoded radix string
+///
+/// # Panics
+///
+/// Panics if the alphabet has non-ASCII digits, use [`encode_bytes`] for
+/// those.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// let id: u128 = 0x0123_4567_89ab_cdef_0123_4567_89ab_cdef;
+/// let encoded = radix::encode_to_string(&id.to_be_bytes(), &radix::BASE36);
+/// assert_eq!(encoded, "2fapl4n1azs5kkwzrxa98bn3");
+/// ```
+pub fn encode_to_string(src: &[u8], alphabet: &Alphabet) -> String {
+    assert!(alphabet.is_ascii(), "encoding/radix: alphabet is not ASCII");
+    String::from_utf8(encode_bytes(src, alphabet)).unwrap()
+}
+
+/// Decode radix bytes to raw bytes
+///
+/// - @param src: radix bytes
+/// - @param alphabet: radix alphabet
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix::{self, Alphabet};
+/// let chars: Vec<u8> = (0x80..=0xff).collect();
+/// let high = Alphabet::new(&chars);
+/// assert_eq!(radix::decode_bytes(&[0x82, 0x82], &high), Ok(vec![0x01, 0x02]));
+/// ```
+pub fn decode_bytes(src: &[u8], alphabet: &Alphabet) -> Result<Vec<u8>, RadixError> {
+    let mut dst = vec![0u8; decode_len(src.len())];
+    let dst_size = decode(&mut dst, src, alphabet)?;
+    dst.truncate(dst_size);
+    Ok(dst)
+}
+
+/// Decode radix string to raw bytes
+///
+/// - @param src: radix string
+/// - @param alphabet: radix alphabet
+/// - @return: decoded raw bytes if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::radix;
+/// let dst = radix::decode_str("7tQLFHz", &radix::BASE62).unwrap();
+/// assert_eq!(dst, b"hello");
+/// ```
+pub fn decode_str(src: &str, alphabet: &Alphabet) -> Result<Vec<u8>, RadixError> {
+    decode_bytes(src.as_bytes(), alphabet)
+}
+
+#[cfg(test)]
+mod tests_radix {
+    use super::*;
+
+    fn test_base(data: &[u8], encoded_expect: &str, alphabet: &Alphabet) {
+        let encoded = encode_to_string(data, alphabet);
+        assert_eq!(encoded, encoded_expect);
+        let decoded = decode_str(&encoded, alphabet).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    /// Deterministic pseudo random bytes for the chunked path tests
+    fn sample(len: usize, seed: u32) -> Vec<u8> {
+        let mut x = seed.wrapping_mul(2654435761).wrapping_add(1);
+        (0..len)
+            .map(|_| {
+                x ^= x << 13;
+                x ^= x >> 17;
+                x ^= x << 5;
+                (x >> 24) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_base62_01() {
+        test_base(b"", "", &BASE62);
+        test_base(b"\x00", "0", &BASE62);
+        test_base(b"\x00\x00", "00", &BASE62);
+        test_base(b"\x01", "1", &BASE62);
+        test_base(b"\x3d", "z", &BASE62);
+        test_base(b"\x3e", "10", &BASE62);
+        test_base(b"\xff", "47", &BASE62);
+        test_base(b"hello", "7tQLFHz", &BASE62);
+        test_base(b"\x00hello", "07tQLFHz", &BASE62);
+    }
+
+    #[test]
+    fn test_base58_01() {
+        test_base(b"", "", &BASE58);
+        test_base(b"\x00\x00yes", "11hmzS", &BASE58);
+        test_base(b"hello world", "StV1DL6CwTryKyV", &BASE58);
+        assert_eq!(
+            decode_str("0OIl", &BASE58),
+            Err(RadixError::InvalidByte(b'0'))
+        );
+    }
+
+    #[test]
+    fn test_base36_01() {
+        test_base(b"", "", &BASE36);
+        test_base(b"\x00", "0", &BASE36);
+        test_base(b"\x23", "z", &BASE36);
+        test_base(b"\x24", "10", &BASE36);
+        test_base(b"hello", "5pzcszu7", &BASE36);
+        test_base(&[0xff; 16], "f5lxx1zz5pnorynqglhzmsp33", &BASE36);
+    }
+
+    #[test]
+    fn test_base36_ignore_case() {
+        assert_eq!(decode_str("5PZCSZU7", &BASE36), Ok(b"hello".to_vec()));
+        assert_eq!(decode_str("5pZcSzU7", &BASE36), Ok(b"hello".to_vec()));
+        assert_eq!(
+            decode_str("7TQLFHZ", &BASE62),
+            Ok(vec![0x62, 0xd9, 0xac, 0x67, 0x95])
+        );
+    }
+
+    #[test]
+    fn test_radix_2_and_256() {
+        let binary = Alphabet::new(b"01");
+        test_base(b"\x00\x05", "0101", &binary);
+        test_base(b"\x80", "10000000", &binary);
+
+        let chars: Vec<u8> = (0..=255).collect();
+        let identity = Alphabet::new(&chars);
+        assert_eq!(identity.radix(), 256);
+        let data = sample(100, 7);
+        let mut dst = vec![0; encode_len(data.len(), 256)];
+        let dst_size = encode(&mut dst, &data, &identity);
+        assert_eq!(&dst[..dst_size], &data[..]);
+        assert!(!identity.is_ascii());
+        assert_eq!(
+            decode_bytes(&encode_bytes(&data, &identity), &identity),
+            Ok(data)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "encoding/radix: alphabet is not ASCII")]
+    fn test_non_ascii_string() {
+        let chars: Vec<u8> = (0x80..=0xff).collect();
+        encode_to_string(b"hello", &Alphabet::new(&chars));
+    }
+
+    #[test]
+    fn test_invalid_byte() {
+        assert_eq!(
+            decode_str("7tQ-FHz", &BASE62),
+            Err(RadixError::InvalidByte(b'-'))
+        );
+        assert_eq!(
+            decode_str("hello!", &BASE36),
+            Err(RadixError::InvalidByte(b'!'))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_duplicated_alphabet() {
+        Alphabet::new(b"0120");
+    }
+
+    #[test]
+    fn test_chunked_same_as_simple() {
+        for radix in [2u32, 3, 10, 36, 58, 62, 85, 255, 256] {
+            for len in [1, 2, 3, 4, 5, 31, 33, 64, 100, 257] {
+                let data = sample(len, radix + len as u32);
+                let digits = to_digits_simple(&data, radix);
+                assert_eq!(digits, to_digits_chunked(&data, radix), "radix {radix}");
+
+                let mut big_endian = digits.clone();
+                big_endian.reverse();
+                let bytes = to_bytes_simple(&big_endian, radix);
+                assert_eq!(bytes, to_bytes_chunked(&big_endian, radix), "radix {radix}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_roundtrip() {
+        for len in [33, 100, 1000] {
+            let mut data = sample(len, len as u32);
+            data[0] = 0;
+            data[1] = 0;
+            let encoded = encode_to_string(&data, &BASE62);
+            assert!(encoded.len() <= encode_len(data.len(), 62));
+            assert!(encoded.starts_with("00"));
+            assert_eq!(decode_str(&encoded, &BASE62).unwrap(), data);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Shared serde glue of the `serde` modules of the text codecs.
+//!
+//! Human-readable formats get the encoded string, binary formats get the raw
+//! bytes. Deserializing accepts a string, bytes or a sequence of bytes, so
+//! formats without native byte support still work.
+
+use ::serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use std::marker::PhantomData;
+
+/// Text encoding of bytes, implemented by a marker type per codec
+pub(crate) trait TextCodec {
+    /// What the deserializer expects, for error messages
+    const EXPECTING: &'static str;
+
+    fn encode(src: &[u8]) -> String;
+
+    fn decode(src: &str) -> Result<Vec<u8>, String>;
+}
+
+pub(crate) fn serialize<C: TextCodec, S: Serializer>(
+    bytes: &[u8],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&C::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+struct BytesVisitor<C>(PhantomData<C>);
+
+impl<'de, C: TextCodec> Visitor<'de> for BytesVisitor<C> {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(C::EXPECTING)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+        // the codecs reject empty input, but an empty string is empty data
+        if v.is_empty() {
+            return Ok(Vec::new());
+        }
+        C::decode(v).map_err(E::custom)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn deserialize<'de, C: TextCodec, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<u8>, D::Error> {
+    let visitor = BytesVisitor::<C>(PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_byte_buf(visitor)
+    }
+}
+
+/// Convert decoded bytes to the field type, `Vec<u8>` or `[u8; N]`
+pub(crate) fn convert<T: TryFrom<Vec<u8>>, E: de::Error>(bytes: Vec<u8>) -> Result<T, E> {
+    let len = bytes.len();
+    T::try_from(bytes).map_err(|_| E::invalid_length(len, &"a byte array of the field length"))
+}
+
+struct Encoded<'a, C>(&'a [u8], PhantomData<C>);
+
+impl<C: TextCodec> Serialize for Encoded<'_, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize::<C, S>(self.0, serializer)
+    }
+}
+
+struct Decoded<C>(Vec<u8>, PhantomData<C>);
+
+impl<'de, C: TextCodec> Deserialize<'de> for Decoded<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize::<C, D>(deserializer).map(|bytes| Decoded(bytes, PhantomData))
+    }
+}
+
+pub(crate) fn serialize_option<C: TextCodec, S: Serializer>(
+    bytes: Option<&[u8]>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match bytes {
+        Some(bytes) => serializer.serialize_some(&Encoded::<C>(bytes, PhantomData)),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub(crate) fn deserialize_option<'de, C: TextCodec, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<u8>>, D::Error> {
+    Ok(Option::<Decoded<C>>::deserialize(deserializer)?.map(|decoded| decoded.0))
+}
+
+/// Define the public `serialize`/`deserialize` functions and the `option`
+/// module of a codec's `serde` module
+macro_rules! serde_module {
+    ($codec:ty) => {
+        /// Serialize bytes, as a string for human-readable formats
+        pub fn serialize<T, S>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: AsRef<[u8]> + ?Sized,
+            S: ::serde::Serializer,
+        {
+            $crate::encoding::serde_codec::serialize::<$codec, S>(bytes.as_ref(), serializer)
+        }
+
+        /// Deserialize bytes to `Vec<u8>`, `[u8; N]` or any `TryFrom<Vec<u8>>`
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: TryFrom<Vec<u8>>,
+            D: ::serde::Deserializer<'de>,
+        {
+            let bytes = $crate::encoding::serde_codec::deserialize::<$codec, D>(deserializer)?;
+            $crate::encoding::serde_codec::convert(bytes)
+        }
+
+        /// The same for optional fields, `Option<Vec<u8>>` or `Option<[u8; N]>`
+        pub mod option {
+            /// Serialize optional bytes, as a string for human-readable formats
+            pub fn serialize<T, S>(bytes: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                T: AsRef<[u8]>,
+                S: ::serde::Serializer,
+            {
+                $crate::encoding::serde_codec::serialize_option::<$codec, S>(
+                    bytes.as_ref().map(AsRef::as_ref),
+                    serializer,
+                )
+            }
+
+            /// Deserialize optional bytes
+            pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+            where
+                T: TryFrom<Vec<u8>>,
+                D: ::serde::Deserializer<'de>,
+            {
+                $crate::encoding::serde_codec::deserialize_option::<$codec, D>(deserializer)?
+                    .map($crate::encoding::serde_codec::convert)
+                    .transpose()
+            }
+        }
+    };
+}
+
+pub(crate) use serde_module;
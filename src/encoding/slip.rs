@@ -0,0 +1,351 @@
+//! RFC1055 implemented, Serial Line Internet Protocol framing.
+//!
+//! Frames are delimited by `END` (0xC0). `END` and `ESC` (0xDB) inside the
+//! data are replaced by `ESC ESC_END` and `ESC ESC_ESC`.
+//!
+//! # Examples
+//!
+//! SLIP encode basic usage:
+//!
+//! ```
+//! use connx::encoding::slip;
+//! assert_eq!(slip::encode_frame(&[0x01, 0xC0, 0xDB]), [0xC0, 0x01, 0xDB, 0xDC, 0xDB, 0xDD, 0xC0]);
+//! ```
+//!
+//! Decoding frames from a byte stream:
+//!
+//! ```
+//! use connx::encoding::slip;
+//! let mut decoder = slip::FrameDecoder::new();
+//! assert!(decoder.push(&[0xC0, 0x01, 0xDB]).is_empty());
+//! assert_eq!(decoder.push(&[0xDC, 0xC0]), [Ok(vec![0x01, 0xC0])]);
+//! ```
+
+use crate::encoding::framing::FrameBuffer;
+
+/// Frame delimiter
+pub const END: u8 = 0xC0;
+
+/// Escape byte
+pub const ESC: u8 = 0xDB;
+
+/// Escaped `END`, follows `ESC`
+pub const ESC_END: u8 = 0xDC;
+
+/// Escaped `ESC`, follows `ESC`
+pub const ESC_ESC: u8 = 0xDD;
+
+/// Errors when SLIP decode
+#[derive(Debug, PartialEq)]
+pub enum SlipError {
+    /// `END` byte at this offset inside frame data
+    UnexpectedDelimiter(usize),
+    /// `ESC` at this offset is not followed by `ESC_END` or `ESC_ESC`
+    InvalidEscape(usize),
+    /// Data ends with `ESC`
+    Truncated,
+    /// Frame longer than the limit of the frame decoder
+    FrameTooLong,
+}
+
+impl std::fmt::Display for SlipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedDelimiter(offset) => {
+                write!(f, "encoding/slip: unexpected END at offset {}", offset)
+            }
+            Self::InvalidEscape(offset) => {
+                write!(f, "encoding/slip: invalid escape at offset {}", offset)
+            }
+            Self::Truncated => write!(f, "encoding/slip: truncated data"),
+            Self::FrameTooLong => write!(f, "encoding/slip: frame too long"),
+        }
+    }
+}
+
+impl std::error::Error for SlipError {}
+
+/// Calculate the maximum SLIP encoded length, without the `END` delimiters
+///
+/// - @param n: raw bytes length
+/// - @return: upper bound of the encoded length, (n*2)
+#[inline]
+pub fn encode_len(n: usize) -> usize {
+    n * 2
+}
+
+/// Calculate the maximum SLIP decoded length
+///
+/// - @param n: encoded length, without the `END` delimiters
+/// - @return: upper bound of the decoded length, (n)
+#[inline]
+pub fn decode_len(n: usize) -> usize {
+    n
+}
+
+/// Escape bytes to SLIP bytes, without the `END` delimiters
+///
+/// - @param dst: encoded bytes, at least `encode_len(src.len())`
+/// - @param src: raw bytes
+/// - @return: encoded byte size
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::slip;
+/// let src = [0xC0, 0x01];
+/// let mut dst = vec![0; slip::encode_len(src.len())];
+/// let dst_size = slip::encode(&mut dst, &src);
+/// assert_eq!(&dst[..dst_size], [0xDB, 0xDC, 0x01]);
+/// ```
+pub fn encode(dst: &mut [u8], src: &[u8]) -> usize {
+    let mut dst_idx = 0;
+    for &b in src {
+        match b {
+            END => {
+                dst[dst_idx] = ESC;
+                dst[dst_idx + 1] = ESC_END;
+                dst_idx += 2;
+            }
+            ESC => {
+                dst[dst_idx] = ESC;
+                dst[dst_idx + 1] = ESC_ESC;
+                dst_idx += 2;
+            }
+            _ => {
+                dst[dst_idx] = b;
+                dst_idx += 1;
+            }
+        }
+    }
+    dst_idx
+}
+
+/// Encode bytes to a SLIP frame, with a leading and a trailing `END`
+///
+/// The leading `END` flushes line noise received before the frame, as
+/// recommended by RFC1055.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::slip;
+/// assert_eq!(slip::encode_frame(&[0x01]), [0xC0, 0x01, 0xC0]);
+/// ```
+pub fn encode_frame(src: &[u8]) -> Vec<u8> {
+    let mut dst = vec![0; encode_len(src.len()) + 2];
+    dst[0] = END;
+    let dst_size = encode(&mut dst[1..], src) + 1;
+    dst[dst_size] = END;
+    dst.truncate(dst_size + 1);
+    dst
+}
+
+/// Unescape SLIP bytes to raw bytes, without the `END` delimiters
+///
+/// - @param dst: decoded raw bytes, at least `decode_len(src.len())`
+/// - @param src: SLIP bytes
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::slip::{self, SlipError};
+/// let src = [0xDB, 0xDC, 0x01];
+/// let mut dst = vec![0; slip::decode_len(src.len())];
+/// let dst_size = slip::decode(&mut dst, &src).unwrap();
+/// assert_eq!(&dst[..dst_size], [0xC0, 0x01]);
+/// assert_eq!(slip::decode(&mut dst, &[0xDB, 0x01]), Err(SlipError::InvalidEscape(0)));
+/// ```
+pub fn decode(dst: &mut [u8], src: &[u8]) -> Result<usize, SlipError> {
+    let mut src_idx = 0;
+    let mut dst_idx = 0;
+    while src_idx < src.len() {
+        dst[dst_idx] = match src[src_idx] {
+            END => return Err(SlipError::UnexpectedDelimiter(src_idx)),
+            ESC => {
+                src_idx += 1;
+                match src.get(src_idx) {
+                    Some(&ESC_END) => END,
+                    Some(&ESC_ESC) => ESC,
+                    Some(_) => return Err(SlipError::InvalidEscape(src_idx - 1)),
+                    None => return Err(SlipError::Truncated),
+                }
+            }
+            b => b,
+        };
+        src_idx += 1;
+        dst_idx += 1;
+    }
+    Ok(dst_idx)
+}
+
+/// Unescape SLIP bytes in place, the decoded data is never longer than the
+/// encoded data
+///
+/// - @param buf: SLIP bytes, overwritten with the raw bytes
+/// - @return: raw byte size if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::slip;
+/// let mut buf = [0x01, 0xDB, 0xDD, 0x02];
+/// let n = slip::decode_in_place(&mut buf).unwrap();
+/// assert_eq!(&buf[..n], [0x01, 0xDB, 0x02]);
+/// ```
+pub fn decode_in_place(buf: &mut [u8]) -> Result<usize, SlipError> {
+    let mut src_idx = 0;
+    let mut dst_idx = 0;
+    while src_idx < buf.len() {
+        buf[dst_idx] = match buf[src_idx] {
+            END => return Err(SlipError::UnexpectedDelimiter(src_idx)),
+            ESC => {
+                src_idx += 1;
+                match buf.get(src_idx) {
+                    Some(&ESC_END) => END,
+                    Some(&ESC_ESC) => ESC,
+                    Some(_) => return Err(SlipError::InvalidEscape(src_idx - 1)),
+                    None => return Err(SlipError::Truncated),
+                }
+            }
+            b => b,
+        };
+        src_idx += 1;
+        dst_idx += 1;
+    }
+    Ok(dst_idx)
+}
+
+/// Decode SLIP bytes to raw bytes, without the `END` delimiters
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::slip;
+/// assert_eq!(slip::decode_to_vec(&[0x01, 0xDB, 0xDC]), Ok(vec![0x01, 0xC0]));
+/// ```
+pub fn decode_to_vec(src: &[u8]) -> Result<Vec<u8>, SlipError> {
+    let mut dst = vec![0; decode_len(src.len())];
+    let dst_size = decode(&mut dst, src)?;
+    dst.truncate(dst_size);
+    Ok(dst)
+}
+
+/// Splits a byte stream into `END` delimited frames and decodes them.
+///
+/// Bytes are buffered until an `END` arrives, empty frames between two
+/// `END` bytes are skipped. A frame exceeding the length limit is reported
+/// once and dropped up to the next `END`.
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    frames: FrameBuffer,
+}
+
+impl FrameDecoder {
+    /// Frame decoder without length limit
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the encoded length of a frame
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.frames.set_max_len(max_len);
+        self
+    }
+
+    /// Bytes of the incomplete frame buffered so far
+    pub fn pending(&self) -> usize {
+        self.frames.pending()
+    }
+
+    /// Feed received bytes, returns every frame completed by them
+    pub fn push(&mut self, data: &[u8]) -> Vec<Result<Vec<u8>, SlipError>> {
+        self.frames
+            .push(data, END, || SlipError::FrameTooLong, decode_to_vec)
+    }
+}
+
+#[cfg(test)]
+mod tests_slip {
+    use super::*;
+
+    fn test_base(data: &[u8], encoded_expect: &[u8]) {
+        let mut encoded = vec![0; encode_len(data.len())];
+        let n = encode(&mut encoded, data);
+        assert_eq!(&encoded[..n], encoded_expect);
+        assert_eq!(decode_to_vec(&encoded[..n]).unwrap(), data);
+
+        let mut buf = encoded[..n].to_vec();
+        let m = decode_in_place(&mut buf).unwrap();
+        assert_eq!(&buf[..m], data);
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(&[], &[]);
+        test_base(&[0x01, 0x02], &[0x01, 0x02]);
+        test_base(&[END], &[ESC, ESC_END]);
+        test_base(&[ESC], &[ESC, ESC_ESC]);
+        test_base(&[ESC_END, ESC_ESC], &[ESC_END, ESC_ESC]);
+        test_base(
+            &[END, ESC, END],
+            &[ESC, ESC_END, ESC, ESC_ESC, ESC, ESC_END],
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            decode_to_vec(&[0x01, END]),
+            Err(SlipError::UnexpectedDelimiter(1))
+        );
+        assert_eq!(
+            decode_to_vec(&[0x01, ESC, 0x02]),
+            Err(SlipError::InvalidEscape(1))
+        );
+        assert_eq!(decode_to_vec(&[0x01, ESC]), Err(SlipError::Truncated));
+        assert_eq!(
+            decode_in_place(&mut [ESC, END]),
+            Err(SlipError::InvalidEscape(0))
+        );
+        assert_eq!(decode_in_place(&mut [ESC]), Err(SlipError::Truncated));
+    }
+
+    #[test]
+    fn test_frame_decoder() {
+        let mut stream = Vec::new();
+        stream.extend(encode_frame(&[0x01, END, 0x02]));
+        stream.extend(encode_frame(&[]));
+        stream.extend(encode_frame(&[ESC, ESC]));
+
+        let mut decoder = FrameDecoder::new();
+        let frames: Vec<_> = stream.iter().flat_map(|b| decoder.push(&[*b])).collect();
+        assert_eq!(frames, [Ok(vec![0x01, END, 0x02]), Ok(vec![ESC, ESC])]);
+        assert_eq!(decoder.pending(), 0);
+
+        let mut decoder = FrameDecoder::new();
+        let frames = decoder.push(&[0x01, ESC, 0x02, END, 0x03, END, 0x04]);
+        assert_eq!(frames, [Err(SlipError::InvalidEscape(1)), Ok(vec![0x03])]);
+        assert_eq!(decoder.pending(), 1);
+    }
+
+    #[test]
+    fn test_frame_decoder_limits() {
+        let mut decoder = FrameDecoder::new().with_max_len(4);
+        let mut stream = encode_frame(&[0x11; 10]);
+        stream.extend(encode_frame(&[ESC, 0x22]));
+        let frames = decoder.push(&stream);
+        assert_eq!(frames, [Err(SlipError::FrameTooLong), Ok(vec![ESC, 0x22])]);
+    }
+}
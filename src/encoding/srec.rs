@@ -0,0 +1,397 @@
+//! Motorola S-record implemented, the firmware file format of records S0 to S9.
+//!
+//! Every line is a record `S<type><count><address><data><checksum>` of hex
+//! digits. S1, S2 and S3 carry data with a 16, 24 or 32 bit address, S5 and
+//! S6 count the data records, and S9, S8 or S7 end the file with the start
+//! address. S0 is a free form header.
+//!
+//! # Examples
+//!
+//! S-record encode basic usage:
+//!
+//! ```
+//! use connx::encoding::srec::{self, AddressSize, Srec};
+//! let mut file = Srec::default();
+//! file.image.write(0x0100, b"hello");
+//! let encoded = srec::encode(&file, AddressSize::Bits16, 16).unwrap();
+//! assert_eq!(encoded, "S0030000FC\nS108010068656C6C6FE2\nS5030001FB\nS9030000FC\n");
+//! ```
+//!
+//! S-record decode basic usage:
+//!
+//! ```
+//! use connx::encoding::srec;
+//! let file = srec::decode("S0060000686472BB\nS108010068656C6C6FE2\nS9030100FB\n").unwrap();
+//! assert_eq!(file.header, b"hdr");
+//! assert_eq!(file.image.get(0x0100), Some(b'h'));
+//! assert_eq!(file.image.start_address, Some(0x0100));
+//! ```
+
+use crate::encoding::base16;
+use crate::encoding::image::MemoryImage;
+
+/// Default number of data bytes per record
+pub const RECORD_LEN: usize = 16;
+
+/// Errors when S-record encode and decode, with the 1-based line number
+#[derive(Debug, PartialEq)]
+pub enum SrecError {
+    /// Missing `S`, bad hex digits, or count not matching the record
+    InvalidRecord(usize),
+    InvalidChecksum(usize),
+    /// Record type S4 or not a digit
+    InvalidType(usize),
+    /// S5 or S6 count differs from the number of data records
+    InvalidCount(usize),
+    /// Data does not fit the address size, when encoding or decoding
+    AddressOverflow,
+}
+
+impl std::fmt::Display for SrecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRecord(line) => {
+                write!(f, "encoding/srec: invalid record at line {}", line)
+            }
+            Self::InvalidChecksum(line) => {
+                write!(f, "encoding/srec: invalid checksum at line {}", line)
+            }
+            Self::InvalidType(line) => {
+                write!(f, "encoding/srec: invalid record type at line {}", line)
+            }
+            Self::InvalidCount(line) => {
+                write!(f, "encoding/srec: record count mismatch at line {}", line)
+            }
+            Self::AddressOverflow => write!(f, "encoding/srec: address overflow"),
+        }
+    }
+}
+
+impl std::error::Error for SrecError {}
+
+/// Address width of the data and termination records
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressSize {
+    /// S1 data records, S9 termination
+    Bits16,
+    /// S2 data records, S8 termination
+    Bits24,
+    /// S3 data records, S7 termination
+    Bits32,
+}
+
+impl AddressSize {
+    /// Address field length, in bytes
+    pub const fn addr_len(&self) -> usize {
+        match self {
+            Self::Bits16 => 2,
+            Self::Bits24 => 3,
+            Self::Bits32 => 4,
+        }
+    }
+
+    /// Smallest size which can address every byte of the image
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// use connx::encoding::{image::MemoryImage, srec::AddressSize};
+    /// let mut image = MemoryImage::new();
+    /// image.write(0xFFFF, b"a");
+    /// assert_eq!(AddressSize::for_image(&image), AddressSize::Bits16);
+    /// image.write(0x10000, b"b");
+    /// assert_eq!(AddressSize::for_image(&image), AddressSize::Bits24);
+    /// ```
+    pub fn for_image(image: &MemoryImage) -> AddressSize {
+        let last = image
+            .segments()
+            .map(|(addr, data)| u64::from(addr) + data.len() as u64 - 1)
+            .chain(image.start_address.map(u64::from))
+            .max()
+            .unwrap_or(0);
+        if last <= 0xFFFF {
+            Self::Bits16
+        } else if last <= 0xFF_FFFF {
+            Self::Bits24
+        } else {
+            Self::Bits32
+        }
+    }
+
+    const fn data_type(&self) -> u8 {
+        match self {
+            Self::Bits16 => 1,
+            Self::Bits24 => 2,
+            Self::Bits32 => 3,
+        }
+    }
+
+    const fn termination_type(&self) -> u8 {
+        match self {
+            Self::Bits16 => 9,
+            Self::Bits24 => 8,
+            Self::Bits32 => 7,
+        }
+    }
+}
+
+/// Content of an S-record file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Srec {
+    /// Data of the S0 header record
+    pub header: Vec<u8>,
+    pub image: MemoryImage,
+}
+
+/// Append one record line, computing its count and checksum
+fn write_record(dst: &mut String, ty: u8, addr: u32, addr_len: usize, data: &[u8]) {
+    let mut record = Vec::with_capacity(data.len() + 6);
+    record.push((addr_len + data.len() + 1) as u8);
+    record.extend_from_slice(&addr.to_be_bytes()[4 - addr_len..]);
+    record.extend_from_slice(data);
+    let sum = record.iter().fold(0u8, |acc, &b| acc.wrapping_add(b));
+    record.push(!sum);
+
+    let mut hex = vec![0; base16::encode_len(record.len())];
+    base16::encode(&mut hex, &record);
+    hex.make_ascii_uppercase();
+    dst.push('S');
+    dst.push((b'0' + ty) as char);
+    dst.push_str(std::str::from_utf8(&hex).unwrap());
+    dst.push('\n');
+}
+
+/// Encode a file to S-records
+///
+/// The data record count is written as S5, or S6 above 65535 records, and
+/// left out above 16777215 records. The termination record holds the start
+/// address, zero if there is none.
+///
+/// - @param file: header and memory image
+/// - @param size: address size of the data and termination records
+/// - @param record_len: data bytes per record, 1 to 250
+/// - @return: S-record text, uppercase with LF line breaks, if every address fits `size`.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::srec::{self, AddressSize, Srec, SrecError};
+/// let mut file = Srec::default();
+/// file.image.write(0x0800_0000, &[0xAA, 0xBB]);
+/// file.image.start_address = Some(0x0800_0000);
+/// let encoded = srec::encode(&file, AddressSize::Bits32, 16).unwrap();
+/// let lines: Vec<&str> = encoded.lines().collect();
+/// assert_eq!(lines, ["S0030000FC", "S30708000000AABB8B", "S5030001FB", "S70508000000F2"]);
+/// assert_eq!(srec::encode(&file, AddressSize::Bits24, 16), Err(SrecError::AddressOverflow));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `record_len` is not in 1..=250.
+pub fn encode(file: &Srec, size: AddressSize, record_len: usize) -> Result<String, SrecError> {
+    assert!(
+        (1..=250).contains(&record_len),
+        "encoding/srec: invalid record length"
+    );
+    if AddressSize::for_image(&file.image).addr_len() > size.addr_len() {
+        return Err(SrecError::AddressOverflow);
+    }
+
+    let image_len = file.image.len();
+    let mut dst = String::with_capacity(image_len * 2 + image_len / record_len * 16 + 64);
+    for header in file
+        .header
+        .chunks(252)
+        .chain(file.header.is_empty().then_some(&[][..]))
+    {
+        write_record(&mut dst, 0, 0, 2, header);
+    }
+    let mut count: u32 = 0;
+    for (addr, data) in file.image.segments() {
+        for (i, chunk) in data.chunks(record_len).enumerate() {
+            let chunk_addr = addr + (i * record_len) as u32;
+            write_record(
+                &mut dst,
+                size.data_type(),
+                chunk_addr,
+                size.addr_len(),
+                chunk,
+            );
+            count += 1;
+        }
+    }
+    if count <= 0xFFFF {
+        write_record(&mut dst, 5, count, 2, &[]);
+    } else if count <= 0xFF_FFFF {
+        write_record(&mut dst, 6, count, 3, &[]);
+    }
+    let start = file.image.start_address.unwrap_or(0);
+    write_record(
+        &mut dst,
+        size.termination_type(),
+        start,
+        size.addr_len(),
+        &[],
+    );
+    Ok(dst)
+}
+
+/// Parse one record line to its type and bytes, checksum verified
+fn read_record(line: &str, line_no: usize) -> Result<(u8, Vec<u8>), SrecError> {
+    let rest = line
+        .strip_prefix(['S', 's'])
+        .ok_or(SrecError::InvalidRecord(line_no))?;
+    let ty = match rest.as_bytes().first() {
+        Some(&b @ b'0'..=b'9') if b != b'4' => b - b'0',
+        _ => return Err(SrecError::InvalidType(line_no)),
+    };
+    let hex = &rest.as_bytes()[1..];
+    let mut record = vec![0; base16::decode_len(hex.len())];
+    base16::decode(&mut record, hex).map_err(|_| SrecError::InvalidRecord(line_no))?;
+    if record.len() < 2 || record.len() != record[0] as usize + 1 {
+        return Err(SrecError::InvalidRecord(line_no));
+    }
+    if record.iter().fold(0u8, |acc, &b| acc.wrapping_add(b)) != 0xFF {
+        return Err(SrecError::InvalidChecksum(line_no));
+    }
+    record.pop();
+    record.remove(0);
+    Ok((ty, record))
+}
+
+/// Decode S-records to the header and memory image
+///
+/// Blank lines are skipped and both LF and CRLF are accepted, lines after
+/// the termination record are ignored. A file without termination record
+/// is accepted, as many tools write one.
+///
+/// - @param src: S-record text
+/// - @return: header and memory image if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::srec::{self, SrecError};
+/// let file = srec::decode("S205123456411D\nS804000000FB").unwrap();
+/// assert_eq!(file.image.get(0x123456), Some(b'A'));
+/// assert_eq!(srec::decode("S5030002FA"), Err(SrecError::InvalidCount(1)));
+/// ```
+pub fn decode(src: &str) -> Result<Srec, SrecError> {
+    let mut file = Srec::default();
+    let mut count: u32 = 0;
+    for (idx, line) in src.lines().enumerate() {
+        let line_no = idx + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (ty, record) = read_record(line, line_no)?;
+        let addr_len = match ty {
+            0 | 1 | 5 | 9 => 2,
+            2 | 6 | 8 => 3,
+            _ => 4,
+        };
+        if record.len() < addr_len {
+            return Err(SrecError::InvalidRecord(line_no));
+        }
+        let (addr, data) = record.split_at(addr_len);
+        let addr = addr.iter().fold(0u32, |acc, &b| acc << 8 | u32::from(b));
+        match ty {
+            0 => file.header.extend_from_slice(data),
+            1..=3 => {
+                if u64::from(addr) + data.len() as u64 > 1 << 32 {
+                    return Err(SrecError::AddressOverflow);
+                }
+                file.image.write(addr, data);
+                count += 1;
+            }
+            5 | 6 => {
+                if addr != count {
+                    return Err(SrecError::InvalidCount(line_no));
+                }
+            }
+            _ => {
+                file.image.start_address = Some(addr);
+                return Ok(file);
+            }
+        }
+    }
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests_srec {
+    use super::*;
+
+    const WIKIPEDIA: &str = "\
+S00F000068656C6C6F202020202000003C
+S11F00007C0802A6900100049421FFF07C6C1B787C8C23783C6000003863000026
+S11F001C4BFFFFE5398000007D83637880010014382100107C0803A64E800020E9
+S111003848656C6C6F20776F726C642E0A0042
+S5030003F9
+S9030000FC
+";
+
+    #[test]
+    fn test_roundtrip() {
+        let file = decode(WIKIPEDIA).unwrap();
+        assert_eq!(file.header, b"hello     \0\0");
+        let segments: Vec<(u32, usize)> =
+            file.image.segments().map(|(a, d)| (a, d.len())).collect();
+        assert_eq!(segments, [(0, 70)]);
+        assert_eq!(file.image.start_address, Some(0));
+        assert_eq!(encode(&file, AddressSize::Bits16, 28).unwrap(), WIKIPEDIA);
+    }
+
+    #[test]
+    fn test_address_sizes() {
+        let mut file = Srec::default();
+        file.image.write(0x123456, b"A");
+        let size = AddressSize::for_image(&file.image);
+        assert_eq!(size, AddressSize::Bits24);
+        let encoded = encode(&file, size, RECORD_LEN).unwrap();
+        assert_eq!(
+            encoded,
+            "S0030000FC\nS205123456411D\nS5030001FB\nS804000000FB\n"
+        );
+        let decoded = decode(&encoded).unwrap();
+        assert_eq!(decoded.image.get(0x123456), Some(b'A'));
+        assert_eq!(
+            encode(&file, AddressSize::Bits16, RECORD_LEN),
+            Err(SrecError::AddressOverflow)
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode("X1030000FC"), Err(SrecError::InvalidRecord(1)));
+        assert_eq!(decode("S4030000FC"), Err(SrecError::InvalidType(1)));
+        assert_eq!(decode("SX030000FC"), Err(SrecError::InvalidType(1)));
+        assert_eq!(decode("S1040000FC"), Err(SrecError::InvalidRecord(1)));
+        assert_eq!(decode("S30300"), Err(SrecError::InvalidRecord(1)));
+        assert_eq!(decode("\nS9030000FD"), Err(SrecError::InvalidChecksum(2)));
+        assert_eq!(
+            decode("S108010068656C6C6FE2\nS604000000FB"),
+            Err(SrecError::InvalidCount(2))
+        );
+        assert_eq!(
+            decode("S108010068656C6C6FE2\nS604000001FA")
+                .unwrap()
+                .image
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid record length")]
+    fn test_encode_record_len() {
+        let _ = encode(&Srec::default(), AddressSize::Bits32, 0);
+    }
+}
@@ -0,0 +1,350 @@
+//! RFC2152 and RFC3501 implemented, UTF-7 and modified UTF-7 encoding.
+//!
+//! Both encodings write characters outside of a safe ASCII subset as shifted
+//! sections of base64 over UTF-16 big endian, without padding. UTF-7 starts
+//! a section with `+`, the IMAP variant used for mailbox names starts it with
+//! `&` and uses `,` instead of `/` in the base64 alphabet.
+//!
+//! # Examples
+//!
+//! UTF-7 basic usage:
+//!
+//! ```
+//! use connx::encoding::utf7;
+//! assert_eq!(utf7::encode("Hi Mom -☺-!"), "Hi Mom -+Jjo--!");
+//! assert_eq!(utf7::decode("Hi Mom -+Jjo--!").unwrap(), "Hi Mom -☺-!");
+//! ```
+//!
+//! Modified UTF-7 basic usage:
+//!
+//! ```
+//! use connx::encoding::utf7;
+//! assert_eq!(utf7::encode_imap("~peter/mail/台北/日本語"), "~peter/mail/&U,BTFw-/&ZeVnLIqe-");
+//! assert_eq!(utf7::decode_imap("Tom &- Jerry").unwrap(), "Tom & Jerry");
+//! ```
+
+use crate::encoding::base64;
+
+/// Errors when UTF-7 decode
+#[derive(Debug, PartialEq)]
+pub enum Utf7Error {
+    /// Byte which can not appear outside of a shifted section
+    InvalidByte(u8),
+    /// Shifted section at this position is empty, unterminated or has a bad length
+    InvalidShift(usize),
+    /// Shifted section at this position holds an unpaired surrogate
+    InvalidSurrogate(usize),
+    /// Shifted section at this position encodes characters which must be written directly
+    UnnecessaryShift(usize),
+}
+
+impl std::fmt::Display for Utf7Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidByte(b) => write!(f, "encoding/utf7: invalid byte: {}", b),
+            Self::InvalidShift(pos) => write!(f, "encoding/utf7: invalid shift at {}", pos),
+            Self::InvalidSurrogate(pos) => {
+                write!(f, "encoding/utf7: unpaired surrogate in shift at {}", pos)
+            }
+            Self::UnnecessaryShift(pos) => {
+                write!(f, "encoding/utf7: unnecessary shift at {}", pos)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Utf7Error {}
+
+/// Characters written directly by UTF-7, RFC2152 set D, set O and white space
+/// except `\` and `~`
+#[inline]
+fn is_direct(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "'(),-./:?!\"#$%&*;<=>@[]^_`{|} \t\r\n".contains(c)
+}
+
+/// Characters written directly by modified UTF-7, printable ASCII except `&`
+#[inline]
+fn is_direct_imap(c: char) -> bool {
+    (' '..='~').contains(&c) && c != '&'
+}
+
+/// Append a shifted section for `units`, with the trailing `-` if `terminate`
+fn encode_shift(dst: &mut String, units: &[u16], shift: char, encode_map: &[u8], terminate: bool) {
+    let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_be_bytes()).collect();
+    let encoded = base64::encode_bytes_with_map(&bytes, encode_map);
+    let unpadded = encoded.len() - encoded.iter().rev().take_while(|&&b| b == b'=').count();
+    dst.push(shift);
+    dst.extend(encoded[..unpadded].iter().map(|&b| b as char));
+    if terminate {
+        dst.push('-');
+    }
+}
+
+fn encode_with(
+    src: &str,
+    shift: char,
+    encode_map: &[u8],
+    direct: fn(char) -> bool,
+    implicit_end: bool,
+) -> String {
+    let mut dst = String::with_capacity(src.len() + src.len() / 2);
+    let mut units: Vec<u16> = Vec::new();
+    for c in src.chars() {
+        if c == shift || direct(c) {
+            if !units.is_empty() {
+                // UTF-7 may end a section implicitly before a non base64 character
+                let terminate =
+                    !implicit_end || c == '-' || c == shift || encode_map.contains(&(c as u8));
+                encode_shift(&mut dst, &units, shift, encode_map, terminate);
+                units.clear();
+            }
+            dst.push(c);
+            if c == shift {
+                dst.push('-');
+            }
+        } else {
+            let mut buf = [0u16; 2];
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+        }
+    }
+    if !units.is_empty() {
+        encode_shift(&mut dst, &units, shift, encode_map, true);
+    }
+    dst
+}
+
+/// Decode the base64 of one shifted section, starting at `pos`
+fn decode_shift(run: &[u8], decode_map: &[u8], pos: usize) -> Result<String, Utf7Error> {
+    if run.len() % 4 == 1 {
+        return Err(Utf7Error::InvalidShift(pos));
+    }
+    let mut padded = run.to_vec();
+    padded.resize(run.len().div_ceil(4) * 4, base64::PAD_CHAR);
+    let mut bytes = vec![0; base64::decode_len(padded.len())];
+    let n = base64::decode(&mut bytes, &padded, decode_map)
+        .map_err(|_| Utf7Error::InvalidShift(pos))?;
+    bytes.truncate(n);
+
+    // only whole UTF-16 units, and the unused low bits must be zero
+    let unused_bits = run.len() * 6 - n * 8;
+    let last = decode_map[run[run.len() - 1] as usize];
+    if n % 2 != 0 || last & ((1 << unused_bits) - 1) != 0 {
+        return Err(Utf7Error::InvalidShift(pos));
+    }
+
+    let units = bytes.chunks(2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map_err(|_| Utf7Error::InvalidSurrogate(pos))
+}
+
+fn decode_with(src: &str, shift: u8, decode_map: &[u8], imap: bool) -> Result<String, Utf7Error> {
+    let src = src.as_bytes();
+    let mut dst = String::with_capacity(src.len());
+    let mut prev_shift_end = None;
+    let mut idx = 0;
+    while idx < src.len() {
+        let b = src[idx];
+        if b != shift {
+            let valid = if imap {
+                (0x20..=0x7E).contains(&b)
+            } else {
+                b.is_ascii()
+            };
+            if !valid {
+                return Err(Utf7Error::InvalidByte(b));
+            }
+            dst.push(b as char);
+            idx += 1;
+            continue;
+        }
+
+        let start = idx;
+        idx += 1;
+        let run_start = idx;
+        while idx < src.len() && decode_map[src[idx] as usize] != 0xFF {
+            idx += 1;
+        }
+        let run = &src[run_start..idx];
+        let terminated = src.get(idx) == Some(&b'-');
+        if terminated {
+            idx += 1;
+        }
+
+        if run.is_empty() {
+            if !terminated {
+                return Err(Utf7Error::InvalidShift(start));
+            }
+            dst.push(shift as char);
+            continue;
+        }
+        if imap && !terminated {
+            return Err(Utf7Error::InvalidShift(start));
+        }
+        // two adjacent sections should have been written as one
+        if imap && prev_shift_end == Some(start) {
+            return Err(Utf7Error::UnnecessaryShift(start));
+        }
+        let decoded = decode_shift(run, decode_map, start)?;
+        if imap && decoded.chars().any(|c| (' '..='~').contains(&c)) {
+            return Err(Utf7Error::UnnecessaryShift(start));
+        }
+        dst.push_str(&decoded);
+        prev_shift_end = Some(idx);
+    }
+    Ok(dst)
+}
+
+/// Encode a string to UTF-7
+///
+/// - @param src: unicode string
+/// - @return: UTF-7 encoded string
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::utf7;
+/// assert_eq!(utf7::encode("日本語"), "+ZeVnLIqe-");
+/// assert_eq!(utf7::encode("1 + 1 = 2"), "1 +- 1 = 2");
+/// ```
+pub fn encode(src: &str) -> String {
+    encode_with(src, '+', base64::ENCODE_STD, is_direct, true)
+}
+
+/// Decode UTF-7 to a string
+///
+/// A shifted section ends at the first non base64 character, a `-` ending
+/// it is absorbed.
+///
+/// - @param src: UTF-7 encoded string
+/// - @return: decoded string if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::utf7::{self, Utf7Error};
+/// assert_eq!(utf7::decode("A+ImIDkQ.").unwrap(), "A≢Α.");
+/// assert_eq!(utf7::decode("+!"), Err(Utf7Error::InvalidShift(0)));
+/// ```
+pub fn decode(src: &str) -> Result<String, Utf7Error> {
+    decode_with(src, b'+', base64::DECODE_STD_MAP, false)
+}
+
+/// Encode a mailbox name to modified UTF-7
+///
+/// - @param src: unicode mailbox name
+/// - @return: modified UTF-7 encoded mailbox name
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::utf7;
+/// assert_eq!(utf7::encode_imap("Entwürfe"), "Entw&APw-rfe");
+/// assert_eq!(utf7::encode_imap("R&D"), "R&-D");
+/// ```
+pub fn encode_imap(src: &str) -> String {
+    encode_with(src, '&', base64::ENCODE_IMAP, is_direct_imap, false)
+}
+
+/// Decode a modified UTF-7 mailbox name
+///
+/// Decoding is strict: every shifted section must end with `-`, must not
+/// encode printable ASCII and must not directly follow another section.
+///
+/// - @param src: modified UTF-7 encoded mailbox name
+/// - @return: decoded mailbox name if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::utf7::{self, Utf7Error};
+/// assert_eq!(utf7::decode_imap("Entw&APw-rfe").unwrap(), "Entwürfe");
+/// assert_eq!(utf7::decode_imap("&AGE-"), Err(Utf7Error::UnnecessaryShift(0)));
+/// ```
+pub fn decode_imap(src: &str) -> Result<String, Utf7Error> {
+    let decode_map = base64::decode_map(base64::ENCODE_IMAP);
+    decode_with(src, b'&', &decode_map, true)
+}
+
+#[cfg(test)]
+mod tests_utf7 {
+    use super::*;
+
+    fn test_base(data: &str, encoded_expect: &str) {
+        let encoded = encode(data);
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(decode(&encoded).unwrap(), data);
+    }
+
+    fn test_base_imap(data: &str, encoded_expect: &str) {
+        let encoded = encode_imap(data);
+        assert_eq!(encoded, encoded_expect);
+        assert_eq!(decode_imap(&encoded).unwrap(), data);
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base("", "");
+        test_base("Hi Mom -☺-!", "Hi Mom -+Jjo--!");
+        test_base("日本語", "+ZeVnLIqe-");
+        test_base("A≢Α.", "A+ImIDkQ.");
+        test_base("~\\", "+AH4AXA-");
+        test_base("a😀b", "a+2D3eAA-b");
+        test_base("a\r\n\tb", "a\r\n\tb");
+    }
+
+    #[test]
+    fn test_decode_01() {
+        // explicit terminator, and optional characters shifted
+        assert_eq!(decode("A+ImIDkQ-.").unwrap(), "A≢Α.");
+        assert_eq!(decode("+ACE-").unwrap(), "!");
+        assert_eq!(decode("+-+-").unwrap(), "++");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode("+"), Err(Utf7Error::InvalidShift(0)));
+        assert_eq!(decode("a+A-"), Err(Utf7Error::InvalidShift(1)));
+        // odd number of bytes
+        assert_eq!(decode("+AGEA-"), Err(Utf7Error::InvalidShift(0)));
+        // non-zero unused bits
+        assert_eq!(decode("+AGF-"), Err(Utf7Error::InvalidShift(0)));
+        // lone high surrogate U+D800
+        assert_eq!(decode("+2AA-"), Err(Utf7Error::InvalidSurrogate(0)));
+        assert_eq!(decode("é"), Err(Utf7Error::InvalidByte(0xC3)));
+    }
+
+    #[test]
+    fn test_encode_imap_01() {
+        test_base_imap("", "");
+        test_base_imap("INBOX", "INBOX");
+        test_base_imap("&", "&-");
+        test_base_imap("~peter/mail/台北/日本語", "~peter/mail/&U,BTFw-/&ZeVnLIqe-");
+        test_base_imap("😀&é", "&2D3eAA-&-&AOk-");
+        test_base_imap("a\u{7F}", "a&AH8-");
+    }
+
+    #[test]
+    fn test_decode_imap_errors() {
+        assert_eq!(decode_imap("&"), Err(Utf7Error::InvalidShift(0)));
+        assert_eq!(decode_imap("&AOk"), Err(Utf7Error::InvalidShift(0)));
+        assert_eq!(decode_imap("&AOk."), Err(Utf7Error::InvalidShift(0)));
+        assert_eq!(decode_imap("&U/BTFw-"), Err(Utf7Error::InvalidShift(0)));
+        assert_eq!(decode_imap("x&ACY-"), Err(Utf7Error::UnnecessaryShift(1)));
+        assert_eq!(
+            decode_imap("&U,BTFw-&ZeVnLIqe-"),
+            Err(Utf7Error::UnnecessaryShift(8))
+        );
+        assert_eq!(decode_imap("&2AA-"), Err(Utf7Error::InvalidSurrogate(0)));
+        assert_eq!(decode_imap("tab\t"), Err(Utf7Error::InvalidByte(b'\t')));
+        assert_eq!(decode_imap("é"), Err(Utf7Error::InvalidByte(0xC3)));
+    }
+}
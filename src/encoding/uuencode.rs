@@ -0,0 +1,286 @@
+//! uuencode implemented, as produced by the historical `uuencode` command.
+//!
+//! An encoded file starts with a `begin <mode> <name>` line, every data line
+//! holds up to 45 raw bytes prefixed by their count, and the file ends with
+//! an empty data line followed by `end`.
+//!
+//! # Examples
+//!
+//! uuencode basic usage:
+//!
+//! ```
+//! use connx::encoding::uuencode;
+//! let encoded = uuencode::encode(b"Cat", 0o644, "cat.txt");
+//! assert_eq!(encoded, "begin 644 cat.txt\n#0V%T\n`\nend\n");
+//! ```
+//!
+//! uudecode basic usage:
+//!
+//! ```
+//! use connx::encoding::uuencode;
+//! let file = uuencode::decode(b"begin 644 cat.txt\n#0V%T\n`\nend\n").unwrap();
+//! assert_eq!(file.mode, 0o644);
+//! assert_eq!(file.name, "cat.txt");
+//! assert_eq!(file.data, b"Cat");
+//! ```
+
+/// Raw bytes per encoded line
+pub const LINE_BYTES: usize = 45;
+
+/// Errors when uudecode
+#[derive(Debug, PartialEq)]
+pub enum UuencodeError {
+    /// No `begin` line was found
+    MissingBegin,
+    /// The `begin` line has no valid octal mode or no file name
+    InvalidHeader,
+    /// Input ended before the `end` line
+    MissingEnd,
+    /// Byte outside of the uuencode character range
+    InvalidByte(u8),
+}
+
+impl std::fmt::Display for UuencodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingBegin => write!(f, "encoding/uuencode: missing begin line"),
+            Self::InvalidHeader => write!(f, "encoding/uuencode: invalid begin line"),
+            Self::MissingEnd => write!(f, "encoding/uuencode: missing end line"),
+            Self::InvalidByte(b) => write!(f, "encoding/uuencode: invalid byte: {}", b),
+        }
+    }
+}
+
+impl std::error::Error for UuencodeError {}
+
+/// File decoded from uuencoded text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuFile {
+    /// Unix permission bits from the `begin` line
+    pub mode: u32,
+    /// File name from the `begin` line
+    pub name: String,
+    /// File content
+    pub data: Vec<u8>,
+}
+
+/// Map a 6 bit value to its character, zero is written as a backquote
+#[inline]
+fn to_char(v: u8) -> u8 {
+    if v == 0 {
+        b'`'
+    } else {
+        v + 0x20
+    }
+}
+
+/// Map a character back to its 6 bit value, both space and backquote are zero
+#[inline]
+fn from_char(b: u8) -> Result<u8, UuencodeError> {
+    if !(0x20..=0x60).contains(&b) {
+        return Err(UuencodeError::InvalidByte(b));
+    }
+    Ok((b - 0x20) & 0x3F)
+}
+
+/// Encode one data line, up to [`LINE_BYTES`] raw bytes, without line break
+///
+/// - @param dst: encoded line is appended here
+/// - @param src: raw bytes
+fn encode_line(dst: &mut String, src: &[u8]) {
+    dst.push(to_char(src.len() as u8) as char);
+    for chunk in src.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let val = u32::from(group[0]) << 16 | u32::from(group[1]) << 8 | u32::from(group[2]);
+        dst.push(to_char((val >> 18 & 0x3F) as u8) as char);
+        dst.push(to_char((val >> 12 & 0x3F) as u8) as char);
+        dst.push(to_char((val >> 6 & 0x3F) as u8) as char);
+        dst.push(to_char((val & 0x3F) as u8) as char);
+    }
+}
+
+/// uuencode bytes as a complete file, with begin and end lines
+///
+/// - @param src: raw bytes
+/// - @param mode: unix permission bits, written in octal
+/// - @param name: file name
+/// - @return: uuencoded text
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::uuencode;
+/// let encoded = uuencode::encode(&[0u8; 46], 0o600, "zeros");
+/// let lines: Vec<&str> = encoded.lines().collect();
+/// assert_eq!(lines, ["begin 600 zeros", &format!("M{}", "`".repeat(60)), "!````", "`", "end"]);
+/// ```
+pub fn encode(src: &[u8], mode: u32, name: &str) -> String {
+    let mut dst = String::with_capacity(src.len() * 4 / 3 + src.len() / LINE_BYTES * 2 + 32);
+    dst.push_str(&format!("begin {:o} {}\n", mode, name));
+    for line in src.chunks(LINE_BYTES) {
+        encode_line(&mut dst, line);
+        dst.push('\n');
+    }
+    dst.push_str("`\nend\n");
+    dst
+}
+
+/// Decode one data line, returns false on the terminating empty line
+fn decode_line(dst: &mut Vec<u8>, line: &[u8]) -> Result<bool, UuencodeError> {
+    let Some((&len_char, body)) = line.split_first() else {
+        return Ok(false);
+    };
+    let n = from_char(len_char)? as usize;
+    if n == 0 {
+        return Ok(false);
+    }
+
+    // mail gateways strip trailing spaces, missing characters count as zero
+    let mut remain = n;
+    let mut groups = body.chunks(4);
+    while remain > 0 {
+        let mut val: u32 = 0;
+        let group = groups.next().unwrap_or(&[]);
+        for i in 0..4 {
+            let c = group.get(i).copied().unwrap_or(b' ');
+            val |= u32::from(from_char(c)?) << (18 - i * 6);
+        }
+        let bytes = [(val >> 16) as u8, (val >> 8) as u8, val as u8];
+        let take = remain.min(3);
+        dst.extend_from_slice(&bytes[..take]);
+        remain -= take;
+    }
+    Ok(true)
+}
+
+/// Decode the first uuencoded file found in the text
+///
+/// Lines before `begin` are skipped, both LF and CRLF line breaks are
+/// accepted.
+///
+/// - @param src: text containing a uuencoded file
+/// - @return: decoded file if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::uuencode::{self, UuencodeError};
+/// let src = b"From: archive\r\n\r\nbegin 755 run.sh\r\n#0V%T\r\n`\r\nend\r\n";
+/// assert_eq!(uuencode::decode(src).unwrap().data, b"Cat");
+/// assert_eq!(uuencode::decode(b"#0V%T\n"), Err(UuencodeError::MissingBegin));
+/// ```
+pub fn decode(src: &[u8]) -> Result<UuFile, UuencodeError> {
+    let mut lines = src
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+
+    let header = lines
+        .find(|line| line.starts_with(b"begin "))
+        .ok_or(UuencodeError::MissingBegin)?;
+    let header = std::str::from_utf8(&header[6..]).map_err(|_| UuencodeError::InvalidHeader)?;
+    let (mode, name) = header
+        .trim_start()
+        .split_once(' ')
+        .ok_or(UuencodeError::InvalidHeader)?;
+    let mode = u32::from_str_radix(mode, 8).map_err(|_| UuencodeError::InvalidHeader)?;
+    if name.is_empty() {
+        return Err(UuencodeError::InvalidHeader);
+    }
+
+    let mut data = Vec::with_capacity(src.len() * 3 / 4);
+    loop {
+        let line = lines.next().ok_or(UuencodeError::MissingEnd)?;
+        if !decode_line(&mut data, line)? {
+            break;
+        }
+    }
+    match lines.next() {
+        Some(line) if core::str::from_utf8(line).is_ok_and(|s| s.trim_end() == "end") => {
+            Ok(UuFile {
+                mode,
+                name: name.to_string(),
+                data,
+            })
+        }
+        _ => Err(UuencodeError::MissingEnd),
+    }
+}
+
+#[cfg(test)]
+mod tests_uuencode {
+    use super::*;
+
+    fn test_base(data: &[u8], encoded_expect: &str) {
+        let encoded = encode(data, 0o644, "f");
+        assert_eq!(encoded, format!("begin 644 f\n{}`\nend\n", encoded_expect));
+        let file = decode(encoded.as_bytes()).unwrap();
+        assert_eq!(file.mode, 0o644);
+        assert_eq!(file.name, "f");
+        assert_eq!(file.data, data);
+    }
+
+    #[test]
+    fn test_encode_01() {
+        test_base(b"", "");
+        test_base(b"C", "!0P``\n");
+        test_base(b"Ca", "\"0V$`\n");
+        test_base(b"Cat", "#0V%T\n");
+        test_base(
+            b"http://www.wikipedia.org\r\n",
+            "::'1T<#HO+W=W=RYW:6MI<&5D:6$N;W)G#0H`\n",
+        );
+    }
+
+    #[test]
+    fn test_long_lines() {
+        let data: Vec<u8> = (0..=255).collect();
+        let encoded = encode(&data, 0o644, "bytes.bin");
+        let lines: Vec<&str> = encoded.lines().collect();
+        assert_eq!(lines.len(), 1 + 6 + 2);
+        for line in &lines[1..6] {
+            assert!(line.starts_with('M'));
+            assert_eq!(line.len(), 61);
+        }
+        assert_eq!(decode(encoded.as_bytes()).unwrap().data, data);
+    }
+
+    #[test]
+    fn test_decode_space_variant() {
+        // old encoders write zero as space and mailers strip trailing spaces
+        let file = decode(b"begin 644 f\n!0P\n \nend\n").unwrap();
+        assert_eq!(file.data, b"C");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(b""), Err(UuencodeError::MissingBegin));
+        assert_eq!(
+            decode(b"begin 9 f\n`\nend\n"),
+            Err(UuencodeError::InvalidHeader)
+        );
+        assert_eq!(
+            decode(b"begin 644\n`\nend\n"),
+            Err(UuencodeError::InvalidHeader)
+        );
+        assert_eq!(
+            decode(b"begin 644 f\n#0V%T\n"),
+            Err(UuencodeError::MissingEnd)
+        );
+        assert_eq!(decode(b"begin 644 f\n`\n"), Err(UuencodeError::MissingEnd));
+        assert_eq!(
+            decode(b"begin 644 f\n#0v%T\n`\nend\n"),
+            Err(UuencodeError::InvalidByte(b'v'))
+        );
+    }
+
+    #[test]
+    fn test_file_name_with_spaces() {
+        let encoded = encode(b"x", 0o644, "my file.txt");
+        assert_eq!(decode(encoded.as_bytes()).unwrap().name, "my file.txt");
+    }
+}
@@ -0,0 +1,659 @@
+//! yEnc 1.3 implemented, 8 bit binary encoding used on Usenet.
+//!
+//! Every byte is shifted by 42, the critical characters NUL, LF, CR and `=`
+//! are escaped as `=` followed by the byte shifted by another 64. Encoded
+//! data sits between a `=ybegin` and a `=yend` line, multipart files add a
+//! `=ypart` line giving the position of the part in the file. Sizes and
+//! CRC32 checksums from these lines are verified when decoding.
+//!
+//! # Examples
+//!
+//! yEnc encode basic usage:
+//!
+//! ```
+//! use connx::encoding::yenc;
+//! let encoded = yenc::encode(b"hello", "hello.txt", yenc::DEFAULT_LINE_LEN);
+//! assert_eq!(
+//!     encoded,
+//!     b"=ybegin line=128 size=5 name=hello.txt\r\n\x92\x8f\x96\x96\x99\r\n=yend size=5 crc32=3610a686\r\n"
+//! );
+//! ```
+//!
+//! yEnc decode basic usage:
+//!
+//! ```
+//! use connx::encoding::yenc;
+//! let encoded = yenc::encode(b"hello", "hello.txt", yenc::DEFAULT_LINE_LEN);
+//! let part = yenc::decode(&encoded).unwrap();
+//! assert_eq!(part.name, "hello.txt");
+//! assert_eq!(part.data, b"hello");
+//! ```
+
+/// Usual encoded line length
+pub const DEFAULT_LINE_LEN: usize = 128;
+
+/// Errors when yEnc decode
+#[derive(Debug, PartialEq)]
+pub enum YencError {
+    /// No `=ybegin` line was found
+    MissingHeader,
+    /// A `=ybegin`, `=ypart` or `=yend` line is malformed or inconsistent
+    InvalidHeader,
+    /// Input ended before the `=yend` line
+    MissingTrailer,
+    /// Decoded size differs from the size announced in the headers
+    SizeMismatch { expected: u64, actual: u64 },
+    /// Decoded data differs from the CRC32 announced in the trailer
+    CrcMismatch { expected: u32, actual: u32 },
+    /// Multipart parts are missing or overlap
+    IncompleteParts,
+}
+
+impl std::fmt::Display for YencError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingHeader => write!(f, "encoding/yenc: missing =ybegin line"),
+            Self::InvalidHeader => write!(f, "encoding/yenc: invalid header line"),
+            Self::MissingTrailer => write!(f, "encoding/yenc: missing =yend line"),
+            Self::SizeMismatch { expected, actual } => write!(
+                f,
+                "encoding/yenc: size mismatch, expected {} got {}",
+                expected, actual
+            ),
+            Self::CrcMismatch { expected, actual } => write!(
+                f,
+                "encoding/yenc: crc32 mismatch, expected {:08x} got {:08x}",
+                expected, actual
+            ),
+            Self::IncompleteParts => write!(f, "encoding/yenc: incomplete multipart"),
+        }
+    }
+}
+
+impl std::error::Error for YencError {}
+
+/// One decoded yEnc part, or a whole single part file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YencPart {
+    /// File name from the `=ybegin` line
+    pub name: String,
+    /// Size of the whole file from the `=ybegin` line
+    pub size: u64,
+    /// Part number, `None` for a single part file
+    pub part: Option<u32>,
+    /// Total number of parts, if announced
+    pub total: Option<u32>,
+    /// 1-based offset of the first byte of this part in the file
+    pub begin: u64,
+    /// CRC32 of the whole file, if announced in the trailer
+    pub crc32: Option<u32>,
+    /// Decoded bytes of this part
+    pub data: Vec<u8>,
+}
+
+const CRC32_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut c = i as u32;
+        let mut k = 0;
+        while k < 8 {
+            c = if c & 1 != 0 {
+                0xEDB8_8320 ^ (c >> 1)
+            } else {
+                c >> 1
+            };
+            k += 1;
+        }
+        table[i] = c;
+        i += 1;
+    }
+    table
+};
+
+/// Calculate the IEEE CRC32 checksum, as used by yEnc, zip and gzip
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::yenc;
+/// assert_eq!(yenc::crc32(b""), 0);
+/// assert_eq!(yenc::crc32(b"123456789"), 0xcbf43926);
+/// ```
+pub fn crc32(src: &[u8]) -> u32 {
+    !src.iter().fold(!0u32, |crc, &b| {
+        CRC32_TABLE[((crc ^ u32::from(b)) & 0xFF) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Append encoded lines of `src`, each terminated by CRLF
+fn encode_body(dst: &mut Vec<u8>, src: &[u8], line_len: usize) {
+    let mut col = 0;
+    for (i, &b) in src.iter().enumerate() {
+        let o = b.wrapping_add(42);
+        let last_in_line = col + 1 >= line_len || i + 1 == src.len();
+        let escape = match o {
+            0x00 | 0x0A | 0x0D | b'=' => true,
+            // whitespace at the edges of a line and a leading dot do not
+            // survive news servers
+            b'\t' | b' ' => col == 0 || last_in_line,
+            b'.' => col == 0,
+            _ => false,
+        };
+        if escape {
+            dst.push(b'=');
+            dst.push(o.wrapping_add(64));
+            col += 2;
+        } else {
+            dst.push(o);
+            col += 1;
+        }
+        if col >= line_len {
+            dst.extend_from_slice(b"\r\n");
+            col = 0;
+        }
+    }
+    if col > 0 {
+        dst.extend_from_slice(b"\r\n");
+    }
+}
+
+/// yEnc encode bytes as a single part file
+///
+/// - @param src: raw bytes
+/// - @param name: file name
+/// - @param line_len: encoded line length, usually [`DEFAULT_LINE_LEN`]
+/// - @return: yEnc encoded bytes, with header and trailer lines
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::yenc;
+/// let encoded = yenc::encode(&[0xd6, 0xe0, 0xe3, 0x13], "x", 128);
+/// assert!(encoded.windows(8).any(|w| w == b"=@=J=M=}"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `line_len` is zero.
+pub fn encode(src: &[u8], name: &str, line_len: usize) -> Vec<u8> {
+    assert!(line_len > 0, "encoding/yenc: zero line length");
+    let mut dst = Vec::with_capacity(src.len() + src.len() / 32 + 128);
+    dst.extend_from_slice(
+        format!(
+            "=ybegin line={} size={} name={}\r\n",
+            line_len,
+            src.len(),
+            name
+        )
+        .as_bytes(),
+    );
+    encode_body(&mut dst, src, line_len);
+    dst.extend_from_slice(
+        format!("=yend size={} crc32={:08x}\r\n", src.len(), crc32(src)).as_bytes(),
+    );
+    dst
+}
+
+/// yEnc encode bytes as a multipart file
+///
+/// - @param src: raw bytes
+/// - @param name: file name
+/// - @param line_len: encoded line length, usually [`DEFAULT_LINE_LEN`]
+/// - @param part_size: raw bytes per part
+/// - @return: yEnc encoded parts, in order; an empty file is a single part
+///   with `begin=1 end=0`
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::yenc;
+/// let parts = yenc::encode_parts(&[7u8; 250], "seven.bin", 128, 100);
+/// assert_eq!(parts.len(), 3);
+/// assert!(parts[2].starts_with(b"=ybegin part=3 total=3 line=128 size=250 name=seven.bin\r\n=ypart begin=201 end=250\r\n"));
+/// ```
+///
+/// # Panics
+///
+/// Panics if `line_len` or `part_size` is zero.
+pub fn encode_parts(src: &[u8], name: &str, line_len: usize, part_size: usize) -> Vec<Vec<u8>> {
+    assert!(line_len > 0, "encoding/yenc: zero line length");
+    assert!(part_size > 0, "encoding/yenc: zero part size");
+    let total = src.len().div_ceil(part_size).max(1);
+    let file_crc = crc32(src);
+    let mut parts = Vec::with_capacity(total);
+    for part in 0..total {
+        let begin = part * part_size;
+        let end = src.len().min(begin + part_size);
+        let chunk = &src[begin..end];
+
+        let mut dst = Vec::with_capacity(chunk.len() + chunk.len() / 32 + 192);
+        dst.extend_from_slice(
+            format!(
+                "=ybegin part={} total={} line={} size={} name={}\r\n=ypart begin={} end={}\r\n",
+                part + 1,
+                total,
+                line_len,
+                src.len(),
+                name,
+                begin + 1,
+                end
+            )
+            .as_bytes(),
+        );
+        encode_body(&mut dst, chunk, line_len);
+        dst.extend_from_slice(
+            format!(
+                "=yend size={} part={} pcrc32={:08x} crc32={:08x}\r\n",
+                chunk.len(),
+                part + 1,
+                crc32(chunk),
+                file_crc
+            )
+            .as_bytes(),
+        );
+        parts.push(dst);
+    }
+    parts
+}
+
+/// `key=value` fields of a keyword line, `name` takes the rest of the line
+struct Keywords {
+    line: String,
+}
+
+impl Keywords {
+    fn new(line: &[u8]) -> Self {
+        Self {
+            line: String::from_utf8_lossy(line).into_owned(),
+        }
+    }
+
+    fn name(&self) -> Option<&str> {
+        let idx = self.line.find(" name=")?;
+        Some(self.line[idx + 6..].trim_end())
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        let fields = match self.line.find(" name=") {
+            Some(idx) => &self.line[..idx],
+            None => &self.line[..],
+        };
+        fields
+            .split_whitespace()
+            .filter_map(|field| field.split_once('='))
+            .find(|&(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    fn number<T: std::str::FromStr>(&self, key: &str) -> Result<Option<T>, YencError> {
+        self.get(key)
+            .map(|v| v.parse().map_err(|_| YencError::InvalidHeader))
+            .transpose()
+    }
+
+    fn crc(&self, key: &str) -> Result<Option<u32>, YencError> {
+        self.get(key)
+            .map(|v| u32::from_str_radix(v, 16).map_err(|_| YencError::InvalidHeader))
+            .transpose()
+    }
+}
+
+fn decode_line(dst: &mut Vec<u8>, line: &[u8]) {
+    let mut escaped = false;
+    for &c in line {
+        if escaped {
+            dst.push(c.wrapping_sub(64).wrapping_sub(42));
+            escaped = false;
+        } else if c == b'=' {
+            escaped = true;
+        } else {
+            dst.push(c.wrapping_sub(42));
+        }
+    }
+}
+
+fn check_crc(expected: Option<u32>, data: &[u8]) -> Result<(), YencError> {
+    match expected {
+        Some(expected) if expected != crc32(data) => Err(YencError::CrcMismatch {
+            expected,
+            actual: crc32(data),
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Decode the first yEnc part found in the input
+///
+/// Lines before `=ybegin` are skipped, both LF and CRLF line breaks are
+/// accepted. Sizes and `pcrc32` are verified, `crc32` too for a single part
+/// file; the CRC32 of a multipart file is verified by [`assemble`].
+///
+/// - @param src: bytes containing a yEnc part
+/// - @return: decoded part if successfully decoded.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::yenc::{self, YencError};
+/// let mut encoded = yenc::encode(b"hello", "hello.txt", 128);
+/// encoded[40] ^= 1;
+/// assert!(matches!(yenc::decode(&encoded), Err(YencError::CrcMismatch { .. })));
+/// ```
+pub fn decode(src: &[u8]) -> Result<YencPart, YencError> {
+    let mut lines = src
+        .split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line));
+
+    let header = lines
+        .find(|line| line.starts_with(b"=ybegin "))
+        .map(Keywords::new)
+        .ok_or(YencError::MissingHeader)?;
+    let name = header.name().ok_or(YencError::InvalidHeader)?.to_string();
+    let size: u64 = header.number("size")?.ok_or(YencError::InvalidHeader)?;
+    let part: Option<u32> = header.number("part")?;
+    let total: Option<u32> = header.number("total")?;
+
+    let mut range = None;
+    let mut data = Vec::new();
+    let trailer = loop {
+        let line = lines.next().ok_or(YencError::MissingTrailer)?;
+        if line.starts_with(b"=ypart ") {
+            if part.is_none() || range.is_some() || !data.is_empty() {
+                return Err(YencError::InvalidHeader);
+            }
+            let keywords = Keywords::new(line);
+            let begin: u64 = keywords.number("begin")?.ok_or(YencError::InvalidHeader)?;
+            let end: u64 = keywords.number("end")?.ok_or(YencError::InvalidHeader)?;
+            // an empty file has the single empty range `begin=1 end=0`
+            let empty = size == 0 && begin == 1 && end == 0;
+            if !empty && (begin == 0 || end < begin || end > size) {
+                return Err(YencError::InvalidHeader);
+            }
+            range = Some((begin, end));
+        } else if line.starts_with(b"=yend") {
+            break Keywords::new(line);
+        } else {
+            decode_line(&mut data, line);
+        }
+    };
+
+    let (begin, end) = match (part, range) {
+        (None, _) => (1, size),
+        (Some(_), Some(range)) => range,
+        (Some(_), None) => return Err(YencError::InvalidHeader),
+    };
+    if trailer
+        .number::<u32>("part")?
+        .is_some_and(|p| Some(p) != part)
+    {
+        return Err(YencError::InvalidHeader);
+    }
+    let trailer_size: u64 = trailer.number("size")?.ok_or(YencError::InvalidHeader)?;
+    for expected in [end - (begin - 1), trailer_size] {
+        if expected != data.len() as u64 {
+            return Err(YencError::SizeMismatch {
+                expected,
+                actual: data.len() as u64,
+            });
+        }
+    }
+
+    let crc = trailer.crc("crc32")?;
+    check_crc(trailer.crc("pcrc32")?, &data)?;
+    if part.is_none() {
+        check_crc(crc, &data)?;
+    }
+    Ok(YencPart {
+        name,
+        size,
+        part,
+        total,
+        begin,
+        crc32: crc,
+        data,
+    })
+}
+
+/// Reassemble a multipart file from its decoded parts, in any order
+///
+/// - @param parts: every part of the file
+/// - @return: file content if all parts are present and the CRC32 matches.
+///
+/// # Examples
+///
+/// Basic usage:
+///
+/// ```
+/// use connx::encoding::yenc::{self, YencError};
+/// let src: Vec<u8> = (0..=255).collect();
+/// let mut parts: Vec<_> = yenc::encode_parts(&src, "bytes.bin", 128, 100)
+///     .iter()
+///     .map(|part| yenc::decode(part).unwrap())
+///     .collect();
+/// parts.reverse();
+/// assert_eq!(yenc::assemble(&parts).unwrap(), src);
+/// assert_eq!(yenc::assemble(&parts[1..]), Err(YencError::IncompleteParts));
+/// ```
+pub fn assemble(parts: &[YencPart]) -> Result<Vec<u8>, YencError> {
+    let first = parts.first().ok_or(YencError::IncompleteParts)?;
+    if parts
+        .iter()
+        .any(|p| p.size != first.size || p.name != first.name)
+    {
+        return Err(YencError::InvalidHeader);
+    }
+
+    // the announced size is untrusted, allocate what the parts hold
+    let total: u64 = parts.iter().map(|p| p.data.len() as u64).sum();
+    if total != first.size {
+        return Err(YencError::IncompleteParts);
+    }
+
+    let mut ordered: Vec<&YencPart> = parts.iter().collect();
+    ordered.sort_by_key(|p| p.begin);
+    let mut dst = Vec::with_capacity(total as usize);
+    for part in ordered {
+        if part.begin != dst.len() as u64 + 1 {
+            return Err(YencError::IncompleteParts);
+        }
+        dst.extend_from_slice(&part.data);
+    }
+
+    check_crc(parts.iter().find_map(|p| p.crc32), &dst)?;
+    Ok(dst)
+}
+
+#[cfg(test)]
+mod tests_yenc {
+    use super::*;
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"a"), 0xe8b7be43);
+        assert_eq!(crc32(b"hello"), 0x3610a686);
+        assert_eq!(
+            crc32(b"The quick brown fox jumps over the lazy dog"),
+            0x414fa339
+        );
+    }
+
+    #[test]
+    fn test_encode_escapes() {
+        // bytes which become NUL, LF, CR and = after the shift of 42
+        let encoded = encode(&[0xd6, 0xe0, 0xe3, 0x13], "x", 128);
+        let body = encoded.split(|&b| b == b'\n').nth(1).unwrap();
+        assert_eq!(body, b"=@=J=M=}\r");
+
+        // tab and space at the edges of a line, dot at the start
+        let encoded = encode(&[0xdf, 0x04, 0xdf, 0xf6], "x", 128);
+        let body = encoded.split(|&b| b == b'\n').nth(1).unwrap();
+        assert_eq!(body, b"=I.\t=`\r");
+        let encoded = encode(&[0x04, 0x04], "x", 128);
+        let body = encoded.split(|&b| b == b'\n').nth(1).unwrap();
+        assert_eq!(body, b"=n.\r");
+        let encoded = encode(&[0x41, 0x61], "x", 128);
+        let body = encoded.split(|&b| b == b'\n').nth(1).unwrap();
+        assert_eq!(body, b"k\x8b\r");
+    }
+
+    #[test]
+    fn test_roundtrip_all_bytes() {
+        let src: Vec<u8> = (0..=255).cycle().take(3000).collect();
+        for line_len in [1, 2, 3, 64, 128] {
+            let encoded = encode(&src, "all.bin", line_len);
+            for line in encoded.split(|&b| b == b'\n').skip(1) {
+                if !line.starts_with(b"=y") {
+                    assert!(line.len() <= line_len + 2);
+                }
+            }
+            let part = decode(&encoded).unwrap();
+            assert_eq!(part.part, None);
+            assert_eq!(part.size, 3000);
+            assert_eq!(part.begin, 1);
+            assert_eq!(part.crc32, Some(crc32(&src)));
+            assert_eq!(part.data, src);
+        }
+    }
+
+    #[test]
+    fn test_decode_lf_and_garbage() {
+        let src = b"Subject: x\n\n=ybegin line=128 size=2 name=a b.txt\n\x8b\x8c\n=yend size=2\n";
+        let part = decode(src).unwrap();
+        assert_eq!(part.name, "a b.txt");
+        assert_eq!(part.data, b"ab");
+        assert_eq!(part.crc32, None);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(b"hello"), Err(YencError::MissingHeader));
+        assert_eq!(decode(b"=ybegin size=1\r\n"), Err(YencError::InvalidHeader));
+        assert_eq!(
+            decode(b"=ybegin size=x name=a\r\n"),
+            Err(YencError::InvalidHeader)
+        );
+        assert_eq!(
+            decode(b"=ybegin size=1 name=a\r\n\x8b\r\n"),
+            Err(YencError::MissingTrailer)
+        );
+        assert_eq!(
+            decode(b"=ybegin size=2 name=a\r\n\x8b\r\n=yend size=1\r\n"),
+            Err(YencError::SizeMismatch {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            decode(b"=ybegin size=1 name=a\r\n\x8b\r\n=yend size=1 crc32=00000000\r\n"),
+            Err(YencError::CrcMismatch {
+                expected: 0,
+                actual: 0xe8b7be43
+            })
+        );
+        assert_eq!(
+            decode(b"=ybegin part=1 size=1 name=a\r\n\x8b\r\n=yend size=1\r\n"),
+            Err(YencError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    fn test_multipart() {
+        let src: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 256) as u8).collect();
+        let encoded = encode_parts(&src, "multi.bin", 128, 300);
+        assert_eq!(encoded.len(), 4);
+        let parts: Vec<YencPart> = encoded.iter().map(|p| decode(p).unwrap()).collect();
+        assert_eq!(parts[1].part, Some(2));
+        assert_eq!(parts[1].total, Some(4));
+        assert_eq!(parts[1].begin, 301);
+        assert_eq!(parts[3].data.len(), 100);
+        assert_eq!(assemble(&parts).unwrap(), src);
+
+        let mut shuffled = vec![
+            parts[2].clone(),
+            parts[0].clone(),
+            parts[3].clone(),
+            parts[1].clone(),
+        ];
+        assert_eq!(assemble(&shuffled).unwrap(), src);
+        shuffled.push(parts[1].clone());
+        assert_eq!(assemble(&shuffled), Err(YencError::IncompleteParts));
+
+        let mut corrupt = parts.clone();
+        corrupt[0].crc32 = Some(0);
+        assert!(matches!(
+            assemble(&corrupt),
+            Err(YencError::CrcMismatch { .. })
+        ));
+
+        // a damaged part is caught by its own pcrc32
+        let mut damaged = encoded[1].clone();
+        let idx = damaged.len() / 2;
+        damaged[idx] = damaged[idx].wrapping_add(1);
+        assert!(matches!(
+            decode(&damaged),
+            Err(YencError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_assemble_huge_size() {
+        let src = b"=ybegin part=1 total=2 line=128 size=1099511627776 name=big\r\n\
+            =ypart begin=1 end=1\r\n\x8b\r\n=yend size=1 part=1\r\n";
+        let part = decode(src).unwrap();
+        assert_eq!(part.size, 1 << 40);
+        assert_eq!(assemble(&[part]), Err(YencError::IncompleteParts));
+    }
+
+    #[test]
+    fn test_empty() {
+        let part = decode(&encode(b"", "empty", 128)).unwrap();
+        assert_eq!(part.size, 0);
+        assert_eq!(part.begin, 1);
+        assert_eq!(part.crc32, Some(0));
+        assert!(part.data.is_empty());
+
+        let encoded = encode_parts(b"", "empty", 128, 100);
+        assert_eq!(encoded.len(), 1);
+        assert!(encoded[0].starts_with(
+            b"=ybegin part=1 total=1 line=128 size=0 name=empty\r\n=ypart begin=1 end=0\r\n"
+        ));
+        let parts: Vec<YencPart> = encoded.iter().map(|p| decode(p).unwrap()).collect();
+        assert_eq!(parts[0].part, Some(1));
+        assert!(parts[0].data.is_empty());
+        assert_eq!(assemble(&parts).unwrap(), b"");
+
+        // an empty range only makes sense for an empty file
+        assert_eq!(
+            decode(b"=ybegin part=1 size=1 name=a\r\n=ypart begin=1 end=0\r\n=yend size=0\r\n"),
+            Err(YencError::InvalidHeader)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "zero line length")]
+    fn test_encode_zero_line_len() {
+        encode(b"abc", "a", 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero line length")]
+    fn test_encode_parts_zero_line_len() {
+        encode_parts(b"abc", "a", 0, 100);
+    }
+
+    #[test]
+    #[should_panic(expected = "zero part size")]
+    fn test_encode_parts_zero_part_size() {
+        encode_parts(b"abc", "a", 128, 0);
+    }
+}
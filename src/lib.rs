@@ -1,4 +1,22 @@
+//! Toolkit for coding.
+//!
+//! # Features
+//!
+//! - `std` (default): everything, implies `alloc`.
+//! - `alloc`: the `Vec` and `String` helpers of `encoding::{base16, base32,
+//!   base64}`, `Vec` buffers for `binary::Writer` and `binary::structfmt`.
+//!   Without `std`, the crate is `no_std` and only provides
+//!   `encoding::{base16, base32, base64, binary}`, whose slice APIs need no
+//!   allocator.
+//! - `serde`: `serde` modules for base16, base32 and base64 fields.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub mod encoding;
+#[cfg(feature = "std")]
 pub mod minidds;
 
 pub fn add(left: usize, right: usize) -> usize {
@@ -0,0 +1,25 @@
+//! `connx` command line tool, the `encoding` modules on files and pipes.
+
+mod cli;
+
+use std::io;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let stdin = io::stdin();
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    match cli::run(&args, &mut stdin.lock(), &mut stdout) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(cli::CliError::Io(e)) if e.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("connx: {}", e);
+            if matches!(e, cli::CliError::Usage(_)) {
+                eprint!("{}", cli::USAGE);
+                ExitCode::from(2)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}